@@ -28,6 +28,26 @@ fn setup(kt: tink::proto::KeyTemplate) -> (Box<dyn tink::Aead>, Vec<u8>) {
     (a, ct)
 }
 
+/// Build an AEAD from a keyset with several rotated keys, returning ciphertexts produced by the
+/// primary key and by a RAW key.
+fn setup_rotated(kt: tink::proto::KeyTemplate) -> (Box<dyn tink::Aead>, Vec<u8>, Vec<u8>) {
+    tink_aead::init();
+    let mut raw_kt = kt.clone();
+    raw_kt.output_prefix_type = tink::proto::OutputPrefixType::Raw as i32;
+    let mut ksm = tink::keyset::Manager::new();
+    ksm.rotate(&raw_kt).unwrap();
+    let raw_ct = tink_aead::new(&ksm.handle().unwrap())
+        .unwrap()
+        .encrypt(MSG, AAD)
+        .unwrap();
+    for _ in 0..8 {
+        ksm.rotate(&kt).unwrap();
+    }
+    let a = tink_aead::new(&ksm.handle().unwrap()).unwrap();
+    let ct = a.encrypt(MSG, AAD).unwrap();
+    (a, ct, raw_ct)
+}
+
 #[bench]
 fn bench_aes128_gcm_encrypt(b: &mut Bencher) {
     let (a, _ct) = setup(tink_aead::aes128_gcm_key_template());
@@ -123,3 +143,35 @@ fn bench_x_cha_cha20_poly1305_decrypt(b: &mut Bencher) {
     let (a, ct) = setup(tink_aead::x_cha_cha20_poly1305_key_template());
     b.iter(|| a.decrypt(&ct, AAD).unwrap());
 }
+
+#[bench]
+fn bench_rotated_keyset_decrypt(b: &mut Bencher) {
+    let (a, ct, _raw_ct) = setup_rotated(tink_aead::aes128_gcm_key_template());
+    b.iter(|| a.decrypt(&ct, AAD).unwrap());
+}
+
+#[bench]
+fn bench_rotated_keyset_decrypt_raw(b: &mut Bencher) {
+    let (a, _ct, raw_ct) = setup_rotated(tink_aead::aes128_gcm_key_template());
+    b.iter(|| a.decrypt(&raw_ct, AAD).unwrap());
+}
+
+#[bench]
+fn bench_aes128_gcm_encrypt_to_reused_buffer(b: &mut Bencher) {
+    let (a, _ct) = setup(tink_aead::aes128_gcm_key_template());
+    let mut out = Vec::with_capacity(1024);
+    b.iter(|| {
+        out.clear();
+        a.encrypt_to(MSG, AAD, &mut out).unwrap()
+    });
+}
+
+#[bench]
+fn bench_aes128_gcm_decrypt_to_reused_buffer(b: &mut Bencher) {
+    let (a, ct) = setup(tink_aead::aes128_gcm_key_template());
+    let mut out = Vec::with_capacity(1024);
+    b.iter(|| {
+        out.clear();
+        a.decrypt_to(&ct, AAD, &mut out).unwrap()
+    });
+}
@@ -16,7 +16,8 @@
 
 //! Provides an implementation of AEAD using a set of underlying implementations.
 
-use tink::{utils::wrap_err, TinkError};
+use std::sync::Arc;
+use tink::{monitoring::Logger, utils::wrap_err, ErrorKind, TinkError};
 
 /// Returns a [`tink::Aead`] primitive from the given keyset handle.
 pub fn new(h: &tink::keyset::Handle) -> Result<Box<dyn tink::Aead>, TinkError> {
@@ -37,87 +38,268 @@ pub fn new_with_key_manager(
     Ok(Box::new(ret))
 }
 
+/// Return a [`tink::Aead`] primitive from the given keyset handle, which counts encryptions
+/// per key in `usage` and enforces its [`EncryptionLimits`](crate::EncryptionLimits).
+pub fn new_with_usage(
+    h: &tink::keyset::Handle,
+    usage: crate::KeyUsage,
+) -> Result<Box<dyn tink::Aead>, TinkError> {
+    let ps = h
+        .primitives()
+        .map_err(|e| wrap_err("aead::factory: cannot obtain primitive set", e))?;
+
+    let mut ret = WrappedAead::new(ps)?;
+    ret.usage = Some(usage);
+    Ok(Box::new(ret))
+}
+
+/// Return a [`tink::Aead`] primitive from the given keyset handle, using the key managers in
+/// the given registry configuration.
+pub fn new_with_config(
+    h: &tink::keyset::Handle,
+    config: &tink::registry::Registry,
+) -> Result<Box<dyn tink::Aead>, TinkError> {
+    let ps = h
+        .primitives_with_config(config)
+        .map_err(|e| wrap_err("aead::factory: cannot obtain primitive set", e))?;
+
+    let ret = WrappedAead::new(ps)?;
+    Ok(Box::new(ret))
+}
+
+/// [`PrimitiveWrapper`](tink::registry::PrimitiveWrapper) that combines the primitives for the keys
+/// in a keyset into a single [`tink::Aead`] primitive. Registered by [`init`](crate::init).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AeadWrapper;
+
+impl tink::registry::PrimitiveWrapper for AeadWrapper {
+    type Primitive = dyn tink::Aead;
+
+    fn wrap(&self, ps: tink::primitiveset::PrimitiveSet) -> Result<Box<dyn tink::Aead>, TinkError> {
+        Ok(Box::new(WrappedAead::new(ps)?))
+    }
+}
+
 /// `WrappedAead` is an AEAD implementation that uses the underlying primitive set for encryption
 /// and decryption.
 #[derive(Clone)]
 struct WrappedAead {
     ps: tink::primitiveset::PrimitiveSet,
+    encrypt_logger: Arc<dyn Logger>,
+    decrypt_logger: Arc<dyn Logger>,
+    usage: Option<crate::KeyUsage>,
 }
 
 impl WrappedAead {
     fn new(ps: tink::primitiveset::PrimitiveSet) -> Result<WrappedAead, TinkError> {
         let entry = match &ps.primary {
-            None => return Err("aead::factory: no primary primitive".into()),
+            None => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "aead::factory: no primary primitive",
+                ))
+            }
             Some(p) => p,
         };
         match entry.primitive {
             tink::Primitive::Aead(_) => {}
-            _ => return Err("aead::factory: not an AEAD primitive".into()),
+            _ => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "aead::factory: not an AEAD primitive",
+                ))
+            }
         };
-        for (_, primitives) in ps.entries.iter() {
+        for (_, primitives) in ps.entries().iter() {
             for p in primitives {
                 match p.primitive {
                     tink::Primitive::Aead(_) => {}
-                    _ => return Err("aead::factory: not an AEAD primitive".into()),
+                    _ => {
+                        return Err(TinkError::new_with_kind(
+                            ErrorKind::InvalidKeyset,
+                            "aead::factory: not an AEAD primitive",
+                        ))
+                    }
                 };
             }
         }
-        Ok(WrappedAead { ps })
+        let encrypt_logger = tink::monitoring::new_logger(&ps, "aead", "encrypt")?;
+        let decrypt_logger = tink::monitoring::new_logger(&ps, "aead", "decrypt")?;
+        Ok(WrappedAead {
+            ps,
+            encrypt_logger,
+            decrypt_logger,
+            usage: None,
+        })
     }
 }
 
-impl tink::Aead for WrappedAead {
-    fn encrypt(&self, pt: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+impl WrappedAead {
+    /// Return the primary entry and its AEAD, after counting an encryption against its key.
+    fn encrypting_primary(
+        &self,
+    ) -> Result<(&tink::primitiveset::Entry, &dyn tink::Aead), TinkError> {
         let primary = self
             .ps
             .primary
             .as_ref()
-            .ok_or_else(|| TinkError::new("no primary"))?;
-
-        match &primary.primitive {
-            tink::Primitive::Aead(p) => {
-                let ct = p.encrypt(pt, aad)?;
-
-                let mut ret = Vec::with_capacity(primary.prefix.len() + ct.len());
-                ret.extend_from_slice(&primary.prefix);
-                ret.extend_from_slice(&ct);
-                Ok(ret)
+            .ok_or_else(|| TinkError::new_with_kind(ErrorKind::InvalidKeyset, "no primary"))?;
+        let p = match &primary.primitive {
+            tink::Primitive::Aead(p) => p,
+            _ => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "aead::encrypt: not an AEAD primitive",
+                ))
+            }
+        };
+        if let Some(usage) = &self.usage {
+            if let Err(e) = usage.record_encryption(primary.key_id) {
+                self.encrypt_logger.log_failure();
+                return Err(e);
             }
-            _ => Err("aead::encrypt: not an AEAD primitive".into()),
         }
+        Ok((primary, p.as_ref()))
+    }
+
+    /// Return the AEAD of an entry, or `None` if the entry is not an AEAD (in which case it is
+    /// skipped when decrypting).
+    fn entry_aead(entry: &tink::primitiveset::Entry) -> Option<&dyn tink::Aead> {
+        match &entry.primitive {
+            tink::Primitive::Aead(p) => Some(p.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Record a failed decryption, returning the corresponding error.
+    fn decrypt_failure(&self, tried: bool) -> TinkError {
+        self.decrypt_logger.log_failure();
+        TinkError::new_with_kind(
+            if tried {
+                ErrorKind::Authentication
+            } else {
+                ErrorKind::KeyNotFound
+            },
+            "aead::decrypt: decryption failed",
+        )
+    }
+}
+
+impl tink::Aead for WrappedAead {
+    fn encrypt(&self, pt: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+        let mut ret = Vec::new();
+        self.encrypt_to(pt, aad, &mut ret)?;
+        Ok(ret)
     }
 
     fn decrypt(&self, ct: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+        let mut ret = Vec::new();
+        self.decrypt_to(ct, aad, &mut ret)?;
+        Ok(ret)
+    }
+
+    fn encrypt_to(&self, pt: &[u8], aad: &[u8], out: &mut Vec<u8>) -> Result<(), TinkError> {
+        let (primary, p) = self.encrypting_primary()?;
+        let start = out.len();
+        out.extend_from_slice(&primary.prefix);
+        if let Err(e) = p.encrypt_to(pt, aad, out) {
+            out.truncate(start);
+            self.encrypt_logger.log_failure();
+            return Err(e);
+        }
+        self.encrypt_logger.log(primary.key_id, pt.len());
+        Ok(())
+    }
+
+    fn decrypt_to(&self, ct: &[u8], aad: &[u8], out: &mut Vec<u8>) -> Result<(), TinkError> {
+        let mut tried = false;
         // try non-raw keys
         let prefix_size = tink::cryptofmt::NON_RAW_PREFIX_SIZE;
         if ct.len() > prefix_size {
             let prefix = &ct[..prefix_size];
             let ct_no_prefix = &ct[prefix_size..];
-            let entries = self.ps.entries_for_prefix(&prefix);
-            for entry in &entries {
-                if let tink::Primitive::Aead(p) = &entry.primitive {
-                    if let Ok(pt) = p.decrypt(ct_no_prefix, aad) {
-                        return Ok(pt);
-                    }
-                } else {
-                    return Err("aead::decrypt: not an AEAD primitive".into());
+            for entry in self.ps.entries_for_prefix(prefix) {
+                let p = match Self::entry_aead(entry) {
+                    Some(p) => p,
+                    None => continue,
+                };
+                tried = true;
+                if p.decrypt_to(ct_no_prefix, aad, out).is_ok() {
+                    self.decrypt_logger.log(entry.key_id, ct_no_prefix.len());
+                    return Ok(());
                 }
             }
         }
 
         // try raw keys
-        let entries = self.ps.raw_entries();
-        for entry in &entries {
-            if let tink::Primitive::Aead(p) = &entry.primitive {
-                if let Ok(pt) = p.decrypt(ct, aad) {
-                    return Ok(pt);
+        for entry in self.ps.raw_entries() {
+            let p = match Self::entry_aead(entry) {
+                Some(p) => p,
+                None => continue,
+            };
+            tried = true;
+            if p.decrypt_to(ct, aad, out).is_ok() {
+                self.decrypt_logger.log(entry.key_id, ct.len());
+                return Ok(());
+            }
+        }
+
+        // nothing worked
+        Err(self.decrypt_failure(tried))
+    }
+
+    fn encrypt_in_place(&self, buffer: &mut Vec<u8>, aad: &[u8]) -> Result<(), TinkError> {
+        let (primary, p) = self.encrypting_primary()?;
+        let pt_len = buffer.len();
+        if let Err(e) = p.encrypt_in_place_with_prefix(&primary.prefix, buffer, aad) {
+            self.encrypt_logger.log_failure();
+            return Err(e);
+        }
+        self.encrypt_logger.log(primary.key_id, pt_len);
+        Ok(())
+    }
+
+    fn decrypt_in_place(&self, buffer: &mut Vec<u8>, aad: &[u8]) -> Result<(), TinkError> {
+        let mut tried = false;
+        // try non-raw keys, with the prefix removed from the buffer
+        const PREFIX_SIZE: usize = tink::cryptofmt::NON_RAW_PREFIX_SIZE;
+        if buffer.len() > PREFIX_SIZE {
+            let entries = self.ps.entries_for_prefix(&buffer[..PREFIX_SIZE]);
+            if !entries.is_empty() {
+                let mut prefix = [0u8; PREFIX_SIZE];
+                prefix.copy_from_slice(&buffer[..PREFIX_SIZE]);
+                buffer.drain(..PREFIX_SIZE);
+                let ct_len = buffer.len();
+                for entry in entries {
+                    let p = match Self::entry_aead(entry) {
+                        Some(p) => p,
+                        None => continue,
+                    };
+                    tried = true;
+                    if p.decrypt_in_place(buffer, aad).is_ok() {
+                        self.decrypt_logger.log(entry.key_id, ct_len);
+                        return Ok(());
+                    }
                 }
-            } else {
-                return Err("aead::decrypt: not an AEAD primitive".into());
+                buffer.splice(0..0, prefix.iter().cloned());
+            }
+        }
+
+        // try raw keys
+        let ct_len = buffer.len();
+        for entry in self.ps.raw_entries() {
+            let p = match Self::entry_aead(entry) {
+                Some(p) => p,
+                None => continue,
+            };
+            tried = true;
+            if p.decrypt_in_place(buffer, aad).is_ok() {
+                self.decrypt_logger.log(entry.key_id, ct_len);
+                return Ok(());
             }
         }
 
         // nothing worked
-        Err("aead::decrypt: decryption failed".into())
+        Err(self.decrypt_failure(tried))
     }
 }
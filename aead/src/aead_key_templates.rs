@@ -62,6 +62,22 @@ pub fn aes256_gcm_siv_no_prefix_key_template() -> KeyTemplate {
     create_aes_gcm_siv_key_template(32, OutputPrefixType::Raw)
 }
 
+/// Return a [`KeyTemplate`] that generates a key-committing AES-GCM key with the following
+/// parameters:
+///   - Key size: 16 bytes
+///   - Output prefix type: TINK
+pub fn aes128_gcm_committing_key_template() -> KeyTemplate {
+    create_aes_gcm_committing_key_template(16, OutputPrefixType::Tink)
+}
+
+/// Return a [`KeyTemplate`] that generates a key-committing AES-GCM key with the following
+/// parameters:
+///   - Key size: 32 bytes
+///   - Output prefix type: TINK
+pub fn aes256_gcm_committing_key_template() -> KeyTemplate {
+    create_aes_gcm_committing_key_template(32, OutputPrefixType::Tink)
+}
+
 /// Return a [`KeyTemplate`] that generates an AES-CTR-HMAC-AEAD key with the following parameters:
 ///  - AES key size: 16 bytes
 ///  - AES CTR IV size: 16 bytes
@@ -102,6 +118,20 @@ pub fn x_cha_cha20_poly1305_key_template() -> KeyTemplate {
     }
 }
 
+/// Return a [`KeyTemplate`] that generates an XAES-256-GCM key with the following parameters:
+///   - Nonce size: 24 bytes
+///   - Output prefix type: TINK
+pub fn x_aes256_gcm_192_bit_nonce_key_template() -> KeyTemplate {
+    create_x_aes_gcm_key_template(OutputPrefixType::Tink)
+}
+
+/// Return a [`KeyTemplate`] that generates an XAES-256-GCM key with the following parameters:
+///   - Nonce size: 24 bytes
+///   - Output prefix type: RAW
+pub fn x_aes256_gcm_192_bit_nonce_no_prefix_key_template() -> KeyTemplate {
+    create_x_aes_gcm_key_template(OutputPrefixType::Raw)
+}
+
 /// Return a [`KeyTemplate`] that generates a KmsEnvelopeAead key for a given KEK in remote KMS
 pub fn kms_envelope_aead_key_template(uri: &str, dek_t: KeyTemplate) -> KeyTemplate {
     let f = tink::proto::KmsEnvelopeAeadKeyFormat {
@@ -117,6 +147,24 @@ pub fn kms_envelope_aead_key_template(uri: &str, dek_t: KeyTemplate) -> KeyTempl
     }
 }
 
+/// Return a [`KeyTemplate`] that generates a key for use with caller-supplied nonces (see
+/// [`new_with_nonce`](crate::new_with_nonce)), wrapping a key generated from `aead_t`, which
+/// should be a template for AES-GCM, AES-GCM-SIV, ChaCha20-Poly1305, XChaCha20-Poly1305 or
+/// XAES-256-GCM keys.  The output prefix type is taken from `aead_t`.
+pub fn aead_with_nonce_key_template(aead_t: &KeyTemplate) -> KeyTemplate {
+    let f = tink::proto::AeadWithNonceKeyFormat {
+        aead_type_url: aead_t.type_url.clone(),
+        aead_key_format: aead_t.value.clone(),
+    };
+    let mut serialized_format = Vec::new();
+    f.encode(&mut serialized_format).unwrap(); // safe: proto-encode
+    KeyTemplate {
+        value: serialized_format,
+        type_url: crate::AEAD_WITH_NONCE_TYPE_URL.to_string(),
+        output_prefix_type: aead_t.output_prefix_type,
+    }
+}
+
 /// Return an AES-GCM key template with the given key size in bytes.
 fn create_aes_gcm_key_template(key_size: u32, output_prefix_type: OutputPrefixType) -> KeyTemplate {
     let format = tink::proto::AesGcmKeyFormat {
@@ -150,6 +198,39 @@ fn create_aes_gcm_siv_key_template(
     }
 }
 
+/// Return a key-committing AES-GCM key template with the given key size in bytes.
+fn create_aes_gcm_committing_key_template(
+    key_size: u32,
+    output_prefix_type: OutputPrefixType,
+) -> KeyTemplate {
+    let format = tink::proto::AesGcmCommittingKeyFormat {
+        version: crate::AES_GCM_COMMITTING_KEY_VERSION,
+        key_size,
+    };
+    let mut serialized_format = Vec::new();
+    format.encode(&mut serialized_format).unwrap(); // safe: proto-encode
+    KeyTemplate {
+        type_url: crate::AES_GCM_COMMITTING_TYPE_URL.to_string(),
+        value: serialized_format,
+        output_prefix_type: output_prefix_type as i32,
+    }
+}
+
+/// Return an XAES-256-GCM key template with a 12-byte salt, for a 24-byte nonce.
+fn create_x_aes_gcm_key_template(output_prefix_type: OutputPrefixType) -> KeyTemplate {
+    let format = tink::proto::XAesGcmKeyFormat {
+        version: crate::X_AES_GCM_KEY_VERSION,
+        params: Some(tink::proto::XAesGcmParams { salt_size: 12 }),
+    };
+    let mut serialized_format = Vec::new();
+    format.encode(&mut serialized_format).unwrap(); // safe: proto-encode
+    KeyTemplate {
+        type_url: crate::X_AES_GCM_TYPE_URL.to_string(),
+        value: serialized_format,
+        output_prefix_type: output_prefix_type as i32,
+    }
+}
+
 /// Return an AES-CTR-HMAC key template with the given parameters.
 fn create_aes_ctr_hmac_aead_key_template(
     aes_key_size: u32,
@@ -0,0 +1,222 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! AEAD with caller-supplied nonces, for interoperability with externally defined formats.
+
+use std::sync::Arc;
+use tink::{monitoring::Logger, utils::wrap_err, ErrorKind, TinkError};
+
+/// `AeadWithNonce` is a low-level interface for authenticated encryption with associated data
+/// where the caller supplies the nonce, for implementing wire formats (such as record layers or
+/// legacy file formats) in which the nonce is derived externally.
+///
+/// ## Warning
+///
+/// Unlike [`tink::Aead`], which generates a fresh random nonce for every encryption, the
+/// security of this interface depends entirely on the caller: encrypting two different messages
+/// with the same key and nonce breaks confidentiality, and for AES-GCM and ChaCha20-Poly1305 also
+/// allows forgeries.  Use [`tink::Aead`] unless the format requires control of the nonce.
+///
+/// Implementations are provided by [`subtle::AesGcm`](crate::subtle::AesGcm),
+/// [`subtle::AesGcmSiv`](crate::subtle::AesGcmSiv),
+/// [`subtle::ChaCha20Poly1305`](crate::subtle::ChaCha20Poly1305),
+/// [`subtle::XChaCha20Poly1305`](crate::subtle::XChaCha20Poly1305) and
+/// [`subtle::XAesGcm`](crate::subtle::XAesGcm), and for keysets by
+/// [`new_with_nonce`].
+pub trait AeadWithNonce: Send + Sync {
+    /// Return the size in bytes of the nonces accepted by this primitive.
+    fn nonce_size(&self) -> usize;
+
+    /// Encrypt `plaintext` with `additional_data` as additional authenticated data, using the
+    /// given `nonce`, which must never be reused with the same key.  The result is the ciphertext
+    /// followed by the authentication tag; the nonce is not included.
+    fn encrypt_with_nonce(
+        &self,
+        nonce: &[u8],
+        plaintext: &[u8],
+        additional_data: &[u8],
+    ) -> Result<Vec<u8>, TinkError>;
+
+    /// Decrypt `ciphertext` (as produced by
+    /// [`encrypt_with_nonce`](AeadWithNonce::encrypt_with_nonce)) with `additional_data` as
+    /// additional authenticated data, using the `nonce` that was used for encryption.
+    fn decrypt_with_nonce(
+        &self,
+        nonce: &[u8],
+        ciphertext: &[u8],
+        additional_data: &[u8],
+    ) -> Result<Vec<u8>, TinkError>;
+}
+
+/// Return an [`AeadWithNonce`] primitive for the keys in the given keyset handle, which should
+/// hold keys generated from templates returned by [`aead_with_nonce_key_template`] (which wrap
+/// AES-GCM, AES-GCM-SIV, ChaCha20-Poly1305, XChaCha20-Poly1305 or XAES-256-GCM keys).  Keysets
+/// for the corresponding [`tink::Aead`] primitives are rejected.
+///
+/// As for [`new`](crate::new), ciphertexts produced with a primary key whose output prefix type
+/// is not RAW start with the key identifier, so keys with output prefix type RAW should be used
+/// when the ciphertext format is defined externally.
+pub fn new_with_nonce(h: &tink::keyset::Handle) -> Result<Box<dyn AeadWithNonce>, TinkError> {
+    let ps = h
+        .primitives()
+        .map_err(|e| wrap_err("aead::factory: cannot obtain primitive set", e))?;
+    Ok(Box::new(WrappedAeadWithNonce::new(ps)?))
+}
+
+/// [`PrimitiveWrapper`](tink::registry::PrimitiveWrapper) that combines the
+/// [`AeadWithNonce`] primitives for the keys in a keyset into a single [`AeadWithNonce`]
+/// primitive.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AeadWithNonceWrapper;
+
+impl tink::registry::PrimitiveWrapper for AeadWithNonceWrapper {
+    type Primitive = dyn AeadWithNonce;
+
+    fn wrap(
+        &self,
+        ps: tink::primitiveset::PrimitiveSet,
+    ) -> Result<Box<dyn AeadWithNonce>, TinkError> {
+        Ok(Box::new(WrappedAeadWithNonce::new(ps)?))
+    }
+}
+
+/// An [`AeadWithNonce`] implementation that uses the underlying primitive set for encryption
+/// and decryption.
+struct WrappedAeadWithNonce {
+    ps: tink::primitiveset::PrimitiveSet,
+    encrypt_logger: Arc<dyn Logger>,
+    decrypt_logger: Arc<dyn Logger>,
+}
+
+/// Return the [`AeadWithNonce`] held in a primitive set entry.
+fn entry_primitive(entry: &tink::primitiveset::Entry) -> Result<&dyn AeadWithNonce, TinkError> {
+    entry
+        .primitive
+        .downcast_ref::<Arc<dyn AeadWithNonce>>()
+        .map(|p| p.as_ref())
+        .ok_or_else(|| {
+            TinkError::new_with_kind(
+                ErrorKind::InvalidKeyset,
+                "aead::factory: not an AeadWithNonce primitive",
+            )
+        })
+}
+
+impl WrappedAeadWithNonce {
+    fn new(ps: tink::primitiveset::PrimitiveSet) -> Result<WrappedAeadWithNonce, TinkError> {
+        match &ps.primary {
+            None => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "aead::factory: no primary primitive",
+                ))
+            }
+            Some(p) => entry_primitive(p)?,
+        };
+        for entry in ps.entries().values().flatten() {
+            entry_primitive(entry)?;
+        }
+        let encrypt_logger = tink::monitoring::new_logger(&ps, "aead_with_nonce", "encrypt")?;
+        let decrypt_logger = tink::monitoring::new_logger(&ps, "aead_with_nonce", "decrypt")?;
+        Ok(WrappedAeadWithNonce {
+            ps,
+            encrypt_logger,
+            decrypt_logger,
+        })
+    }
+
+    fn primary(&self) -> Result<&tink::primitiveset::Entry, TinkError> {
+        self.ps
+            .primary
+            .as_deref()
+            .ok_or_else(|| TinkError::new_with_kind(ErrorKind::InvalidKeyset, "no primary"))
+    }
+}
+
+impl AeadWithNonce for WrappedAeadWithNonce {
+    /// Return the nonce size of the primary key.
+    fn nonce_size(&self) -> usize {
+        self.primary()
+            .and_then(entry_primitive)
+            .expect("primary AeadWithNonce") // safe: checked in new()
+            .nonce_size()
+    }
+
+    fn encrypt_with_nonce(
+        &self,
+        nonce: &[u8],
+        pt: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, TinkError> {
+        let primary = self.primary()?;
+        let ct = match entry_primitive(primary)?.encrypt_with_nonce(nonce, pt, aad) {
+            Ok(ct) => ct,
+            Err(e) => {
+                self.encrypt_logger.log_failure();
+                return Err(e);
+            }
+        };
+        self.encrypt_logger.log(primary.key_id, pt.len());
+
+        let mut ret = Vec::with_capacity(primary.prefix.len() + ct.len());
+        ret.extend_from_slice(&primary.prefix);
+        ret.extend_from_slice(&ct);
+        Ok(ret)
+    }
+
+    fn decrypt_with_nonce(
+        &self,
+        nonce: &[u8],
+        ct: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, TinkError> {
+        let mut tried = false;
+        // try non-raw keys
+        let prefix_size = tink::cryptofmt::NON_RAW_PREFIX_SIZE;
+        if ct.len() > prefix_size {
+            let prefix = &ct[..prefix_size];
+            let ct_no_prefix = &ct[prefix_size..];
+            for entry in self.ps.entries_for_prefix(prefix) {
+                tried = true;
+                if let Ok(pt) = entry_primitive(entry)?.decrypt_with_nonce(nonce, ct_no_prefix, aad)
+                {
+                    self.decrypt_logger.log(entry.key_id, ct_no_prefix.len());
+                    return Ok(pt);
+                }
+            }
+        }
+
+        // try raw keys
+        for entry in self.ps.raw_entries() {
+            tried = true;
+            if let Ok(pt) = entry_primitive(entry)?.decrypt_with_nonce(nonce, ct, aad) {
+                self.decrypt_logger.log(entry.key_id, ct.len());
+                return Ok(pt);
+            }
+        }
+
+        // nothing worked
+        self.decrypt_logger.log_failure();
+        Err(TinkError::new_with_kind(
+            if tried {
+                ErrorKind::Authentication
+            } else {
+                ErrorKind::KeyNotFound
+            },
+            "aead::factory: decryption failed",
+        ))
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Key manager for AEAD keys used with caller-supplied nonces.
+
+use crate::{
+    aes_gcm_key_manager::new_aes_gcm, aes_gcm_siv_key_manager::new_aes_gcm_siv,
+    chacha20poly1305_key_manager::new_chacha20poly1305, x_aes_gcm_key_manager::new_x_aes_gcm,
+    xchacha20poly1305_key_manager::new_xchacha20poly1305, AeadWithNonce, AesGcmKeyManager,
+    AesGcmSivKeyManager, ChaCha20Poly1305KeyManager, XAesGcmKeyManager,
+    XChaCha20Poly1305KeyManager, AES_GCM_SIV_TYPE_URL, AES_GCM_TYPE_URL,
+    CHA_CHA20_POLY1305_TYPE_URL, X_AES_GCM_TYPE_URL, X_CHA_CHA20_POLY1305_TYPE_URL,
+};
+use prost::Message;
+use std::sync::Arc;
+use tink::{registry::KeyManager, utils::wrap_err, ErrorKind, TinkError};
+
+/// Maximal version of keys for AEAD with caller-supplied nonces.
+pub const AEAD_WITH_NONCE_KEY_VERSION: u32 = 0;
+/// Type URL of keys for AEAD with caller-supplied nonces.
+pub const AEAD_WITH_NONCE_TYPE_URL: &str = "type.googleapis.com/tink_rust.AeadWithNonceKey";
+
+/// `AeadWithNonceKeyManager` is an implementation of the `tink::registry::KeyManager` trait.
+/// It generates new [`AeadWithNonceKey`](tink::proto::AeadWithNonceKey) keys, which wrap an
+/// AES-GCM, AES-GCM-SIV, ChaCha20-Poly1305, XChaCha20-Poly1305 or XAES-256-GCM key, and produces
+/// [`AeadWithNonce`] primitives (as a [`tink::Primitive::Custom`] holding an
+/// `Arc<dyn AeadWithNonce>`).
+///
+/// Keys of the wrapped types themselves are rejected, so that a keyset for the corresponding
+/// [`tink::Aead`] (which relies on random nonces) cannot be used with caller-supplied nonces.
+#[derive(Default)]
+pub(crate) struct AeadWithNonceKeyManager {}
+
+impl tink::registry::KeyManager for AeadWithNonceKeyManager {
+    /// Create an [`AeadWithNonce`] for the given serialized [`tink::proto::AeadWithNonceKey`].
+    fn primitive(&self, serialized_key: &[u8]) -> Result<tink::Primitive, TinkError> {
+        let key = decode_key(serialized_key)?;
+        let aead_key = &key.aead_key;
+        let p: Arc<dyn AeadWithNonce> = match key.aead_type_url.as_str() {
+            AES_GCM_TYPE_URL => Arc::new(new_aes_gcm(aead_key)?),
+            AES_GCM_SIV_TYPE_URL => Arc::new(new_aes_gcm_siv(aead_key)?),
+            CHA_CHA20_POLY1305_TYPE_URL => Arc::new(new_chacha20poly1305(aead_key)?),
+            X_CHA_CHA20_POLY1305_TYPE_URL => Arc::new(new_xchacha20poly1305(aead_key)?),
+            X_AES_GCM_TYPE_URL => Arc::new(new_x_aes_gcm(aead_key)?),
+            type_url => return Err(unsupported(type_url)),
+        };
+        Ok(tink::Primitive::custom(p))
+    }
+
+    /// Create a new key according to specification the given serialized
+    /// [`tink::proto::AeadWithNonceKeyFormat`].
+    fn new_key(&self, serialized_key_format: &[u8]) -> Result<Vec<u8>, TinkError> {
+        if serialized_key_format.is_empty() {
+            return Err("AeadWithNonceKeyManager: invalid key format".into());
+        }
+        let key_format = tink::proto::AeadWithNonceKeyFormat::decode(serialized_key_format)
+            .map_err(|e| wrap_err("AeadWithNonceKeyManager: invalid key format", e))?;
+        let aead_key = aead_key_manager(&key_format.aead_type_url)?
+            .new_key(&key_format.aead_key_format)
+            .map_err(|e| wrap_err("AeadWithNonceKeyManager: invalid key format", e))?;
+        let key = tink::proto::AeadWithNonceKey {
+            version: AEAD_WITH_NONCE_KEY_VERSION,
+            aead_type_url: key_format.aead_type_url.clone(),
+            aead_key,
+        };
+        let mut sk = Vec::new();
+        key.encode(&mut sk)
+            .map_err(|e| wrap_err("AeadWithNonceKeyManager: failed to encode new key", e))?;
+        Ok(sk)
+    }
+
+    fn type_url(&self) -> &'static str {
+        AEAD_WITH_NONCE_TYPE_URL
+    }
+
+    fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
+        tink::proto::key_data::KeyMaterialType::Symmetric
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "AeadWithNonce"
+    }
+
+    fn fips_compatible(&self) -> bool {
+        true
+    }
+
+    /// Only keys that wrap a key of a FIPS 140-approved type (i.e. AES-GCM) are compatible.
+    fn fips_compatible_key(&self, serialized_key: &[u8]) -> bool {
+        decode_key(serialized_key)
+            .and_then(|key| {
+                Ok(aead_key_manager(&key.aead_type_url)?.fips_compatible_key(&key.aead_key))
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// Decode and validate a serialized [`tink::proto::AeadWithNonceKey`].
+fn decode_key(serialized_key: &[u8]) -> Result<tink::proto::AeadWithNonceKey, TinkError> {
+    if serialized_key.is_empty() {
+        return Err("AeadWithNonceKeyManager: invalid key".into());
+    }
+    let key = tink::proto::AeadWithNonceKey::decode(serialized_key)
+        .map_err(|e| wrap_err("AeadWithNonceKeyManager: invalid key", e))?;
+    tink::keyset::validate_key_version(key.version, AEAD_WITH_NONCE_KEY_VERSION)
+        .map_err(|e| wrap_err("AeadWithNonceKeyManager: invalid key", e))?;
+    Ok(key)
+}
+
+/// Return the key manager for the wrapped key type with the given type URL.
+fn aead_key_manager(type_url: &str) -> Result<Arc<dyn KeyManager>, TinkError> {
+    Ok(match type_url {
+        AES_GCM_TYPE_URL => Arc::new(AesGcmKeyManager::default()),
+        AES_GCM_SIV_TYPE_URL => Arc::new(AesGcmSivKeyManager::default()),
+        CHA_CHA20_POLY1305_TYPE_URL => Arc::new(ChaCha20Poly1305KeyManager::default()),
+        X_CHA_CHA20_POLY1305_TYPE_URL => Arc::new(XChaCha20Poly1305KeyManager::default()),
+        X_AES_GCM_TYPE_URL => Arc::new(XAesGcmKeyManager::default()),
+        type_url => return Err(unsupported(type_url)),
+    })
+}
+
+fn unsupported(type_url: &str) -> TinkError {
+    TinkError::new_with_kind(
+        ErrorKind::Unsupported,
+        &format!("AeadWithNonceKeyManager: unsupported key type {}", type_url),
+    )
+}
@@ -104,6 +104,29 @@ impl tink::registry::KeyManager for AesCtrHmacAeadKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
+
+    fn version(&self) -> u32 {
+        AES_CTR_HMAC_AEAD_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "Aead"
+    }
+
+    fn fips_compatible(&self) -> bool {
+        true
+    }
+
+    fn fips_compatible_key(&self, serialized_key: &[u8]) -> bool {
+        tink::proto::AesCtrHmacAeadKey::decode(serialized_key)
+            .ok()
+            .and_then(|key| {
+                key.hmac_key
+                    .as_ref()
+                    .and_then(|hmac_key| hmac_key.params.as_ref().map(|params| params.hash))
+            })
+            .map_or(false, tink::fips::is_approved_hash)
+    }
 }
 
 /// Validate and extract the AES parts of the given [`tink::proto::AesCtrHmacAeadKey`].
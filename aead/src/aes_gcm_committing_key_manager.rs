@@ -0,0 +1,104 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Key manager for key-committing AES-GCM keys.
+
+use crate::subtle;
+use prost::Message;
+use tink::{utils::wrap_err, TinkError};
+
+/// Maximal version of key-committing AES-GCM keys.
+pub const AES_GCM_COMMITTING_KEY_VERSION: u32 = 0;
+/// Type URL of key-committing AES-GCM keys.  This key type is specific to this project (so the
+/// type URL is in the `tink_rust` namespace), and is not supported by other Tink implementations.
+pub const AES_GCM_COMMITTING_TYPE_URL: &str = "type.googleapis.com/tink_rust.AesGcmCommittingKey";
+
+/// `AesGcmCommittingKeyManager` is an implementation of the `tink::registry::KeyManager` trait.
+/// It generates new [`AesGcmCommittingKey`](tink::proto::AesGcmCommittingKey) keys and produces
+/// new instances of [`subtle::AesGcmCommitting`].
+#[derive(Default)]
+pub(crate) struct AesGcmCommittingKeyManager {}
+
+impl tink::registry::KeyManager for AesGcmCommittingKeyManager {
+    /// Create a [`subtle::AesGcmCommitting`] for the given serialized
+    /// [`tink::proto::AesGcmCommittingKey`].
+    fn primitive(&self, serialized_key: &[u8]) -> Result<tink::Primitive, TinkError> {
+        if serialized_key.is_empty() {
+            return Err("AesGcmCommittingKeyManager: invalid key".into());
+        }
+        let key = tink::proto::AesGcmCommittingKey::decode(serialized_key)
+            .map_err(|e| wrap_err("AesGcmCommittingKeyManager: invalid key", e))?;
+        validate_key(&key)?;
+        match subtle::AesGcmCommitting::new(&key.key_value) {
+            Ok(p) => Ok(tink::Primitive::Aead(Box::new(p))),
+            Err(e) => Err(wrap_err(
+                "AesGcmCommittingKeyManager: cannot create new primitive",
+                e,
+            )),
+        }
+    }
+
+    /// Create a new key according to specification the given serialized
+    /// [`tink::proto::AesGcmCommittingKeyFormat`].
+    fn new_key(&self, serialized_key_format: &[u8]) -> Result<Vec<u8>, TinkError> {
+        if serialized_key_format.is_empty() {
+            return Err("AesGcmCommittingKeyManager: invalid key format".into());
+        }
+        let key_format = tink::proto::AesGcmCommittingKeyFormat::decode(serialized_key_format)
+            .map_err(|e| wrap_err("AesGcmCommittingKeyManager: invalid key format", e))?;
+        validate_key_format(&key_format)
+            .map_err(|e| wrap_err("AesGcmCommittingKeyManager: invalid key format", e))?;
+        let key_value = tink::subtle::random::get_random_bytes(key_format.key_size as usize);
+        let key = tink::proto::AesGcmCommittingKey {
+            version: AES_GCM_COMMITTING_KEY_VERSION,
+            key_value,
+        };
+        let mut sk = Vec::new();
+        key.encode(&mut sk)
+            .map_err(|e| wrap_err("AesGcmCommittingKeyManager: failed to encode new key", e))?;
+        Ok(sk)
+    }
+
+    fn type_url(&self) -> &'static str {
+        AES_GCM_COMMITTING_TYPE_URL
+    }
+    fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
+        tink::proto::key_data::KeyMaterialType::Symmetric
+    }
+
+    fn version(&self) -> u32 {
+        AES_GCM_COMMITTING_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "Aead"
+    }
+}
+
+/// Validate the given [`tink::proto::AesGcmCommittingKey`].
+fn validate_key(key: &tink::proto::AesGcmCommittingKey) -> Result<(), TinkError> {
+    tink::keyset::validate_key_version(key.version, AES_GCM_COMMITTING_KEY_VERSION)
+        .map_err(|e| wrap_err("AesGcmCommittingKeyManager", e))?;
+    let key_size = key.key_value.len();
+    crate::subtle::validate_aes_key_size(key_size)
+        .map_err(|e| wrap_err("AesGcmCommittingKeyManager", e))
+}
+
+/// Validate the given [`tink::proto::AesGcmCommittingKeyFormat`].
+fn validate_key_format(format: &tink::proto::AesGcmCommittingKeyFormat) -> Result<(), TinkError> {
+    crate::subtle::validate_aes_key_size(format.key_size as usize)
+        .map_err(|e| wrap_err("AesGcmCommittingKeyManager", e))
+}
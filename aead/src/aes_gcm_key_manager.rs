@@ -34,16 +34,9 @@ pub(crate) struct AesGcmKeyManager {}
 impl tink::registry::KeyManager for AesGcmKeyManager {
     /// Create a [`subtle::AesGcm`] for the given serialized [`tink::proto::AesGcmKey`].
     fn primitive(&self, serialized_key: &[u8]) -> Result<tink::Primitive, TinkError> {
-        if serialized_key.is_empty() {
-            return Err("AesGcmKeyManager: invalid key".into());
-        }
-        let key = tink::proto::AesGcmKey::decode(serialized_key)
-            .map_err(|e| wrap_err("AesGcmKeyManager: invalid key", e))?;
-        validate_key(&key)?;
-        match subtle::AesGcm::new(&key.key_value) {
-            Ok(p) => Ok(tink::Primitive::Aead(Box::new(p))),
-            Err(e) => Err(wrap_err("AesGcmKeyManager: cannot create new primitive", e)),
-        }
+        Ok(tink::Primitive::Aead(Box::new(new_aes_gcm(
+            serialized_key,
+        )?)))
     }
 
     /// Create a new key according to specification the given serialized
@@ -73,6 +66,30 @@ impl tink::registry::KeyManager for AesGcmKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
+
+    fn version(&self) -> u32 {
+        AES_GCM_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "Aead"
+    }
+
+    fn fips_compatible(&self) -> bool {
+        true
+    }
+}
+
+/// Create a [`subtle::AesGcm`] for the given serialized [`tink::proto::AesGcmKey`].
+pub(crate) fn new_aes_gcm(serialized_key: &[u8]) -> Result<subtle::AesGcm, TinkError> {
+    if serialized_key.is_empty() {
+        return Err("AesGcmKeyManager: invalid key".into());
+    }
+    let key = tink::proto::AesGcmKey::decode(serialized_key)
+        .map_err(|e| wrap_err("AesGcmKeyManager: invalid key", e))?;
+    validate_key(&key)?;
+    subtle::AesGcm::new(&key.key_value)
+        .map_err(|e| wrap_err("AesGcmKeyManager: cannot create new primitive", e))
 }
 
 /// Validate the given [`tink::proto::AesGcmKey`].
@@ -34,19 +34,9 @@ pub(crate) struct AesGcmSivKeyManager {}
 impl tink::registry::KeyManager for AesGcmSivKeyManager {
     /// Create a [`subtle::AesGcmSiv`] for the given serialized [`tink::proto::AesGcmSivKey`].
     fn primitive(&self, serialized_key: &[u8]) -> Result<tink::Primitive, TinkError> {
-        if serialized_key.is_empty() {
-            return Err("AesGcmSivKeyManager: invalid key".into());
-        }
-        let key = tink::proto::AesGcmSivKey::decode(serialized_key)
-            .map_err(|e| wrap_err("AesGcmSivKeyManager: invalid key", e))?;
-        validate_key(&key)?;
-        match subtle::AesGcmSiv::new(&key.key_value) {
-            Ok(p) => Ok(tink::Primitive::Aead(Box::new(p))),
-            Err(e) => Err(wrap_err(
-                "AesGcmSivKeyManager: cannot create new primitive",
-                e,
-            )),
-        }
+        Ok(tink::Primitive::Aead(Box::new(new_aes_gcm_siv(
+            serialized_key,
+        )?)))
     }
 
     /// Create a new key according to specification the given serialized
@@ -76,6 +66,26 @@ impl tink::registry::KeyManager for AesGcmSivKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
+
+    fn version(&self) -> u32 {
+        AES_GCM_SIV_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "Aead"
+    }
+}
+
+/// Create a [`subtle::AesGcmSiv`] for the given serialized [`tink::proto::AesGcmSivKey`].
+pub(crate) fn new_aes_gcm_siv(serialized_key: &[u8]) -> Result<subtle::AesGcmSiv, TinkError> {
+    if serialized_key.is_empty() {
+        return Err("AesGcmSivKeyManager: invalid key".into());
+    }
+    let key = tink::proto::AesGcmSivKey::decode(serialized_key)
+        .map_err(|e| wrap_err("AesGcmSivKeyManager: invalid key", e))?;
+    validate_key(&key)?;
+    subtle::AesGcmSiv::new(&key.key_value)
+        .map_err(|e| wrap_err("AesGcmSivKeyManager: cannot create new primitive", e))
 }
 
 /// Validate the given [`tink::proto::AesGcmSivKey`].
@@ -36,19 +36,9 @@ impl tink::registry::KeyManager for ChaCha20Poly1305KeyManager {
     /// Create a [`subtle::ChaCha20Poly1305`] for the given serialized
     /// [`tink::proto::ChaCha20Poly1305Key`].
     fn primitive(&self, serialized_key: &[u8]) -> Result<tink::Primitive, TinkError> {
-        if serialized_key.is_empty() {
-            return Err("ChaCha20Poly1305KeyManager: invalid key".into());
-        }
-        let key = tink::proto::ChaCha20Poly1305Key::decode(serialized_key)
-            .map_err(|e| wrap_err("ChaCha20Poly1305KeyManager: invalid key", e))?;
-        validate_key(&key)?;
-        match subtle::ChaCha20Poly1305::new(&key.key_value) {
-            Ok(p) => Ok(tink::Primitive::Aead(Box::new(p))),
-            Err(e) => Err(wrap_err(
-                "ChaCha20Poly1305KeyManager: cannot create new primitive",
-                e,
-            )),
-        }
+        Ok(tink::Primitive::Aead(Box::new(new_chacha20poly1305(
+            serialized_key,
+        )?)))
     }
 
     /// Create a new key, ignoring the specification in the given serialized key format
@@ -68,6 +58,14 @@ impl tink::registry::KeyManager for ChaCha20Poly1305KeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
+
+    fn version(&self) -> u32 {
+        CHA_CHA20_POLY1305_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "Aead"
+    }
 }
 
 fn new_cha_cha20_poly1305_key() -> tink::proto::ChaCha20Poly1305Key {
@@ -78,6 +76,21 @@ fn new_cha_cha20_poly1305_key() -> tink::proto::ChaCha20Poly1305Key {
     }
 }
 
+/// Create a [`subtle::ChaCha20Poly1305`] for the given serialized
+/// [`tink::proto::ChaCha20Poly1305Key`].
+pub(crate) fn new_chacha20poly1305(
+    serialized_key: &[u8],
+) -> Result<subtle::ChaCha20Poly1305, TinkError> {
+    if serialized_key.is_empty() {
+        return Err("ChaCha20Poly1305KeyManager: invalid key".into());
+    }
+    let key = tink::proto::ChaCha20Poly1305Key::decode(serialized_key)
+        .map_err(|e| wrap_err("ChaCha20Poly1305KeyManager: invalid key", e))?;
+    validate_key(&key)?;
+    subtle::ChaCha20Poly1305::new(&key.key_value)
+        .map_err(|e| wrap_err("ChaCha20Poly1305KeyManager: cannot create new primitive", e))
+}
+
 /// Validate the given [`tink::proto::ChaCha20Poly1305Key`].
 fn validate_key(key: &tink::proto::ChaCha20Poly1305Key) -> Result<(), TinkError> {
     tink::keyset::validate_key_version(key.version, CHA_CHA20_POLY1305_KEY_VERSION)
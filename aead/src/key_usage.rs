@@ -0,0 +1,157 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Per-key accounting of encryption operations.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+use tink::{ErrorKind, KeyId, TinkError};
+
+/// Maximum number of encryptions that should be performed with a single AES-GCM key using random
+/// 96-bit nonces, to keep the probability of a nonce collision below 2^-32 (NIST SP 800-38D).
+pub const AES_GCM_MAX_ENCRYPTIONS: u64 = 1 << 32;
+
+/// Limits on the number of encryptions performed with each key.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EncryptionLimits {
+    /// Number of encryptions after which the key should be rotated; reaching this limit
+    /// triggers the [`KeyUsage`] callback but encryption continues.
+    pub soft_limit: Option<u64>,
+    /// Number of encryptions after which encryption with the key fails with
+    /// [`ErrorKind::LimitExceeded`].
+    pub hard_limit: Option<u64>,
+}
+
+/// Callback invoked when a key reaches the soft limit, with the key ID and its encryption count.
+pub type SoftLimitCallback = Arc<dyn Fn(KeyId, u64) + Send + Sync>;
+
+/// Error message for usage counts lock.
+const UERR: &str = "key usage lock poisoned";
+
+/// Per-key encryption counters, checked against [`EncryptionLimits`].
+///
+/// A `KeyUsage` is passed to [`new_with_usage`](crate::new_with_usage), and the returned AEAD
+/// counts every encryption against the key ID of the primary key (before performing it, so
+/// failed encryptions are also counted).  Clones of a `KeyUsage` share the same counters, so a
+/// copy can be kept to query the counts.  The counts are held in memory only; they can be
+/// persisted by the caller with [`counts`](KeyUsage::counts) and restored with
+/// [`set_count`](KeyUsage::set_count).
+#[derive(Clone)]
+pub struct KeyUsage {
+    limits: EncryptionLimits,
+    state: Arc<Mutex<UsageState>>,
+    soft_limit_callback: Option<SoftLimitCallback>,
+}
+
+/// Shared state of a [`KeyUsage`].
+#[derive(Default)]
+struct UsageState {
+    counts: HashMap<KeyId, u64>,
+    /// Keys for which the soft limit callback has already been invoked.
+    soft_limit_notified: HashSet<KeyId>,
+}
+
+impl KeyUsage {
+    /// Create a new set of counters with the given limits.
+    pub fn new(limits: EncryptionLimits) -> Self {
+        Self {
+            limits,
+            state: Arc::new(Mutex::new(UsageState::default())),
+            soft_limit_callback: None,
+        }
+    }
+
+    /// Set a callback that is invoked (once per key) when a key reaches the soft limit.
+    pub fn with_soft_limit_callback(mut self, callback: SoftLimitCallback) -> Self {
+        self.soft_limit_callback = Some(callback);
+        self
+    }
+
+    /// Return the configured limits.
+    pub fn limits(&self) -> EncryptionLimits {
+        self.limits
+    }
+
+    /// Return the number of encryptions counted for the key with ID `key_id`.
+    pub fn count(&self, key_id: KeyId) -> u64 {
+        *self
+            .state
+            .lock()
+            .expect(UERR) // safe: lock
+            .counts
+            .get(&key_id)
+            .unwrap_or(&0)
+    }
+
+    /// Return the encryption counts for all keys that have been used.
+    pub fn counts(&self) -> HashMap<KeyId, u64> {
+        self.state.lock().expect(UERR).counts.clone() // safe: lock
+    }
+
+    /// Set the encryption count for the key with ID `key_id`, for example to restore a
+    /// previously persisted value.  If the restored count is at or above the soft limit, the soft
+    /// limit callback is invoked on the next encryption with the key.
+    pub fn set_count(&self, key_id: KeyId, count: u64) {
+        self.state.lock().expect(UERR).counts.insert(key_id, count); // safe: lock
+    }
+
+    /// Indicate whether the key with ID `key_id` has reached the soft limit, and so should be
+    /// replaced as the primary key.
+    pub fn soft_limit_reached(&self, key_id: KeyId) -> bool {
+        reached(self.count(key_id), self.limits.soft_limit)
+    }
+
+    /// Indicate whether the key with ID `key_id` has reached the hard limit, and so can no longer
+    /// be used for encryption.
+    pub fn hard_limit_reached(&self, key_id: KeyId) -> bool {
+        reached(self.count(key_id), self.limits.hard_limit)
+    }
+
+    /// Count an encryption with the key with ID `key_id`, failing if that would exceed the hard
+    /// limit.
+    pub(crate) fn record_encryption(&self, key_id: KeyId) -> Result<(), TinkError> {
+        let (count, notify) = {
+            let mut state = self.state.lock().expect(UERR); // safe: lock
+            let count = state.counts.entry(key_id).or_insert(0);
+            if reached(*count, self.limits.hard_limit) {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::LimitExceeded,
+                    &format!(
+                        "aead: key {} has reached its limit of {} encryptions",
+                        key_id, *count
+                    ),
+                ));
+            }
+            *count += 1;
+            let count = *count;
+            let notify =
+                reached(count, self.limits.soft_limit) && state.soft_limit_notified.insert(key_id);
+            (count, notify)
+        };
+        if notify {
+            if let Some(callback) = &self.soft_limit_callback {
+                callback(key_id, count);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn reached(count: u64, limit: Option<u64>) -> bool {
+    limit.map_or(false, |limit| count >= limit)
+}
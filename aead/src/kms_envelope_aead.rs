@@ -17,7 +17,8 @@
 //! Provide an implementation of AEAD using a KMS.
 
 use std::convert::TryInto;
-use tink::{utils::wrap_err, TinkError};
+use tink::{utils::wrap_err, ErrorKind, TinkError};
+use zeroize::Zeroizing;
 
 const LEN_DEK: usize = 4;
 
@@ -50,7 +51,7 @@ impl KmsEnvelopeAead {
 impl tink::Aead for KmsEnvelopeAead {
     fn encrypt(&self, pt: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
         // Create a new key for each encryption operation.
-        let dek = tink::registry::new_key(&self.dek_template)?;
+        let dek = Zeroizing::new(tink::registry::new_key(&self.dek_template)?);
         let encrypted_dek = self.remote.encrypt(&dek, &[])?;
 
         let primitive = match tink::registry::primitive(&self.dek_template.type_url, &dek)? {
@@ -64,7 +65,10 @@ impl tink::Aead for KmsEnvelopeAead {
     fn decrypt(&self, ct: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
         // Verify we have enough bytes for the length of the encrypted DEK.
         if ct.len() <= LEN_DEK {
-            return Err("KmsEnvelopeAead: invalid ciphertext".into());
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "KmsEnvelopeAead: invalid ciphertext",
+            ));
         }
 
         // Extract length of encrypted DEK and advance past that length.
@@ -73,7 +77,10 @@ impl tink::Aead for KmsEnvelopeAead {
 
         // Verify we have enough bytes for the encrypted DEK.
         if ed == 0 || ct.len() < ed {
-            return Err("KmsEnvelopeAead: invalid ciphertext".into());
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "KmsEnvelopeAead: invalid ciphertext",
+            ));
         }
 
         // Extract the encrypted DEK and the payload.
@@ -81,7 +88,7 @@ impl tink::Aead for KmsEnvelopeAead {
         let payload = &ct[ed..];
 
         // Decrypt the DEK.
-        let dek = self.remote.decrypt(encrypted_dek, &[])?;
+        let dek = Zeroizing::new(self.remote.decrypt(encrypted_dek, &[])?);
 
         // Get an AEAD primitive corresponding to the DEK.
         let p = tink::registry::primitive(&self.dek_template.type_url, &dek)
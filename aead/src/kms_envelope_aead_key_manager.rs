@@ -85,6 +85,14 @@ impl tink::registry::KeyManager for KmsEnvelopeAeadKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Remote
     }
+
+    fn version(&self) -> u32 {
+        KMS_ENVELOPE_AEAD_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "Aead"
+    }
 }
 
 /// Validate the given [`tink::proto::KmsEnvelopeAeadKey`].
@@ -28,18 +28,30 @@ mod aead_factory;
 pub use aead_factory::*;
 mod aead_key_templates;
 pub use aead_key_templates::*;
+mod aead_with_nonce;
+pub use aead_with_nonce::*;
+mod aead_with_nonce_key_manager;
+pub use aead_with_nonce_key_manager::*;
 mod aes_ctr_hmac_aead_key_manager;
 pub use aes_ctr_hmac_aead_key_manager::*;
+mod aes_gcm_committing_key_manager;
+pub use aes_gcm_committing_key_manager::*;
 mod aes_gcm_key_manager;
 pub use aes_gcm_key_manager::*;
 mod aes_gcm_siv_key_manager;
 pub use aes_gcm_siv_key_manager::*;
 mod chacha20poly1305_key_manager;
 pub use chacha20poly1305_key_manager::*;
+mod key_usage;
+pub use key_usage::*;
 mod kms_envelope_aead;
 pub use kms_envelope_aead::*;
 mod kms_envelope_aead_key_manager;
 pub use kms_envelope_aead_key_manager::*;
+mod password_aead;
+pub use password_aead::*;
+mod x_aes_gcm_key_manager;
+pub use x_aes_gcm_key_manager::*;
 mod xchacha20poly1305_key_manager;
 pub use xchacha20poly1305_key_manager::*;
 
@@ -55,14 +67,24 @@ static INIT: Once = Once::new();
 /// tink.
 pub fn init() {
     INIT.call_once(|| {
+        tink::registry::register_primitive_wrapper(AeadWrapper).expect("tink_aead::init() failed"); // safe: init
+        tink::registry::register_primitive_wrapper(AeadWithNonceWrapper)
+            .expect("tink_aead::init() failed"); // safe: init
+
+        register_key_manager(std::sync::Arc::new(AeadWithNonceKeyManager::default()))
+            .expect("tink_aead::init() failed"); // safe: init
         register_key_manager(std::sync::Arc::new(AesCtrHmacAeadKeyManager::default()))
             .expect("tink_aead::init() failed"); // safe: init
         register_key_manager(std::sync::Arc::new(AesGcmKeyManager::default()))
             .expect("tink_aead::init() failed"); // safe: init
+        register_key_manager(std::sync::Arc::new(AesGcmCommittingKeyManager::default()))
+            .expect("tink_aead::init() failed"); // safe: init
         register_key_manager(std::sync::Arc::new(AesGcmSivKeyManager::default()))
             .expect("tink_aead::init() failed"); // safe: init
         register_key_manager(std::sync::Arc::new(ChaCha20Poly1305KeyManager::default()))
             .expect("tink_aead::init() failed"); // safe: init
+        register_key_manager(std::sync::Arc::new(XAesGcmKeyManager::default()))
+            .expect("tink_aead::init() failed"); // safe: init
         register_key_manager(std::sync::Arc::new(XChaCha20Poly1305KeyManager::default()))
             .expect("tink_aead::init() failed"); // safe: init
         register_key_manager(std::sync::Arc::new(KmsEnvelopeAeadKeyManager::default()))
@@ -72,6 +94,14 @@ pub fn init() {
         tink::registry::register_template_generator("AES256_GCM", aes256_gcm_key_template);
         tink::registry::register_template_generator("AES128_GCM_SIV", aes128_gcm_siv_key_template);
         tink::registry::register_template_generator("AES256_GCM_SIV", aes256_gcm_siv_key_template);
+        tink::registry::register_template_generator(
+            "AES128_GCM_COMMITTING",
+            aes128_gcm_committing_key_template,
+        );
+        tink::registry::register_template_generator(
+            "AES256_GCM_COMMITTING",
+            aes256_gcm_committing_key_template,
+        );
         tink::registry::register_template_generator(
             "AES128_CTR_HMAC_SHA256",
             aes128_ctr_hmac_sha256_key_template,
@@ -84,6 +114,14 @@ pub fn init() {
             "CHACHA20_POLY1305",
             cha_cha20_poly1305_key_template,
         );
+        tink::registry::register_template_generator(
+            "XAES_256_GCM_192_BIT_NONCE",
+            x_aes256_gcm_192_bit_nonce_key_template,
+        );
+        tink::registry::register_template_generator(
+            "XAES_256_GCM_192_BIT_NONCE_NO_PREFIX",
+            x_aes256_gcm_192_bit_nonce_no_prefix_key_template,
+        );
         tink::registry::register_template_generator(
             "XCHACHA20_POLY1305",
             x_cha_cha20_poly1305_key_template,
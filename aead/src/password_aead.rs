@@ -0,0 +1,269 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Provide an implementation of AEAD using a key derived from a password.
+
+use std::convert::TryInto;
+use tink::{utils::wrap_err, ErrorKind, TinkError};
+use zeroize::{Zeroize, Zeroizing};
+
+const VERSION: u8 = 1;
+const KDF_ARGON2ID: u8 = 1;
+const KDF_SCRYPT: u8 = 2;
+const SALT_SIZE: usize = 16;
+const KEY_SIZE: usize = 32;
+/// Version byte, KDF identifier, three 32-bit KDF parameters and salt.
+const HEADER_SIZE: usize = 2 + 3 * 4 + SALT_SIZE;
+/// Upper bound on the memory used for key derivation, to prevent a ciphertext with crafted
+/// parameters from exhausting resources during decryption.
+const MAX_KDF_MEMORY: u64 = 1 << 30;
+const MAX_KDF_ITERATIONS: u32 = 64;
+const MAX_KDF_PARALLELISM: u32 = 64;
+
+/// Key derivation function (and parameters) used to derive an encryption key from a password.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PasswordKdf {
+    /// Argon2id, as specified in RFC 9106.
+    Argon2id {
+        /// Memory size in KiB.
+        memory_kib: u32,
+        /// Number of passes over the memory.
+        iterations: u32,
+        /// Degree of parallelism (number of lanes).
+        parallelism: u32,
+    },
+    /// scrypt, as specified in RFC 7914.
+    Scrypt {
+        /// Base-2 logarithm of the CPU/memory cost parameter N.
+        log_n: u8,
+        /// Block size.
+        r: u32,
+        /// Parallelization parameter.
+        p: u32,
+    },
+}
+
+impl Default for PasswordKdf {
+    /// Return the second recommended Argon2id option from RFC 9106 (64 MiB, 3 passes, 4 lanes).
+    fn default() -> Self {
+        PasswordKdf::Argon2id {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 4,
+        }
+    }
+}
+
+impl PasswordKdf {
+    /// Return the recommended scrypt parameters (N = 2^17, r = 8, p = 1).
+    pub fn scrypt() -> Self {
+        PasswordKdf::Scrypt {
+            log_n: 17,
+            r: 8,
+            p: 1,
+        }
+    }
+
+    /// Check that the parameters are valid and within resource limits.
+    fn validate(&self) -> Result<(), TinkError> {
+        match *self {
+            PasswordKdf::Argon2id {
+                memory_kib,
+                iterations,
+                parallelism,
+            } => {
+                if parallelism == 0 || parallelism > MAX_KDF_PARALLELISM {
+                    return Err("PasswordAead: invalid Argon2id parallelism".into());
+                }
+                if iterations == 0 || iterations > MAX_KDF_ITERATIONS {
+                    return Err("PasswordAead: invalid Argon2id iterations".into());
+                }
+                if memory_kib < 8 * parallelism || u64::from(memory_kib) * 1024 > MAX_KDF_MEMORY {
+                    return Err("PasswordAead: invalid Argon2id memory size".into());
+                }
+            }
+            PasswordKdf::Scrypt { log_n, r, p } => {
+                if log_n == 0 || log_n >= 32 || r == 0 || p == 0 || p > MAX_KDF_PARALLELISM {
+                    return Err("PasswordAead: invalid scrypt parameters".into());
+                }
+                if 128 * u64::from(r) * (1u64 << log_n) > MAX_KDF_MEMORY {
+                    return Err("PasswordAead: invalid scrypt parameters".into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Derive an AES-256 key from `password` and `salt`.
+    fn derive_key(&self, password: &[u8], salt: &[u8]) -> Result<Zeroizing<Vec<u8>>, TinkError> {
+        match *self {
+            PasswordKdf::Argon2id {
+                memory_kib,
+                iterations,
+                parallelism,
+            } => {
+                let config = argon2::Config {
+                    variant: argon2::Variant::Argon2id,
+                    version: argon2::Version::Version13,
+                    mem_cost: memory_kib,
+                    time_cost: iterations,
+                    lanes: parallelism,
+                    thread_mode: argon2::ThreadMode::Sequential,
+                    secret: &[],
+                    ad: &[],
+                    hash_length: KEY_SIZE as u32,
+                };
+                argon2::hash_raw(password, salt, &config)
+                    .map(Zeroizing::new)
+                    .map_err(|e| wrap_err("PasswordAead: key derivation failed", e))
+            }
+            PasswordKdf::Scrypt { log_n, r, p } => {
+                let params = scrypt::ScryptParams::new(log_n, r, p)
+                    .map_err(|e| wrap_err("PasswordAead: invalid scrypt parameters", e))?;
+                let mut key = Zeroizing::new(vec![0; KEY_SIZE]);
+                scrypt::scrypt(password, salt, &params, &mut key)
+                    .map_err(|e| wrap_err("PasswordAead: key derivation failed", e))?;
+                Ok(key)
+            }
+        }
+    }
+
+    /// Encode the KDF identifier and parameters.
+    fn encode(&self, out: &mut Vec<u8>) {
+        let (id, params) = match *self {
+            PasswordKdf::Argon2id {
+                memory_kib,
+                iterations,
+                parallelism,
+            } => (KDF_ARGON2ID, [memory_kib, iterations, parallelism]),
+            PasswordKdf::Scrypt { log_n, r, p } => (KDF_SCRYPT, [u32::from(log_n), r, p]),
+        };
+        out.push(id);
+        for param in &params {
+            out.extend_from_slice(&param.to_be_bytes());
+        }
+    }
+
+    /// Decode the KDF identifier and parameters produced by [`encode`](PasswordKdf::encode).
+    fn decode(id: u8, params: &[u8]) -> Result<Self, TinkError> {
+        let param = |i: usize| u32::from_be_bytes(params[4 * i..4 * (i + 1)].try_into().unwrap()); // safe: length checked by caller
+        let kdf = match id {
+            KDF_ARGON2ID => PasswordKdf::Argon2id {
+                memory_kib: param(0),
+                iterations: param(1),
+                parallelism: param(2),
+            },
+            KDF_SCRYPT => PasswordKdf::Scrypt {
+                log_n: param(0)
+                    .try_into()
+                    .map_err(|_| TinkError::new("PasswordAead: invalid scrypt parameters"))?,
+                r: param(1),
+                p: param(2),
+            },
+            _ => return Err("PasswordAead: unknown key derivation function".into()),
+        };
+        kdf.validate()?;
+        Ok(kdf)
+    }
+}
+
+/// `PasswordAead` is an AEAD whose key is derived from a password, suitable for use as the master
+/// key when reading and writing keysets with [`tink::keyset::Handle::read`] and
+/// [`tink::keyset::Handle::write`] where no KMS is available.
+///
+/// Each encryption derives a fresh AES-256-GCM key from the password and a random salt.  The
+/// ciphertext format is:
+///
+/// `version (1 byte) || kdf (1 byte) || kdf parameters (3 x 4 bytes, big-endian) || salt (16 bytes) || AES-GCM ciphertext`
+///
+/// The header is authenticated as part of the associated data, and the KDF parameters stored in
+/// it are used for decryption, so ciphertexts remain readable if the default parameters change.
+#[derive(Clone)]
+pub struct PasswordAead {
+    password: Vec<u8>,
+    kdf: PasswordKdf,
+}
+
+impl PasswordAead {
+    /// Return a new `PasswordAead` for `password`, using the default key derivation function.
+    pub fn new(password: &[u8]) -> Result<PasswordAead, TinkError> {
+        Self::new_with_kdf(password, PasswordKdf::default())
+    }
+
+    /// Return a new `PasswordAead` for `password`, using the given key derivation function to
+    /// derive keys for encryption.  Decryption uses the parameters held in the ciphertext.
+    pub fn new_with_kdf(password: &[u8], kdf: PasswordKdf) -> Result<PasswordAead, TinkError> {
+        if password.is_empty() {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::InvalidArgument,
+                "PasswordAead: empty password",
+            ));
+        }
+        kdf.validate()
+            .map_err(|e| e.with_kind(ErrorKind::InvalidArgument))?;
+        Ok(PasswordAead {
+            password: password.to_vec(),
+            kdf,
+        })
+    }
+}
+
+impl Drop for PasswordAead {
+    fn drop(&mut self) {
+        self.password.zeroize();
+    }
+}
+
+impl tink::Aead for PasswordAead {
+    fn encrypt(&self, pt: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+        let salt = tink::subtle::random::get_random_bytes(SALT_SIZE);
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+        header.push(VERSION);
+        self.kdf.encode(&mut header);
+        header.extend_from_slice(&salt);
+
+        let key = self.kdf.derive_key(&self.password, &salt)?;
+        let cipher = crate::subtle::AesGcm::new(&key)?;
+        let ct = cipher.encrypt(pt, &[&header, aad].concat())?;
+        header.extend_from_slice(&ct);
+        Ok(header)
+    }
+
+    fn decrypt(&self, ct: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+        if ct.len() < HEADER_SIZE {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "PasswordAead: ciphertext too short",
+            ));
+        }
+        let (header, payload) = ct.split_at(HEADER_SIZE);
+        if header[0] != VERSION {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "PasswordAead: unknown ciphertext version",
+            ));
+        }
+        let kdf = PasswordKdf::decode(header[1], &header[2..HEADER_SIZE - SALT_SIZE])
+            .map_err(|e| e.with_kind(ErrorKind::Authentication))?;
+        let salt = &header[HEADER_SIZE - SALT_SIZE..];
+
+        let key = kdf.derive_key(&self.password, salt)?;
+        let cipher = crate::subtle::AesGcm::new(&key)?;
+        cipher
+            .decrypt(payload, &[header, aad].concat())
+            .map_err(|e| wrap_err("PasswordAead: decryption failed", e))
+    }
+}
@@ -16,6 +16,12 @@
 
 //! Utilities for AEAD functionality.
 
+use ::aead::{
+    generic_array::{typenum::Unsigned, GenericArray},
+    AeadInPlace,
+};
+use tink::{ErrorKind, TinkError};
+
 /// Check if the given key size is a valid AES key size.
 pub fn validate_aes_key_size(size_in_bytes: usize) -> Result<(), tink::TinkError> {
     match size_in_bytes {
@@ -23,3 +29,155 @@ pub fn validate_aes_key_size(size_in_bytes: usize) -> Result<(), tink::TinkError
         _ => Err(format!("invalid AES key size; want 16 or 32, got {}", size_in_bytes).into()),
     }
 }
+
+/// Nonce type for an AEAD cipher from the RustCrypto `aead` crate.
+pub(crate) type Nonce<A> = GenericArray<u8, <A as AeadInPlace>::NonceSize>;
+
+/// Encrypt `pt` with `cipher` and `nonce`, appending `nonce || ciphertext || tag` to `out`.
+pub(crate) fn encrypt_to<A: AeadInPlace>(
+    cipher: &A,
+    nonce: &Nonce<A>,
+    pt: &[u8],
+    aad: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<(), ::aead::Error> {
+    let orig_len = out.len();
+    out.reserve(nonce.len() + pt.len() + A::TagSize::USIZE);
+    out.extend_from_slice(nonce);
+    let start = out.len();
+    out.extend_from_slice(pt);
+    match cipher.encrypt_in_place_detached(nonce, aad, &mut out[start..]) {
+        Ok(tag) => {
+            out.extend_from_slice(&tag);
+            Ok(())
+        }
+        Err(e) => {
+            out.truncate(orig_len);
+            Err(e)
+        }
+    }
+}
+
+/// Encrypt the contents of `buffer` with `cipher` and `nonce`, replacing them with
+/// `prefix || nonce || ciphertext || tag`.  The space for the whole output is reserved up front,
+/// and the ciphertext is moved only once to make room for the header.
+pub(crate) fn encrypt_in_place<A: AeadInPlace>(
+    cipher: &A,
+    nonce: &Nonce<A>,
+    prefix: &[u8],
+    buffer: &mut Vec<u8>,
+    aad: &[u8],
+) -> Result<(), ::aead::Error> {
+    let pt_len = buffer.len();
+    let header_len = prefix.len() + nonce.len();
+    buffer.reserve(header_len + A::TagSize::USIZE);
+    let tag = cipher.encrypt_in_place_detached(nonce, aad, buffer)?;
+    buffer.resize(header_len + pt_len, 0);
+    buffer.copy_within(..pt_len, header_len);
+    buffer[..prefix.len()].copy_from_slice(prefix);
+    buffer[prefix.len()..header_len].copy_from_slice(nonce);
+    buffer.extend_from_slice(&tag);
+    Ok(())
+}
+
+/// Split `nonce || ciphertext || tag` into its parts, returning `None` if it is too short.
+fn split_ciphertext<A: AeadInPlace>(ct: &[u8]) -> Option<(&[u8], &[u8], &[u8])> {
+    let nonce_size = A::NonceSize::USIZE;
+    let tag_size = A::TagSize::USIZE;
+    if ct.len() < nonce_size + tag_size {
+        return None;
+    }
+    let (nonce, rest) = ct.split_at(nonce_size);
+    let (payload, tag) = rest.split_at(rest.len() - tag_size);
+    Some((nonce, payload, tag))
+}
+
+/// Decrypt `nonce || ciphertext || tag` with `cipher`, appending the plaintext to `out`.  On
+/// failure `out` is left unchanged.
+pub(crate) fn decrypt_to<A: AeadInPlace>(
+    cipher: &A,
+    ct: &[u8],
+    aad: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<(), ::aead::Error> {
+    let (nonce, payload, tag) = split_ciphertext::<A>(ct).ok_or(::aead::Error)?;
+    let start = out.len();
+    out.extend_from_slice(payload);
+    let result = cipher.decrypt_in_place_detached(
+        GenericArray::from_slice(nonce),
+        aad,
+        &mut out[start..],
+        GenericArray::from_slice(tag),
+    );
+    if result.is_err() {
+        out.truncate(start);
+    }
+    result
+}
+
+/// Decrypt the `nonce || ciphertext || tag` held in `buffer` with `cipher`, replacing it with
+/// the plaintext.  On failure `buffer` is left unchanged.
+pub(crate) fn decrypt_in_place<A: AeadInPlace>(
+    cipher: &A,
+    buffer: &mut Vec<u8>,
+    aad: &[u8],
+) -> Result<(), ::aead::Error> {
+    let (nonce, _, tag) = split_ciphertext::<A>(buffer).ok_or(::aead::Error)?;
+    let nonce = Nonce::<A>::clone_from_slice(nonce);
+    let tag = GenericArray::<u8, A::TagSize>::clone_from_slice(tag);
+    let nonce_size = nonce.len();
+    let tag_start = buffer.len() - tag.len();
+    // The underlying implementations verify the tag before exposing any plaintext, and leave
+    // the ciphertext intact if verification fails.
+    cipher.decrypt_in_place_detached(&nonce, aad, &mut buffer[nonce_size..tag_start], &tag)?;
+    buffer.truncate(tag_start);
+    buffer.drain(..nonce_size);
+    Ok(())
+}
+
+/// Convert a caller-supplied nonce to the nonce type of `A`, checking its size.
+pub(crate) fn nonce_from_slice<A: AeadInPlace>(nonce: &[u8]) -> Result<&Nonce<A>, TinkError> {
+    if nonce.len() != A::NonceSize::USIZE {
+        return Err(TinkError::new_with_kind(
+            ErrorKind::InvalidArgument,
+            &format!(
+                "invalid nonce size {} (want {})",
+                nonce.len(),
+                A::NonceSize::USIZE
+            ),
+        ));
+    }
+    Ok(GenericArray::from_slice(nonce))
+}
+
+/// Encrypt `pt` with `cipher` and a caller-supplied `nonce`, returning `ciphertext || tag`
+/// (without the nonce).
+pub(crate) fn encrypt_with_nonce<A: AeadInPlace>(
+    cipher: &A,
+    nonce: &Nonce<A>,
+    pt: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, ::aead::Error> {
+    let mut out = Vec::with_capacity(pt.len() + A::TagSize::USIZE);
+    out.extend_from_slice(pt);
+    let tag = cipher.encrypt_in_place_detached(nonce, aad, &mut out)?;
+    out.extend_from_slice(&tag);
+    Ok(out)
+}
+
+/// Decrypt `ciphertext || tag` with `cipher` and a caller-supplied `nonce`.
+pub(crate) fn decrypt_with_nonce<A: AeadInPlace>(
+    cipher: &A,
+    nonce: &Nonce<A>,
+    ct: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, ::aead::Error> {
+    let tag_size = A::TagSize::USIZE;
+    if ct.len() < tag_size {
+        return Err(::aead::Error);
+    }
+    let (payload, tag) = ct.split_at(ct.len() - tag_size);
+    let mut out = payload.to_vec();
+    cipher.decrypt_in_place_detached(nonce, aad, &mut out, GenericArray::from_slice(tag))?;
+    Ok(out)
+}
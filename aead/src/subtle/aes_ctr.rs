@@ -21,6 +21,7 @@ use aes_ctr::cipher::stream::{
     consts::U16, generic_array::GenericArray, Key, NewStreamCipher, SyncStreamCipher,
 };
 use tink::{utils::wrap_err, TinkError};
+use zeroize::Zeroize;
 
 /// The minimum IV size that this implementation supports.
 pub const AES_CTR_MIN_IV_SIZE: usize = 12;
@@ -33,6 +34,15 @@ enum AesCtrVariant {
     Aes256(Key<aes_ctr::Aes256Ctr>),
 }
 
+impl Drop for AesCtrVariant {
+    fn drop(&mut self) {
+        match self {
+            AesCtrVariant::Aes128(key) => key.as_mut_slice().zeroize(),
+            AesCtrVariant::Aes256(key) => key.as_mut_slice().zeroize(),
+        }
+    }
+}
+
 /// `AesCtr` is an implementation of AEAD interface.
 #[derive(Clone)]
 pub struct AesCtr {
@@ -72,6 +82,24 @@ impl AesCtr {
         padded_iv[..iv.len()].copy_from_slice(&iv);
         padded_iv.into()
     }
+
+    /// Return the (padded) IV at the start of `ciphertext`.
+    fn ciphertext_iv(&self, ciphertext: &[u8]) -> Result<GenericArray<u8, U16>, TinkError> {
+        if ciphertext.len() < self.iv_size {
+            return Err("AesCtr: ciphertext too short".into());
+        }
+        let mut padded_iv = [0; AES_BLOCK_SIZE_IN_BYTES];
+        padded_iv[..self.iv_size].copy_from_slice(&ciphertext[..self.iv_size]);
+        Ok(padded_iv.into())
+    }
+
+    /// Apply the keystream starting from `iv` to `data`.
+    fn apply_keystream(&self, iv: &GenericArray<u8, U16>, data: &mut [u8]) {
+        match &self.key {
+            AesCtrVariant::Aes128(key) => aes_ctr::Aes128Ctr::new(key, iv).apply_keystream(data),
+            AesCtrVariant::Aes256(key) => aes_ctr::Aes256Ctr::new(key, iv).apply_keystream(data),
+        }
+    }
 }
 
 impl IndCpaCipher for AesCtr {
@@ -79,49 +107,69 @@ impl IndCpaCipher for AesCtr {
     /// The resulting ciphertext consists of two parts:
     /// (1) the IV used for encryption and (2) the actual ciphertext.
     fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, TinkError> {
-        if plaintext.len() > ((isize::MAX as usize) - self.iv_size) {
-            return Err("AesCtr: plaintext too long".into());
-        }
-        let iv = self.new_iv();
         let mut ciphertext = Vec::with_capacity(self.iv_size + plaintext.len());
-        ciphertext.extend_from_slice(&iv[..self.iv_size]);
-        ciphertext.extend_from_slice(plaintext);
-        match &self.key {
-            AesCtrVariant::Aes128(key) => {
-                let mut stream = aes_ctr::Aes128Ctr::new(&key, &iv);
-                stream.apply_keystream(&mut ciphertext[self.iv_size..]);
-            }
-            AesCtrVariant::Aes256(key) => {
-                let mut stream = aes_ctr::Aes256Ctr::new(&key, &iv);
-                stream.apply_keystream(&mut ciphertext[self.iv_size..]);
-            }
-        }
-
+        self.encrypt_to(plaintext, &mut ciphertext)?;
         Ok(ciphertext)
     }
 
     /// Decrypt ciphertext.
     fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, TinkError> {
-        if ciphertext.len() < self.iv_size {
-            return Err("AesCtr: ciphertext too short".into());
-        }
+        let mut plaintext = Vec::with_capacity(ciphertext.len().saturating_sub(self.iv_size));
+        self.decrypt_to(ciphertext, &mut plaintext)?;
+        Ok(plaintext)
+    }
 
-        let mut padded_iv = [0; AES_BLOCK_SIZE_IN_BYTES];
-        padded_iv[..self.iv_size].copy_from_slice(&ciphertext[..self.iv_size]);
+    /// Encrypt the plaintext in `buffer` in place, then move it up to make room for `prefix` and
+    /// the IV in front of it.
+    fn encrypt_in_place_with_prefix(
+        &self,
+        prefix: &[u8],
+        buffer: &mut Vec<u8>,
+        suffix_len: usize,
+    ) -> Result<(), TinkError> {
+        let pt_len = buffer.len();
+        let header_len = prefix.len() + self.iv_size;
+        if pt_len > ((isize::MAX as usize) - header_len - suffix_len) {
+            return Err("AesCtr: plaintext too long".into());
+        }
+        let iv = self.new_iv();
+        buffer.reserve(header_len + suffix_len);
+        self.apply_keystream(&iv, buffer);
+        buffer.resize(header_len + pt_len, 0);
+        buffer.copy_within(..pt_len, header_len);
+        buffer[..prefix.len()].copy_from_slice(prefix);
+        buffer[prefix.len()..header_len].copy_from_slice(&iv[..self.iv_size]);
+        Ok(())
+    }
 
-        let mut plaintext = Vec::with_capacity(ciphertext.len() - self.iv_size);
-        plaintext.extend_from_slice(&ciphertext[self.iv_size..]);
+    /// Decrypt the ciphertext in `buffer` in place, removing the IV.
+    fn decrypt_in_place(&self, buffer: &mut Vec<u8>) -> Result<(), TinkError> {
+        let iv = self.ciphertext_iv(buffer)?;
+        self.apply_keystream(&iv, &mut buffer[self.iv_size..]);
+        buffer.drain(..self.iv_size);
+        Ok(())
+    }
 
-        match &self.key {
-            AesCtrVariant::Aes128(key) => {
-                let mut stream = aes_ctr::Aes128Ctr::new(&key, &padded_iv.into());
-                stream.apply_keystream(&mut plaintext);
-            }
-            AesCtrVariant::Aes256(key) => {
-                let mut stream = aes_ctr::Aes256Ctr::new(&key, &padded_iv.into());
-                stream.apply_keystream(&mut plaintext);
-            }
+    /// Encrypt `plaintext`, appending the IV and the ciphertext to `out`.
+    fn encrypt_to(&self, plaintext: &[u8], out: &mut Vec<u8>) -> Result<(), TinkError> {
+        if plaintext.len() > ((isize::MAX as usize) - self.iv_size) {
+            return Err("AesCtr: plaintext too long".into());
         }
-        Ok(plaintext)
+        let iv = self.new_iv();
+        out.reserve(self.iv_size + plaintext.len());
+        out.extend_from_slice(&iv[..self.iv_size]);
+        let start = out.len();
+        out.extend_from_slice(plaintext);
+        self.apply_keystream(&iv, &mut out[start..]);
+        Ok(())
+    }
+
+    /// Decrypt `ciphertext`, appending the plaintext to `out`.
+    fn decrypt_to(&self, ciphertext: &[u8], out: &mut Vec<u8>) -> Result<(), TinkError> {
+        let iv = self.ciphertext_iv(ciphertext)?;
+        let start = out.len();
+        out.extend_from_slice(&ciphertext[self.iv_size..]);
+        self.apply_keystream(&iv, &mut out[start..]);
+        Ok(())
     }
 }
@@ -16,8 +16,12 @@
 
 //! AES-GCM based implementation of the [`tink::Aead`] trait.
 
+use super::{
+    decrypt_in_place, decrypt_to, decrypt_with_nonce, encrypt_in_place, encrypt_to,
+    encrypt_with_nonce, nonce_from_slice,
+};
 use aes_gcm::aead::{consts::U12, generic_array::GenericArray, Aead, NewAead, Payload};
-use tink::{utils::wrap_err, TinkError};
+use tink::{utils::wrap_err, ErrorKind, TinkError};
 
 /// The only IV size that this implementation supports.
 pub const AES_GCM_IV_SIZE: usize = 12;
@@ -33,6 +37,9 @@ enum AesGcmVariant {
 }
 
 /// `AesGcm` is an implementation of the [`tink::Aead`] trait.
+///
+/// Note that the AES key schedule and the GHASH key held by an `AesGcm` are not wiped on drop, as
+/// the `aes` crate and the (software) POLYVAL implementation do not (yet) support zeroization.
 #[derive(Clone)]
 pub struct AesGcm {
     key: AesGcmVariant,
@@ -80,9 +87,7 @@ impl tink::Aead for AesGcm {
 
     /// Decrypt `ct` with `aad` as the additional authenticated data.
     fn decrypt(&self, ct: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
-        if ct.len() < AES_GCM_IV_SIZE + AES_GCM_TAG_SIZE {
-            return Err("AesGcm: ciphertext too short".into());
-        }
+        check_ciphertext_size(ct)?;
         let iv = GenericArray::from_slice(&ct[..AES_GCM_IV_SIZE]);
         let payload = Payload {
             msg: &ct[AES_GCM_IV_SIZE..],
@@ -92,9 +97,113 @@ impl tink::Aead for AesGcm {
             AesGcmVariant::Aes128(key) => key.decrypt(iv, payload),
             AesGcmVariant::Aes256(key) => key.decrypt(iv, payload),
         }
-        .map_err(|e| wrap_err("AesGcm", e))?;
+        .map_err(|e| wrap_err("AesGcm", e).with_kind(ErrorKind::Authentication))?;
         Ok(pt)
     }
+
+    /// Encrypt the plaintext in `buffer` in place, replacing it with `prefix` followed by a
+    /// ciphertext with the same format as for [`encrypt`](tink::Aead::encrypt).
+    fn encrypt_in_place_with_prefix(
+        &self,
+        prefix: &[u8],
+        buffer: &mut Vec<u8>,
+        aad: &[u8],
+    ) -> Result<(), TinkError> {
+        if buffer.len() > max_pt_size() {
+            return Err("AesGcm: plaintext too long".into());
+        }
+        let iv = new_iv();
+        match &self.key {
+            AesGcmVariant::Aes128(key) => encrypt_in_place(key.as_ref(), &iv, prefix, buffer, aad),
+            AesGcmVariant::Aes256(key) => encrypt_in_place(key.as_ref(), &iv, prefix, buffer, aad),
+        }
+        .map_err(|e| wrap_err("AesGcm", e))
+    }
+
+    /// Decrypt the ciphertext in `buffer` in place.
+    fn decrypt_in_place(&self, buffer: &mut Vec<u8>, aad: &[u8]) -> Result<(), TinkError> {
+        check_ciphertext_size(buffer)?;
+        match &self.key {
+            AesGcmVariant::Aes128(key) => decrypt_in_place(key.as_ref(), buffer, aad),
+            AesGcmVariant::Aes256(key) => decrypt_in_place(key.as_ref(), buffer, aad),
+        }
+        .map_err(|e| wrap_err("AesGcm", e).with_kind(ErrorKind::Authentication))
+    }
+
+    /// Encrypt `pt`, appending the ciphertext to `out`.
+    fn encrypt_to(&self, pt: &[u8], aad: &[u8], out: &mut Vec<u8>) -> Result<(), TinkError> {
+        if pt.len() > max_pt_size() {
+            return Err("AesGcm: plaintext too long".into());
+        }
+        let iv = new_iv();
+        match &self.key {
+            AesGcmVariant::Aes128(key) => encrypt_to(key.as_ref(), &iv, pt, aad, out),
+            AesGcmVariant::Aes256(key) => encrypt_to(key.as_ref(), &iv, pt, aad, out),
+        }
+        .map_err(|e| wrap_err("AesGcm", e))
+    }
+
+    /// Decrypt `ct`, appending the plaintext to `out`.
+    fn decrypt_to(&self, ct: &[u8], aad: &[u8], out: &mut Vec<u8>) -> Result<(), TinkError> {
+        check_ciphertext_size(ct)?;
+        match &self.key {
+            AesGcmVariant::Aes128(key) => decrypt_to(key.as_ref(), ct, aad, out),
+            AesGcmVariant::Aes256(key) => decrypt_to(key.as_ref(), ct, aad, out),
+        }
+        .map_err(|e| wrap_err("AesGcm", e).with_kind(ErrorKind::Authentication))
+    }
+}
+
+impl crate::AeadWithNonce for AesGcm {
+    fn nonce_size(&self) -> usize {
+        AES_GCM_IV_SIZE
+    }
+
+    /// Encrypt `pt` with the caller-supplied `nonce`, returning the ciphertext followed by the tag.
+    fn encrypt_with_nonce(
+        &self,
+        nonce: &[u8],
+        pt: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, TinkError> {
+        let nonce =
+            nonce_from_slice::<aes_gcm::Aes128Gcm>(nonce).map_err(|e| wrap_err("AesGcm", e))?;
+        if pt.len() > max_pt_size() {
+            return Err("AesGcm: plaintext too long".into());
+        }
+        match &self.key {
+            AesGcmVariant::Aes128(key) => encrypt_with_nonce(key.as_ref(), nonce, pt, aad),
+            AesGcmVariant::Aes256(key) => encrypt_with_nonce(key.as_ref(), nonce, pt, aad),
+        }
+        .map_err(|e| wrap_err("AesGcm", e))
+    }
+
+    /// Decrypt `ct` (the ciphertext followed by the tag) with the caller-supplied `nonce`.
+    fn decrypt_with_nonce(
+        &self,
+        nonce: &[u8],
+        ct: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, TinkError> {
+        let nonce =
+            nonce_from_slice::<aes_gcm::Aes128Gcm>(nonce).map_err(|e| wrap_err("AesGcm", e))?;
+        match &self.key {
+            AesGcmVariant::Aes128(key) => decrypt_with_nonce(key.as_ref(), nonce, ct, aad),
+            AesGcmVariant::Aes256(key) => decrypt_with_nonce(key.as_ref(), nonce, ct, aad),
+        }
+        .map_err(|e| wrap_err("AesGcm", e).with_kind(ErrorKind::Authentication))
+    }
+}
+
+/// Check that a ciphertext is long enough to hold an IV and a tag.
+fn check_ciphertext_size(ct: &[u8]) -> Result<(), TinkError> {
+    if ct.len() < AES_GCM_IV_SIZE + AES_GCM_TAG_SIZE {
+        return Err(TinkError::new_with_kind(
+            ErrorKind::Authentication,
+            "AesGcm: ciphertext too short",
+        ));
+    }
+    Ok(())
 }
 
 /// Create a new IV for encryption.
@@ -0,0 +1,122 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Key-committing AEAD based on AES-GCM and HKDF.
+
+use super::{AesGcm, AES_GCM_IV_SIZE, AES_GCM_TAG_SIZE};
+use crate::AeadWithNonce;
+use tink::{proto::HashType, utils::wrap_err, ErrorKind, TinkError};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Size of the commitment tag included in each ciphertext.
+pub const AES_GCM_COMMITMENT_SIZE: usize = 32;
+/// HKDF info for deriving the per-message AES-GCM key.
+const KEY_INFO: &[u8] = b"tink-rust AesGcmCommitting key";
+/// HKDF info for deriving the per-message commitment tag.
+const COMMITMENT_INFO: &[u8] = b"tink-rust AesGcmCommitting commitment";
+
+/// `AesGcmCommitting` is an implementation of the [`tink::Aead`] trait which, unlike
+/// [`AesGcm`], is key-committing: a ciphertext can only be decrypted with the key that produced
+/// it.
+///
+/// For each message a random 12-byte nonce is generated, and HKDF-SHA256 (keyed with the key
+/// value and salted with the nonce) is used to derive both an AES-GCM key and a 32-byte
+/// commitment tag.  The ciphertext consists of the nonce, the commitment tag and the AES-GCM
+/// ciphertext and tag (using the same nonce); decryption recomputes the commitment tag and
+/// rejects the ciphertext if it does not match.  Finding a ciphertext that decrypts under two
+/// different keys therefore requires a collision of HMAC-SHA256 outputs.
+#[derive(Clone)]
+pub struct AesGcmCommitting {
+    key: Vec<u8>,
+}
+
+impl AesGcmCommitting {
+    /// Return an [`AesGcmCommitting`] instance.
+    /// The key argument should be either 16 or 32 bytes, to select AES-128 or AES-256 for the
+    /// derived keys.
+    pub fn new(key: &[u8]) -> Result<AesGcmCommitting, TinkError> {
+        super::validate_aes_key_size(key.len()).map_err(|e| wrap_err("AesGcmCommitting", e))?;
+        Ok(AesGcmCommitting { key: key.to_vec() })
+    }
+
+    /// Derive the AES-GCM primitive and the commitment tag for the given nonce.
+    fn derive(&self, nonce: &[u8]) -> Result<(AesGcm, Vec<u8>), TinkError> {
+        let key = Zeroizing::new(tink::subtle::compute_hkdf(
+            HashType::Sha256,
+            &self.key,
+            nonce,
+            KEY_INFO,
+            self.key.len(),
+        )?);
+        let commitment = tink::subtle::compute_hkdf(
+            HashType::Sha256,
+            &self.key,
+            nonce,
+            COMMITMENT_INFO,
+            AES_GCM_COMMITMENT_SIZE,
+        )?;
+        Ok((AesGcm::new(&key)?, commitment))
+    }
+}
+
+impl Drop for AesGcmCommitting {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl tink::Aead for AesGcmCommitting {
+    /// Encrypt `pt` with `aad` as additional authenticated data.  The resulting ciphertext consists
+    /// of three parts: (1) the nonce, (2) the commitment tag and (3) the actual ciphertext.
+    fn encrypt(&self, pt: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+        let nonce = tink::subtle::random::get_random_bytes(AES_GCM_IV_SIZE);
+        let (cipher, commitment) = self
+            .derive(&nonce)
+            .map_err(|e| wrap_err("AesGcmCommitting", e))?;
+        let ct = cipher
+            .encrypt_with_nonce(&nonce, pt, aad)
+            .map_err(|e| wrap_err("AesGcmCommitting", e))?;
+        let mut ret = Vec::with_capacity(nonce.len() + commitment.len() + ct.len());
+        ret.extend_from_slice(&nonce);
+        ret.extend_from_slice(&commitment);
+        ret.extend_from_slice(&ct);
+        Ok(ret)
+    }
+
+    /// Decrypt `ct` with `aad` as the additional authenticated data.
+    fn decrypt(&self, ct: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+        if ct.len() < AES_GCM_IV_SIZE + AES_GCM_COMMITMENT_SIZE + AES_GCM_TAG_SIZE {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "AesGcmCommitting: ciphertext too short",
+            ));
+        }
+        let (nonce, rest) = ct.split_at(AES_GCM_IV_SIZE);
+        let (commitment, ct) = rest.split_at(AES_GCM_COMMITMENT_SIZE);
+        let (cipher, want_commitment) = self
+            .derive(nonce)
+            .map_err(|e| wrap_err("AesGcmCommitting", e))?;
+        if !tink::subtle::constant_time_compare(commitment, &want_commitment) {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "AesGcmCommitting: commitment mismatch",
+            ));
+        }
+        cipher
+            .decrypt_with_nonce(nonce, ct, aad)
+            .map_err(|e| wrap_err("AesGcmCommitting", e))
+    }
+}
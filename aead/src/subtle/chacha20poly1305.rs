@@ -16,8 +16,13 @@
 
 //! ChaCha20 Poly1305 implementation of AEAD.
 
+use super::{
+    decrypt_in_place, decrypt_to, decrypt_with_nonce, encrypt_in_place, encrypt_to,
+    encrypt_with_nonce, nonce_from_slice,
+};
 use chacha20poly1305::aead::{Aead, NewAead, Payload};
-use tink::{utils::wrap_err, TinkError};
+use tink::{utils::wrap_err, ErrorKind, TinkError};
+use zeroize::Zeroize;
 
 /// Size of a ChaCh20 key in bytes.
 pub const CHA_CHA20_KEY_SIZE: usize = 32;
@@ -46,6 +51,12 @@ impl ChaCha20Poly1305 {
     }
 }
 
+impl Drop for ChaCha20Poly1305 {
+    fn drop(&mut self) {
+        self.key.as_mut_slice().zeroize();
+    }
+}
+
 impl tink::Aead for ChaCha20Poly1305 {
     /// Encrypt `pt` with `aad` as additional
     /// authenticated data. The resulting ciphertext consists of two parts:
@@ -68,10 +79,7 @@ impl tink::Aead for ChaCha20Poly1305 {
 
     /// Decrypt `ct` with `aad` as the additional authenticated data.
     fn decrypt(&self, ct: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
-        if ct.len() < CHA_CHA20_NONCE_SIZE + POLY1305_TAG_SIZE {
-            return Err("ChaCha20pPly1305: ciphertext too short".into());
-        }
-
+        check_ciphertext_size(ct)?;
         let cipher = chacha20poly1305::ChaCha20Poly1305::new(&self.key);
         let n = chacha20poly1305::Nonce::from_slice(&ct[..CHA_CHA20_NONCE_SIZE]);
         cipher
@@ -82,8 +90,96 @@ impl tink::Aead for ChaCha20Poly1305 {
                     aad,
                 },
             )
+            .map_err(|e| wrap_err("ChaCha20Poly1305", e).with_kind(ErrorKind::Authentication))
+    }
+
+    /// Encrypt the plaintext in `buffer` in place, replacing it with `prefix` followed by a
+    /// ciphertext with the same format as for [`encrypt`](tink::Aead::encrypt).
+    fn encrypt_in_place_with_prefix(
+        &self,
+        prefix: &[u8],
+        buffer: &mut Vec<u8>,
+        aad: &[u8],
+    ) -> Result<(), TinkError> {
+        if buffer.len() > (isize::MAX as usize) - CHA_CHA20_NONCE_SIZE - POLY1305_TAG_SIZE {
+            return Err("ChaCha20Poly1305: plaintext too long".into());
+        }
+        let cipher = chacha20poly1305::ChaCha20Poly1305::new(&self.key);
+        encrypt_in_place(&cipher, &new_nonce(), prefix, buffer, aad)
             .map_err(|e| wrap_err("ChaCha20Poly1305", e))
     }
+
+    /// Decrypt the ciphertext in `buffer` in place.
+    fn decrypt_in_place(&self, buffer: &mut Vec<u8>, aad: &[u8]) -> Result<(), TinkError> {
+        check_ciphertext_size(buffer)?;
+        let cipher = chacha20poly1305::ChaCha20Poly1305::new(&self.key);
+        decrypt_in_place(&cipher, buffer, aad)
+            .map_err(|e| wrap_err("ChaCha20Poly1305", e).with_kind(ErrorKind::Authentication))
+    }
+
+    /// Encrypt `pt`, appending the ciphertext to `out`.
+    fn encrypt_to(&self, pt: &[u8], aad: &[u8], out: &mut Vec<u8>) -> Result<(), TinkError> {
+        if pt.len() > (isize::MAX as usize) - CHA_CHA20_NONCE_SIZE - POLY1305_TAG_SIZE {
+            return Err("ChaCha20Poly1305: plaintext too long".into());
+        }
+        let cipher = chacha20poly1305::ChaCha20Poly1305::new(&self.key);
+        encrypt_to(&cipher, &new_nonce(), pt, aad, out).map_err(|e| wrap_err("ChaCha20Poly1305", e))
+    }
+
+    /// Decrypt `ct`, appending the plaintext to `out`.
+    fn decrypt_to(&self, ct: &[u8], aad: &[u8], out: &mut Vec<u8>) -> Result<(), TinkError> {
+        check_ciphertext_size(ct)?;
+        let cipher = chacha20poly1305::ChaCha20Poly1305::new(&self.key);
+        decrypt_to(&cipher, ct, aad, out)
+            .map_err(|e| wrap_err("ChaCha20Poly1305", e).with_kind(ErrorKind::Authentication))
+    }
+}
+
+impl crate::AeadWithNonce for ChaCha20Poly1305 {
+    fn nonce_size(&self) -> usize {
+        CHA_CHA20_NONCE_SIZE
+    }
+
+    /// Encrypt `pt` with the caller-supplied `nonce`, returning the ciphertext followed by the tag.
+    fn encrypt_with_nonce(
+        &self,
+        nonce: &[u8],
+        pt: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, TinkError> {
+        let nonce = nonce_from_slice::<chacha20poly1305::ChaCha20Poly1305>(nonce)
+            .map_err(|e| wrap_err("ChaCha20Poly1305", e))?;
+        if pt.len() > (isize::MAX as usize) - CHA_CHA20_NONCE_SIZE - POLY1305_TAG_SIZE {
+            return Err("ChaCha20Poly1305: plaintext too long".into());
+        }
+        let cipher = chacha20poly1305::ChaCha20Poly1305::new(&self.key);
+        encrypt_with_nonce(&cipher, nonce, pt, aad).map_err(|e| wrap_err("ChaCha20Poly1305", e))
+    }
+
+    /// Decrypt `ct` (the ciphertext followed by the tag) with the caller-supplied `nonce`.
+    fn decrypt_with_nonce(
+        &self,
+        nonce: &[u8],
+        ct: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, TinkError> {
+        let nonce = nonce_from_slice::<chacha20poly1305::ChaCha20Poly1305>(nonce)
+            .map_err(|e| wrap_err("ChaCha20Poly1305", e))?;
+        let cipher = chacha20poly1305::ChaCha20Poly1305::new(&self.key);
+        decrypt_with_nonce(&cipher, nonce, ct, aad)
+            .map_err(|e| wrap_err("ChaCha20Poly1305", e).with_kind(ErrorKind::Authentication))
+    }
+}
+
+/// Check that a ciphertext is long enough to hold a nonce and a tag.
+fn check_ciphertext_size(ct: &[u8]) -> Result<(), TinkError> {
+    if ct.len() < CHA_CHA20_NONCE_SIZE + POLY1305_TAG_SIZE {
+        return Err(TinkError::new_with_kind(
+            ErrorKind::Authentication,
+            "ChaCha20pPly1305: ciphertext too short",
+        ));
+    }
+    Ok(())
 }
 
 /// Create a new nonce for encryption.
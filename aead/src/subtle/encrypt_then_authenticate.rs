@@ -17,12 +17,14 @@
 //! General AEAD implementation combining an `IndCpaCipher` with a `tink::Mac`
 
 use super::IndCpaCipher;
-use tink::{utils::wrap_err, TinkError};
+use tink::{utils::wrap_err, ErrorKind, TinkError};
 
 /// `EncryptThenAuthenticate` performs an encrypt-then-MAC operation on plaintext
 /// and additional authenticated data (aad). The MAC is computed over (aad ||
 /// ciphertext || size of aad). This implementation is based on
 /// http://tools.ietf.org/html/draft-mcgrew-aead-aes-cbc-hmac-sha2-05.
+///
+/// Key material is held (and wiped on drop) by the component primitives.
 pub struct EncryptThenAuthenticate {
     ind_cpa_cipher: Box<dyn IndCpaCipher>,
     mac: Box<dyn tink::Mac>,
@@ -61,6 +63,66 @@ impl EncryptThenAuthenticate {
     }
 }
 
+impl EncryptThenAuthenticate {
+    /// Compute the tag over (additional_data || payload || n), where n is additional_data's length
+    /// in bits represented as a 64-bit bigendian unsigned integer.
+    fn compute_tag(&self, payload: &[u8], additional_data: &[u8]) -> Result<Vec<u8>, TinkError> {
+        let tag = self
+            .mac
+            .new_computation()
+            .and_then(|mut computation| {
+                auth_data(payload, additional_data, |data| computation.update(data))?;
+                computation.compute_mac()
+            })
+            .map_err(|e| wrap_err("EncryptThenAuthenticate", e))?;
+        if tag.len() != self.tag_size {
+            return Err("EncryptThenAuthenticate: invalid tag size".into());
+        }
+        Ok(tag)
+    }
+
+    /// Verify the tag at the end of `ciphertext`, returning the payload that precedes it.
+    fn verify_tag<'a>(
+        &self,
+        ciphertext: &'a [u8],
+        additional_data: &[u8],
+    ) -> Result<&'a [u8], TinkError> {
+        if ciphertext.len() < self.tag_size {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "EncryptThenAuthenticate: ciphertext too short",
+            ));
+        }
+
+        // payload contains everything except the tag at the end.
+        let (payload, tag) = ciphertext.split_at(ciphertext.len() - self.tag_size);
+
+        // Verify against the tag at the end of the ciphertext.
+        self.mac
+            .new_verification(tag)
+            .and_then(|mut verification| {
+                auth_data(payload, additional_data, |data| verification.update(data))?;
+                verification.verify_mac()
+            })
+            .map_err(|e| {
+                wrap_err("EncryptThenAuthenticate", e).with_kind(ErrorKind::Authentication)
+            })?;
+        Ok(payload)
+    }
+}
+
+/// Pass the data to authenticate (additional_data || payload || aad_size_in_bits) to `update` in
+/// pieces, without assembling it into a single buffer.
+fn auth_data<F>(payload: &[u8], additional_data: &[u8], mut update: F) -> Result<(), TinkError>
+where
+    F: FnMut(&[u8]) -> Result<(), TinkError>,
+{
+    update(additional_data)?;
+    update(payload)?;
+    let aad_size_in_bits = (additional_data.len() * 8) as u64;
+    update(&aad_size_in_bits.to_be_bytes())
+}
+
 impl tink::Aead for EncryptThenAuthenticate {
     /// Encrypt `plaintext` with `additional_data` as additional authenticated
     /// data. The resulting ciphertext allows for checking authenticity and
@@ -71,63 +133,92 @@ impl tink::Aead for EncryptThenAuthenticate {
     /// in bits represented as a 64-bit bigendian unsigned integer. The final
     /// ciphertext format is (IND-CPA ciphertext || mac).
     fn encrypt(&self, plaintext: &[u8], additional_data: &[u8]) -> Result<Vec<u8>, TinkError> {
-        let mut ciphertext = self
-            .ind_cpa_cipher
-            .encrypt(plaintext)
-            .map_err(|e| wrap_err("EncryptThenAuthenticate", e))?;
-
-        // Authenticate the following data:
-        // additional_data || payload || aad_size_in_bits
-        let mut to_auth_data = Vec::with_capacity(additional_data.len() + ciphertext.len() + 8);
-        to_auth_data.extend_from_slice(additional_data);
-        to_auth_data.extend_from_slice(&ciphertext);
-        let aad_size_in_bits = (additional_data.len() * 8) as u64;
-        to_auth_data.extend_from_slice(&aad_size_in_bits.to_be_bytes());
-
-        let tag = self
-            .mac
-            .compute_mac(&to_auth_data)
-            .map_err(|e| wrap_err("EncryptThenAuthenticate", e))?;
-        if tag.len() != self.tag_size {
-            return Err("EncryptThenAuthenticate: invalid tag size".into());
-        }
-
-        // Put the tag at the end of the ciphertext.
-        ciphertext.extend_from_slice(&tag);
+        let mut ciphertext = Vec::new();
+        self.encrypt_to(plaintext, additional_data, &mut ciphertext)?;
         Ok(ciphertext)
     }
 
     /// Decrypt `ciphertext` with `additional_data` as additional authenticated
     /// data.
     fn decrypt(&self, ciphertext: &[u8], additional_data: &[u8]) -> Result<Vec<u8>, TinkError> {
-        if ciphertext.len() < self.tag_size {
-            return Err("EncryptThenAuthenticate: ciphertext too short".into());
-        }
-
-        // payload contains everything except the tag at the end.
-        let payload = &ciphertext[..(ciphertext.len() - self.tag_size)];
-
-        // Authenticate the following data:
-        // additional_data || payload || aad_size_in_bits
-        let mut to_auth_data = Vec::with_capacity(additional_data.len() + payload.len() + 8);
-        to_auth_data.extend_from_slice(additional_data);
-        to_auth_data.extend_from_slice(payload);
-        let aad_size_in_bits = (additional_data.len() * 8) as u64;
-        to_auth_data.extend_from_slice(&aad_size_in_bits.to_be_bytes());
+        let mut plaintext = Vec::new();
+        self.decrypt_to(ciphertext, additional_data, &mut plaintext)?;
+        Ok(plaintext)
+    }
 
-        // Verify against the tag at the end of the ciphertext.
-        self.mac
-            .verify_mac(
-                &ciphertext[(ciphertext.len() - self.tag_size)..],
-                &to_auth_data,
-            )
+    /// Encrypt the plaintext in `buffer` in place, after `prefix`.
+    fn encrypt_in_place_with_prefix(
+        &self,
+        prefix: &[u8],
+        buffer: &mut Vec<u8>,
+        additional_data: &[u8],
+    ) -> Result<(), TinkError> {
+        self.ind_cpa_cipher
+            .encrypt_in_place_with_prefix(prefix, buffer, self.tag_size)
             .map_err(|e| wrap_err("EncryptThenAuthenticate", e))?;
+        match self.compute_tag(&buffer[prefix.len()..], additional_data) {
+            Ok(tag) => {
+                buffer.extend_from_slice(&tag);
+                Ok(())
+            }
+            Err(e) => {
+                // Restore the plaintext.
+                buffer.drain(..prefix.len());
+                self.ind_cpa_cipher.decrypt_in_place(buffer)?;
+                Err(e)
+            }
+        }
+    }
 
-        let plaintext = self
-            .ind_cpa_cipher
-            .decrypt(payload)
+    /// Decrypt the ciphertext in `buffer` in place.
+    fn decrypt_in_place(
+        &self,
+        buffer: &mut Vec<u8>,
+        additional_data: &[u8],
+    ) -> Result<(), TinkError> {
+        let payload_len = self.verify_tag(buffer, additional_data)?.len();
+        let tag = buffer.split_off(payload_len);
+        if let Err(e) = self.ind_cpa_cipher.decrypt_in_place(buffer) {
+            buffer.extend_from_slice(&tag);
+            return Err(wrap_err("EncryptThenAuthenticate", e));
+        }
+        Ok(())
+    }
+
+    /// Encrypt `plaintext`, appending the ciphertext to `out`.
+    fn encrypt_to(
+        &self,
+        plaintext: &[u8],
+        additional_data: &[u8],
+        out: &mut Vec<u8>,
+    ) -> Result<(), TinkError> {
+        let start = out.len();
+        self.ind_cpa_cipher
+            .encrypt_to(plaintext, out)
             .map_err(|e| wrap_err("EncryptThenAuthenticate", e))?;
+        match self.compute_tag(&out[start..], additional_data) {
+            Ok(tag) => {
+                // Put the tag at the end of the ciphertext.
+                out.extend_from_slice(&tag);
+                Ok(())
+            }
+            Err(e) => {
+                out.truncate(start);
+                Err(e)
+            }
+        }
+    }
 
-        Ok(plaintext)
+    /// Decrypt `ciphertext`, appending the plaintext to `out`.
+    fn decrypt_to(
+        &self,
+        ciphertext: &[u8],
+        additional_data: &[u8],
+        out: &mut Vec<u8>,
+    ) -> Result<(), TinkError> {
+        let payload = self.verify_tag(ciphertext, additional_data)?;
+        self.ind_cpa_cipher
+            .decrypt_to(payload, out)
+            .map_err(|e| wrap_err("EncryptThenAuthenticate", e))
     }
 }
@@ -20,13 +20,57 @@
 // indistinguishable against chosen-plaintext attacks. Said primitives do not
 // provide authentication, thus should not be used directly, but only to
 // construct safer primitives such as AEAD.
-pub trait IndCpaCipher: IndCpaCipherBoxClone {
+pub trait IndCpaCipher: IndCpaCipherBoxClone + Send + Sync {
     // Encrypt plaintext. The resulting ciphertext is indistinguishable under
     // chosen-plaintext attack. However, it does not have integrity protection.
     fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, tink::TinkError>;
 
     // Decrypt ciphertext and returns the resulting plaintext.
     fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, tink::TinkError>;
+
+    /// Encrypt the plaintext held in `buffer`, replacing it with the ciphertext.  If encryption
+    /// fails, `buffer` is left unchanged.
+    fn encrypt_in_place(&self, buffer: &mut Vec<u8>) -> Result<(), tink::TinkError> {
+        self.encrypt_in_place_with_prefix(&[], buffer, 0)
+    }
+
+    /// Encrypt the plaintext held in `buffer`, replacing it with `prefix || ciphertext`, and
+    /// reserving space for `suffix_len` more bytes to be appended after the ciphertext.  If
+    /// encryption fails, `buffer` is left unchanged.
+    fn encrypt_in_place_with_prefix(
+        &self,
+        prefix: &[u8],
+        buffer: &mut Vec<u8>,
+        suffix_len: usize,
+    ) -> Result<(), tink::TinkError> {
+        let ct = self.encrypt(buffer)?;
+        buffer.clear();
+        buffer.reserve(prefix.len() + ct.len() + suffix_len);
+        buffer.extend_from_slice(prefix);
+        buffer.extend_from_slice(&ct);
+        Ok(())
+    }
+
+    /// Decrypt the ciphertext held in `buffer`, replacing it with the plaintext.  If decryption
+    /// fails, `buffer` is left unchanged.
+    fn decrypt_in_place(&self, buffer: &mut Vec<u8>) -> Result<(), tink::TinkError> {
+        *buffer = self.decrypt(buffer)?;
+        Ok(())
+    }
+
+    /// Encrypt `plaintext`, appending the ciphertext to `out`.  If encryption fails, `out` is left
+    /// unchanged.
+    fn encrypt_to(&self, plaintext: &[u8], out: &mut Vec<u8>) -> Result<(), tink::TinkError> {
+        out.extend_from_slice(&self.encrypt(plaintext)?);
+        Ok(())
+    }
+
+    /// Decrypt `ciphertext`, appending the plaintext to `out`.  If decryption fails, `out` is left
+    /// unchanged.
+    fn decrypt_to(&self, ciphertext: &[u8], out: &mut Vec<u8>) -> Result<(), tink::TinkError> {
+        out.extend_from_slice(&self.decrypt(ciphertext)?);
+        Ok(())
+    }
 }
 
 /// Trait bound to indicate that primitive trait objects should support cloning
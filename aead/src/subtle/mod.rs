@@ -22,6 +22,8 @@ mod aes_ctr;
 pub use self::aes_ctr::*;
 mod aes_gcm;
 pub use self::aes_gcm::*;
+mod aes_gcm_committing;
+pub use self::aes_gcm_committing::*;
 mod aes_gcm_siv;
 pub use self::aes_gcm_siv::*;
 mod chacha20poly1305;
@@ -30,5 +32,7 @@ mod encrypt_then_authenticate;
 pub use encrypt_then_authenticate::*;
 mod ind_cpa;
 pub use ind_cpa::*;
+mod x_aes_gcm;
+pub use self::x_aes_gcm::*;
 mod xchacha20poly1305;
 pub use self::xchacha20poly1305::*;
@@ -0,0 +1,175 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! XAES-256-GCM implementation of AEAD.
+
+use super::{decrypt_with_nonce, encrypt_with_nonce, AES_GCM_IV_SIZE, AES_GCM_TAG_SIZE};
+use aes_gcm::{
+    aead::{generic_array::GenericArray, NewAead},
+    aes::{Aes256, BlockCipher, NewBlockCipher},
+    Aes256Gcm,
+};
+use tink::{utils::wrap_err, ErrorKind, TinkError};
+use zeroize::{Zeroize, Zeroizing};
+
+/// Size of an XAES-256-GCM key in bytes.
+pub const X_AES_GCM_KEY_SIZE: usize = 32;
+/// Size of an XAES-256-GCM nonce in bytes.
+pub const X_AES_GCM_NONCE_SIZE: usize = 24;
+/// Size of the part of the nonce used to derive the per-message key.
+const SALT_SIZE: usize = X_AES_GCM_NONCE_SIZE - AES_GCM_IV_SIZE;
+/// The maximum supported plaintext size.
+const MAX_X_AES_GCM_PLAINTEXT_SIZE: usize = (1 << 36) - 32;
+
+/// `XAesGcm` is an implementation of the [`tink::Aead`] trait for
+/// [XAES-256-GCM](https://c2sp.org/XAES-256-GCM).
+///
+/// XAES-256-GCM uses 192-bit nonces, so random nonces can safely be used for far more messages
+/// than with [`AesGcm`](super::AesGcm)'s 96-bit nonces.  The first 12 bytes of the nonce are used
+/// to derive a per-message AES-256 key from the main key (with the NIST SP 800-108r1 counter-mode
+/// KDF using AES-256-CMAC), and the remaining 12 bytes are used as the AES-256-GCM nonce, so only
+/// FIPS-approved algorithms are involved.
+///
+/// The AES-256 cipher for the main key is built once, and held for the lifetime of the primitive
+/// along with the derived CMAC subkey (which is zeroized on drop).  Note that the `aes` crate does
+/// not (yet) support zeroization, so the AES-256 key schedules are not wiped on drop: neither the
+/// one for the main key, nor the one inside the AES-256-GCM cipher derived for each message.  The
+/// derived per-message key bytes, and the GHASH key computed from them, are wiped.
+#[derive(Clone)]
+pub struct XAesGcm {
+    cipher: Aes256,
+    /// The CMAC subkey K1 for the main key.
+    k1: Zeroizing<[u8; 16]>,
+}
+
+impl XAesGcm {
+    /// Return an `XAesGcm` instance.
+    /// The `key` argument should be a 32-byte key.
+    pub fn new(key: &[u8]) -> Result<XAesGcm, TinkError> {
+        if key.len() != X_AES_GCM_KEY_SIZE {
+            return Err("XAesGcm: bad key length".into());
+        }
+        let cipher = Aes256::new(GenericArray::from_slice(key));
+        let mut l = GenericArray::default();
+        cipher.encrypt_block(&mut l);
+        let mut k1 = Zeroizing::new([0u8; 16]);
+        for i in 0..15 {
+            k1[i] = (l[i] << 1) | (l[i + 1] >> 7);
+        }
+        k1[15] = (l[15] << 1) ^ if l[0] & 0x80 != 0 { 0x87 } else { 0 };
+        l.as_mut_slice().zeroize();
+        Ok(XAesGcm { cipher, k1 })
+    }
+
+    /// Derive the AES-256-GCM cipher for the given salt (the first part of a nonce).
+    fn derive(&self, salt: &[u8]) -> Aes256Gcm {
+        let mut key = Zeroizing::new([0u8; 32]);
+        for (i, half) in key.chunks_mut(16).enumerate() {
+            let block = GenericArray::from_mut_slice(half);
+            block[1] = i as u8 + 1;
+            block[2] = b'X';
+            block[4..].copy_from_slice(salt);
+            for (b, k) in block.iter_mut().zip(self.k1.iter()) {
+                *b ^= k;
+            }
+            self.cipher.encrypt_block(block);
+        }
+        Aes256Gcm::new(GenericArray::from_slice(&key[..]))
+    }
+}
+
+impl tink::Aead for XAesGcm {
+    /// Encrypt `pt` with `aad` as additional authenticated data.  The resulting ciphertext consists
+    /// of two parts: (1) the 24-byte nonce used for encryption and (2) the actual ciphertext.
+    fn encrypt(&self, pt: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+        let nonce = tink::subtle::random::get_random_bytes(X_AES_GCM_NONCE_SIZE);
+        let ct = crate::AeadWithNonce::encrypt_with_nonce(self, &nonce, pt, aad)?;
+        let mut ret = Vec::with_capacity(nonce.len() + ct.len());
+        ret.extend_from_slice(&nonce);
+        ret.extend_from_slice(&ct);
+        Ok(ret)
+    }
+
+    /// Decrypt `ct` with `aad` as the additional authenticated data.
+    fn decrypt(&self, ct: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+        if ct.len() < X_AES_GCM_NONCE_SIZE + AES_GCM_TAG_SIZE {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "XAesGcm: ciphertext too short",
+            ));
+        }
+        let (nonce, ct) = ct.split_at(X_AES_GCM_NONCE_SIZE);
+        crate::AeadWithNonce::decrypt_with_nonce(self, nonce, ct, aad)
+    }
+}
+
+impl crate::AeadWithNonce for XAesGcm {
+    fn nonce_size(&self) -> usize {
+        X_AES_GCM_NONCE_SIZE
+    }
+
+    /// Encrypt `pt` with the caller-supplied `nonce`, returning the ciphertext followed by the tag.
+    fn encrypt_with_nonce(
+        &self,
+        nonce: &[u8],
+        pt: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, TinkError> {
+        check_nonce_size(nonce)?;
+        if pt.len() > max_pt_size() {
+            return Err("XAesGcm: plaintext too long".into());
+        }
+        let (salt, nonce) = nonce.split_at(SALT_SIZE);
+        let cipher = self.derive(salt);
+        encrypt_with_nonce(&cipher, GenericArray::from_slice(nonce), pt, aad)
+            .map_err(|e| wrap_err("XAesGcm", e))
+    }
+
+    /// Decrypt `ct` (the ciphertext followed by the tag) with the caller-supplied `nonce`.
+    fn decrypt_with_nonce(
+        &self,
+        nonce: &[u8],
+        ct: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, TinkError> {
+        check_nonce_size(nonce)?;
+        let (salt, nonce) = nonce.split_at(SALT_SIZE);
+        let cipher = self.derive(salt);
+        decrypt_with_nonce(&cipher, GenericArray::from_slice(nonce), ct, aad)
+            .map_err(|e| wrap_err("XAesGcm", e).with_kind(ErrorKind::Authentication))
+    }
+}
+
+/// Check that a caller-supplied nonce has the right size.
+fn check_nonce_size(nonce: &[u8]) -> Result<(), TinkError> {
+    if nonce.len() != X_AES_GCM_NONCE_SIZE {
+        return Err(TinkError::new_with_kind(
+            ErrorKind::InvalidArgument,
+            &format!(
+                "XAesGcm: invalid nonce size {} (want {})",
+                nonce.len(),
+                X_AES_GCM_NONCE_SIZE
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Maximum plaintext size.
+fn max_pt_size() -> usize {
+    let x = (isize::MAX as usize) - X_AES_GCM_NONCE_SIZE - AES_GCM_TAG_SIZE;
+    std::cmp::min(x, MAX_X_AES_GCM_PLAINTEXT_SIZE)
+}
@@ -0,0 +1,126 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Key manager for XAES-256-GCM keys.
+
+use crate::subtle;
+use prost::Message;
+use tink::{utils::wrap_err, TinkError};
+
+/// Maximal version of XAES-256-GCM keys.
+pub const X_AES_GCM_KEY_VERSION: u32 = 0;
+/// Type URL of XAES-256-GCM keys that Tink supports.
+pub const X_AES_GCM_TYPE_URL: &str = "type.googleapis.com/google.crypto.tink.XAesGcmKey";
+/// The only salt size supported for XAES-256-GCM keys, which gives a 192-bit nonce.
+const X_AES_GCM_SALT_SIZE: u32 = 12;
+
+/// `XAesGcmKeyManager` is an implementation of the `tink::registry::KeyManager` trait.
+/// It generates new [`XAesGcmKey`](tink::proto::XAesGcmKey) keys and produces new instances of
+/// [`subtle::XAesGcm`].
+#[derive(Default)]
+pub(crate) struct XAesGcmKeyManager {}
+
+impl tink::registry::KeyManager for XAesGcmKeyManager {
+    /// Create a [`subtle::XAesGcm`] for the given serialized [`tink::proto::XAesGcmKey`].
+    fn primitive(&self, serialized_key: &[u8]) -> Result<tink::Primitive, TinkError> {
+        Ok(tink::Primitive::Aead(Box::new(new_x_aes_gcm(
+            serialized_key,
+        )?)))
+    }
+
+    /// Create a new key according to specification the given serialized
+    /// [`tink::proto::XAesGcmKeyFormat`].
+    fn new_key(&self, serialized_key_format: &[u8]) -> Result<Vec<u8>, TinkError> {
+        if serialized_key_format.is_empty() {
+            return Err("XAesGcmKeyManager: invalid key format".into());
+        }
+        let key_format = tink::proto::XAesGcmKeyFormat::decode(serialized_key_format)
+            .map_err(|e| wrap_err("XAesGcmKeyManager: invalid key format", e))?;
+        validate_params(key_format.params.as_ref())
+            .map_err(|e| wrap_err("XAesGcmKeyManager: invalid key format", e))?;
+        let key = tink::proto::XAesGcmKey {
+            version: X_AES_GCM_KEY_VERSION,
+            params: key_format.params.clone(),
+            key_value: tink::subtle::random::get_random_bytes(subtle::X_AES_GCM_KEY_SIZE),
+        };
+        let mut sk = Vec::new();
+        key.encode(&mut sk)
+            .map_err(|e| wrap_err("XAesGcmKeyManager: failed to encode new key", e))?;
+        Ok(sk)
+    }
+
+    fn type_url(&self) -> &'static str {
+        X_AES_GCM_TYPE_URL
+    }
+
+    fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
+        tink::proto::key_data::KeyMaterialType::Symmetric
+    }
+
+    fn version(&self) -> u32 {
+        X_AES_GCM_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "Aead"
+    }
+
+    fn fips_compatible(&self) -> bool {
+        true
+    }
+}
+
+/// Create a [`subtle::XAesGcm`] for the given serialized [`tink::proto::XAesGcmKey`].
+pub(crate) fn new_x_aes_gcm(serialized_key: &[u8]) -> Result<subtle::XAesGcm, TinkError> {
+    if serialized_key.is_empty() {
+        return Err("XAesGcmKeyManager: invalid key".into());
+    }
+    let key = tink::proto::XAesGcmKey::decode(serialized_key)
+        .map_err(|e| wrap_err("XAesGcmKeyManager: invalid key", e))?;
+    validate_key(&key)?;
+    subtle::XAesGcm::new(&key.key_value)
+        .map_err(|e| wrap_err("XAesGcmKeyManager: cannot create new primitive", e))
+}
+
+/// Validate the given [`tink::proto::XAesGcmKey`].
+fn validate_key(key: &tink::proto::XAesGcmKey) -> Result<(), TinkError> {
+    tink::keyset::validate_key_version(key.version, X_AES_GCM_KEY_VERSION)
+        .map_err(|e| wrap_err("XAesGcmKeyManager", e))?;
+    validate_params(key.params.as_ref()).map_err(|e| wrap_err("XAesGcmKeyManager", e))?;
+    let key_size = key.key_value.len();
+    if key_size != subtle::X_AES_GCM_KEY_SIZE {
+        return Err(format!(
+            "XAesGcmKeyManager: invalid key size {} (want {})",
+            key_size,
+            subtle::X_AES_GCM_KEY_SIZE
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Validate the given [`tink::proto::XAesGcmParams`].
+fn validate_params(params: Option<&tink::proto::XAesGcmParams>) -> Result<(), TinkError> {
+    match params {
+        None => Err("no params".into()),
+        Some(params) if params.salt_size != X_AES_GCM_SALT_SIZE => Err(format!(
+            "unsupported salt size {} (want {})",
+            params.salt_size, X_AES_GCM_SALT_SIZE
+        )
+        .into()),
+        Some(_) => Ok(()),
+    }
+}
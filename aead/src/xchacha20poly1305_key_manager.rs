@@ -36,19 +36,9 @@ impl tink::registry::KeyManager for XChaCha20Poly1305KeyManager {
     /// Create a [`subtle::XChaCha20Poly1305`] for the given serialized
     /// [`tink::proto::XChaCha20Poly1305Key`].
     fn primitive(&self, serialized_key: &[u8]) -> Result<tink::Primitive, TinkError> {
-        if serialized_key.is_empty() {
-            return Err("XChaCha20Poly1305KeyManager: invalid key".into());
-        }
-        let key = tink::proto::XChaCha20Poly1305Key::decode(serialized_key)
-            .map_err(|e| wrap_err("XChaCha20Poly1305KeyManager: invalid key", e))?;
-        validate_key(&key)?;
-        match subtle::XChaCha20Poly1305::new(&key.key_value) {
-            Ok(p) => Ok(tink::Primitive::Aead(Box::new(p))),
-            Err(e) => Err(wrap_err(
-                "XChaCha20Poly1305KeyManager: cannot create new primitive",
-                e,
-            )),
-        }
+        Ok(tink::Primitive::Aead(Box::new(new_xchacha20poly1305(
+            serialized_key,
+        )?)))
     }
 
     /// Create a new key, ignoring the specification in the given serialized key format
@@ -68,6 +58,14 @@ impl tink::registry::KeyManager for XChaCha20Poly1305KeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
+
+    fn version(&self) -> u32 {
+        X_CHA_CHA20_POLY1305_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "Aead"
+    }
 }
 
 fn new_x_cha_cha20_poly1305_key() -> tink::proto::XChaCha20Poly1305Key {
@@ -78,6 +76,25 @@ fn new_x_cha_cha20_poly1305_key() -> tink::proto::XChaCha20Poly1305Key {
     }
 }
 
+/// Create a [`subtle::XChaCha20Poly1305`] for the given serialized
+/// [`tink::proto::XChaCha20Poly1305Key`].
+pub(crate) fn new_xchacha20poly1305(
+    serialized_key: &[u8],
+) -> Result<subtle::XChaCha20Poly1305, TinkError> {
+    if serialized_key.is_empty() {
+        return Err("XChaCha20Poly1305KeyManager: invalid key".into());
+    }
+    let key = tink::proto::XChaCha20Poly1305Key::decode(serialized_key)
+        .map_err(|e| wrap_err("XChaCha20Poly1305KeyManager: invalid key", e))?;
+    validate_key(&key)?;
+    subtle::XChaCha20Poly1305::new(&key.key_value).map_err(|e| {
+        wrap_err(
+            "XChaCha20Poly1305KeyManager: cannot create new primitive",
+            e,
+        )
+    })
+}
+
 /// Validates the given [`tink::proto::XChaCha20Poly1305Key`].
 fn validate_key(key: &tink::proto::XChaCha20Poly1305Key) -> Result<(), TinkError> {
     tink::keyset::validate_key_version(key.version, X_CHA_CHA20_POLY1305_KEY_VERSION)
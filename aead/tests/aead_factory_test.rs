@@ -14,6 +14,7 @@
 //
 ////////////////////////////////////////////////////////////////////////////////
 
+use prost::Message;
 use tink::{proto::OutputPrefixType, subtle::random::get_random_bytes, utils::wrap_err, TinkError};
 use tink_aead::subtle;
 
@@ -140,7 +141,7 @@ fn test_factory_with_invalid_primitive_set_type() {
         .expect("failed to build keyset.Handle");
 
     assert!(
-        tink_aead::new(&wrong_kh).is_err(),
+        matches!(tink_aead::new(&wrong_kh), Err(e) if e.kind() == tink::ErrorKind::InvalidKeyset),
         "calling new() with wrong keyset::Handle should fail"
     );
 }
@@ -153,3 +154,354 @@ fn test_factory_with_valid_primitive_set_type() {
 
     tink_aead::new(&good_kh).expect("calling new() with good keyset::Handle failed");
 }
+
+#[test]
+fn test_factory_with_config() {
+    tink_aead::init();
+    let kh = tink::keyset::Handle::new(&tink_aead::aes128_gcm_key_template()).unwrap();
+
+    // A configuration with a different key manager for the same key type.
+    let mut config = tink::registry::Registry::new();
+    config
+        .register_key_manager(std::sync::Arc::new(
+            tink_testutil::DummyAeadKeyManager::default(),
+        ))
+        .unwrap();
+    let a = tink_aead::new_with_config(&kh, &config).unwrap();
+    assert_eq!(
+        a.encrypt(b"plaintext", b"aad").unwrap_err().to_string(),
+        "dummy aead encrypt"
+    );
+
+    // The global registry is unaffected.
+    let a = tink_aead::new(&kh).unwrap();
+    a.encrypt(b"plaintext", b"aad").unwrap();
+
+    // A configuration without a key manager for the key type.
+    assert!(
+        matches!(tink_aead::new_with_config(&kh, &tink::registry::Registry::new()), Err(e) if e.kind() == tink::ErrorKind::Unsupported),
+        "expect an Unsupported error for a configuration without the key type"
+    );
+}
+
+#[test]
+fn test_factory_decrypt_error_kinds() {
+    tink_aead::init();
+    let kh = tink::keyset::Handle::new(&tink_aead::aes128_gcm_key_template()).unwrap();
+    let a = tink_aead::new(&kh).unwrap();
+    let ct = a.encrypt(b"plaintext", b"aad").unwrap();
+
+    // Ciphertext for a key in the keyset that fails to authenticate.
+    let result = a.decrypt(&ct, b"other aad");
+    assert_eq!(result.unwrap_err().kind(), tink::ErrorKind::Authentication);
+    let mut modified = ct.clone();
+    modified[ct.len() - 1] ^= 0x01;
+    let result = a.decrypt(&modified, b"aad");
+    assert_eq!(result.unwrap_err().kind(), tink::ErrorKind::Authentication);
+
+    // Ciphertext for a key that is not in the keyset.
+    let other_kh = tink::keyset::Handle::new(&tink_aead::aes128_gcm_key_template()).unwrap();
+    let other = tink_aead::new(&other_kh).unwrap();
+    let result = other.decrypt(&ct, b"aad");
+    assert_eq!(result.unwrap_err().kind(), tink::ErrorKind::KeyNotFound);
+}
+
+#[test]
+fn test_factory_primitive_shared_across_threads() {
+    tink_aead::init();
+    let kh = tink::keyset::Handle::new(&tink_aead::aes128_gcm_key_template())
+        .expect("failed to build keyset::Handle");
+    let a: std::sync::Arc<dyn tink::Aead> =
+        tink_aead::new(&kh).expect("tink_aead::new failed").into();
+
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            let a = a.clone();
+            std::thread::spawn(move || {
+                let pt = format!("plaintext {}", i);
+                let ct = a.encrypt(pt.as_bytes(), b"aad")?;
+                a.decrypt(&ct, b"aad")
+                    .map(|decrypted| (decrypted, pt.into_bytes()))
+            })
+        })
+        .collect();
+    for t in threads {
+        let (decrypted, pt) = t.join().unwrap().expect("encryption roundtrip failed");
+        assert_eq!(decrypted, pt);
+    }
+}
+
+#[test]
+fn test_factory_monitoring() {
+    tink_aead::init();
+    let client = tink::monitoring::InMemoryClient::new();
+    tink::monitoring::register_client(std::sync::Arc::new(client.clone()));
+
+    let keyset = tink_testutil::new_test_aes_gcm_keyset(OutputPrefixType::Tink);
+    let primary_id = keyset.primary_key_id;
+    let raw_key = keyset.key[1].clone();
+    let mut annotations = std::collections::HashMap::new();
+    annotations.insert("service".to_string(), "test_factory_monitoring".to_string());
+    let keyset_handle = tink::keyset::insecure::new_handle(keyset)
+        .unwrap()
+        .with_annotations(annotations.clone());
+    assert_eq!(keyset_handle.annotations(), &annotations);
+
+    let ps = keyset_handle.primitives().unwrap();
+    assert_eq!(ps.annotations, annotations);
+    let info = tink::monitoring::KeysetInfo::from_primitive_set(&ps);
+    assert_eq!(info.primary_key_id, primary_id);
+    assert_eq!(info.entries.len(), 5);
+    assert!(info
+        .entries
+        .iter()
+        .all(|e| e.key_type == tink_testutil::AES_GCM_TYPE_URL));
+
+    let a = tink_aead::new(&keyset_handle).unwrap();
+    let ct = a.encrypt(b"some data", b"aad").unwrap();
+    assert_eq!(a.decrypt(&ct, b"aad").unwrap(), b"some data");
+    assert!(a.decrypt(&ct, b"wrong aad").is_err());
+
+    // Ciphertext from the non-primary RAW key is attributed to that key.
+    let raw_handle = tink::keyset::insecure::new_handle(tink_testutil::new_keyset(
+        raw_key.key_id,
+        vec![raw_key.clone()],
+    ))
+    .unwrap();
+    let raw_ct = tink_aead::new(&raw_handle)
+        .unwrap()
+        .encrypt(b"raw", b"")
+        .unwrap();
+    a.decrypt(&raw_ct, b"").unwrap();
+
+    let events = client.events();
+    assert_eq!(events.len(), 4);
+    assert!(events
+        .iter()
+        .all(|e| e.primitive == "aead" && e.annotations == annotations));
+    assert_eq!(events[0].api_function, "encrypt");
+    assert_eq!(events[0].key_id, Some(primary_id));
+    assert_eq!(events[0].num_bytes, 9);
+    assert_eq!(events[1].api_function, "decrypt");
+    assert_eq!(client.key_count(primary_id), 2);
+    assert_eq!(client.key_count(raw_key.key_id), 1);
+    assert_eq!(client.key_bytes(raw_key.key_id), raw_ct.len());
+    assert_eq!(client.failure_count(), 1);
+
+    // Primitives from handles without annotations are not monitored.
+    client.clear();
+    let unmonitored = tink_aead::new(&raw_handle).unwrap();
+    unmonitored.encrypt(b"data", b"").unwrap();
+    assert!(client.events().is_empty());
+}
+
+#[test]
+fn test_factory_with_usage_limits() {
+    tink_aead::init();
+    let keyset = tink_testutil::new_test_aes_gcm_keyset(OutputPrefixType::Tink);
+    let primary_id = keyset.primary_key_id;
+    let keyset_handle = tink::keyset::insecure::new_handle(keyset).unwrap();
+
+    let soft_events = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let events = soft_events.clone();
+    let usage = tink_aead::KeyUsage::new(tink_aead::EncryptionLimits {
+        soft_limit: Some(2),
+        hard_limit: Some(3),
+    })
+    .with_soft_limit_callback(std::sync::Arc::new(move |key_id, count| {
+        events.lock().unwrap().push((key_id, count))
+    }));
+    let a = tink_aead::new_with_usage(&keyset_handle, usage.clone()).unwrap();
+
+    let ct = a.encrypt(b"data", b"").unwrap();
+    assert_eq!(usage.count(primary_id), 1);
+    assert!(!usage.soft_limit_reached(primary_id));
+    a.encrypt(b"data", b"").unwrap();
+    assert!(usage.soft_limit_reached(primary_id));
+    assert!(!usage.hard_limit_reached(primary_id));
+    assert_eq!(*soft_events.lock().unwrap(), vec![(primary_id, 2)]);
+    a.encrypt(b"data", b"").unwrap();
+    assert!(usage.hard_limit_reached(primary_id));
+
+    let result = a.encrypt(b"data", b"");
+    assert!(
+        matches!(&result, Err(e) if e.kind() == tink::ErrorKind::LimitExceeded),
+        "encryption beyond the hard limit should fail"
+    );
+    assert_eq!(usage.count(primary_id), 3);
+    assert_eq!(soft_events.lock().unwrap().len(), 1);
+
+    // Decryption is not limited.
+    assert_eq!(a.decrypt(&ct, b"").unwrap(), b"data");
+
+    // A count restored at the soft limit triggers the callback on the next encryption, once.
+    let soft_events = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let events = soft_events.clone();
+    let usage = tink_aead::KeyUsage::new(tink_aead::EncryptionLimits {
+        soft_limit: Some(2),
+        hard_limit: None,
+    })
+    .with_soft_limit_callback(std::sync::Arc::new(move |key_id, count| {
+        events.lock().unwrap().push((key_id, count))
+    }));
+    usage.set_count(primary_id, 2);
+    let a = tink_aead::new_with_usage(&keyset_handle, usage.clone()).unwrap();
+    a.encrypt(b"data", b"").unwrap();
+    a.encrypt(b"data", b"").unwrap();
+    assert_eq!(*soft_events.lock().unwrap(), vec![(primary_id, 3)]);
+
+    // Counts can be restored from persisted values.
+    let usage = tink_aead::KeyUsage::new(tink_aead::EncryptionLimits {
+        soft_limit: None,
+        hard_limit: Some(tink_aead::AES_GCM_MAX_ENCRYPTIONS),
+    });
+    usage.set_count(primary_id, tink_aead::AES_GCM_MAX_ENCRYPTIONS - 1);
+    let a = tink_aead::new_with_usage(&keyset_handle, usage.clone()).unwrap();
+    a.encrypt(b"data", b"").unwrap();
+    assert!(a.encrypt(b"data", b"").is_err());
+    assert_eq!(usage.counts().len(), 1);
+}
+
+#[test]
+fn test_factory_in_place_and_buffer_apis() {
+    tink_aead::init();
+    let keyset = tink_testutil::new_test_aes_gcm_keyset(OutputPrefixType::Tink);
+    let primary_key = keyset.key[0].clone();
+    let raw_key = keyset.key[1].clone();
+    let expected_prefix = tink::cryptofmt::output_prefix(&primary_key).unwrap();
+    let a = tink_aead::new(&tink::keyset::insecure::new_handle(keyset).unwrap()).unwrap();
+    let pt = b"plaintext".to_vec();
+    let aad = b"aad";
+
+    let mut buf = pt.clone();
+    a.encrypt_in_place(&mut buf, aad).unwrap();
+    assert_eq!(&buf[..expected_prefix.len()], &expected_prefix[..]);
+    assert_eq!(a.decrypt(&buf, aad).unwrap(), pt);
+    a.decrypt_in_place(&mut buf, aad).unwrap();
+    assert_eq!(buf, pt);
+
+    let mut out = vec![0xaa];
+    a.encrypt_to(&pt, aad, &mut out).unwrap();
+    assert_eq!(&out[1..1 + expected_prefix.len()], &expected_prefix[..]);
+    let mut dec = vec![0xbb];
+    a.decrypt_to(&out[1..], aad, &mut dec).unwrap();
+    assert_eq!(&dec[1..], &pt[..]);
+
+    // Ciphertexts from a RAW key in the keyset.
+    let raw_keyset = tink_testutil::new_keyset(raw_key.key_id, vec![raw_key]);
+    let raw_a = tink_aead::new(&tink::keyset::insecure::new_handle(raw_keyset).unwrap()).unwrap();
+    let mut buf = pt.clone();
+    raw_a.encrypt_in_place(&mut buf, aad).unwrap();
+    a.decrypt_in_place(&mut buf, aad).unwrap();
+    assert_eq!(buf, pt);
+
+    // A corrupted ciphertext leaves the buffer unchanged, including its prefix.
+    let mut ct = a.encrypt(&pt, aad).unwrap();
+    let last = ct.len() - 1;
+    ct[last] ^= 1;
+    let mut buf = ct.clone();
+    assert!(
+        matches!(a.decrypt_in_place(&mut buf, aad), Err(e) if e.kind() == tink::ErrorKind::Authentication)
+    );
+    assert_eq!(buf, ct);
+    let mut out = Vec::new();
+    assert!(a.decrypt_to(&ct, aad, &mut out).is_err());
+    assert!(out.is_empty());
+}
+
+#[test]
+fn test_new_with_nonce() {
+    tink_aead::init();
+    let mut raw_template = tink_aead::aes256_gcm_key_template();
+    raw_template.output_prefix_type = OutputPrefixType::Raw as i32;
+    let kh =
+        tink::keyset::Handle::new(&tink_aead::aead_with_nonce_key_template(&raw_template)).unwrap();
+    let a = tink_aead::new_with_nonce(&kh).unwrap();
+    assert_eq!(a.nonce_size(), subtle::AES_GCM_IV_SIZE);
+    let pt = b"plaintext";
+    let aad = b"aad";
+    let nonce = get_random_bytes(a.nonce_size());
+
+    // With a RAW key the output is exactly the ciphertext, which is the same as the safe
+    // primitive produces for the same key once the nonce is prepended.
+    let ct = a.encrypt_with_nonce(&nonce, pt, aad).unwrap();
+    assert_eq!(ct.len(), pt.len() + subtle::AES_GCM_TAG_SIZE);
+    assert_eq!(a.decrypt_with_nonce(&nonce, &ct, aad).unwrap(), pt);
+    let ks = tink::keyset::insecure::keyset_material(&kh);
+    let key =
+        tink::proto::AeadWithNonceKey::decode(ks.key[0].key_data.as_ref().unwrap().value.as_ref())
+            .unwrap();
+    assert_eq!(key.aead_type_url, tink_aead::AES_GCM_TYPE_URL);
+    let mut combined_ct = nonce.clone();
+    combined_ct.extend_from_slice(&ct);
+    let safe_a = tink::registry::primitive(&key.aead_type_url, &key.aead_key).unwrap();
+    match safe_a {
+        tink::Primitive::Aead(safe_a) => assert_eq!(safe_a.decrypt(&combined_ct, aad).unwrap(), pt),
+        _ => panic!("not an AEAD"),
+    }
+
+    // Rotate to a ChaCha20-Poly1305 key with a prefix; ciphertexts from the old RAW key still
+    // decrypt.
+    let mut km = tink::keyset::Manager::new_from_handle(kh);
+    let key_id = km
+        .rotate(&tink_aead::aead_with_nonce_key_template(
+            &tink_aead::cha_cha20_poly1305_key_template(),
+        ))
+        .unwrap();
+    let a2 = tink_aead::new_with_nonce(&km.handle().unwrap()).unwrap();
+    assert_eq!(a2.nonce_size(), 12);
+    let ct2 = a2.encrypt_with_nonce(&nonce, pt, aad).unwrap();
+    let mut expected_prefix = vec![tink::cryptofmt::TINK_START_BYTE];
+    expected_prefix.extend_from_slice(&key_id.to_be_bytes());
+    assert_eq!(&ct2[..expected_prefix.len()], &expected_prefix[..]);
+    assert_eq!(a2.decrypt_with_nonce(&nonce, &ct2, aad).unwrap(), pt);
+    assert_eq!(a2.decrypt_with_nonce(&nonce, &ct, aad).unwrap(), pt);
+    assert!(
+        matches!(a.decrypt_with_nonce(&nonce, &ct2, aad), Err(e) if e.kind() == tink::ErrorKind::Authentication)
+    );
+
+    // XAES-256-GCM keys take 24-byte nonces.
+    let kh = tink::keyset::Handle::new(&tink_aead::aead_with_nonce_key_template(
+        &tink_aead::x_aes256_gcm_192_bit_nonce_no_prefix_key_template(),
+    ))
+    .unwrap();
+    let a3 = tink_aead::new_with_nonce(&kh).unwrap();
+    assert_eq!(a3.nonce_size(), subtle::X_AES_GCM_NONCE_SIZE);
+    let nonce = get_random_bytes(a3.nonce_size());
+    let ct3 = a3.encrypt_with_nonce(&nonce, pt, aad).unwrap();
+    assert_eq!(a3.decrypt_with_nonce(&nonce, &ct3, aad).unwrap(), pt);
+
+    // Keys for AEAD with caller-supplied nonces cannot be used as AEAD keys.
+    assert!(tink_aead::new(&kh).is_err());
+
+    // Key types without nonce control are rejected.
+    let template =
+        tink_aead::aead_with_nonce_key_template(&tink_aead::aes128_ctr_hmac_sha256_key_template());
+    assert!(
+        matches!(tink::keyset::Handle::new(&template), Err(e) if e.kind() == tink::ErrorKind::Unsupported)
+    );
+}
+
+#[test]
+fn test_new_with_nonce_rejects_aead_keysets() {
+    tink_aead::init();
+    // Keysets for AEAD, which rely on random nonces, cannot be used with caller-supplied nonces.
+    for template in &[
+        tink_aead::aes128_gcm_key_template(),
+        tink_aead::aes256_gcm_siv_key_template(),
+        tink_aead::cha_cha20_poly1305_key_template(),
+        tink_aead::x_cha_cha20_poly1305_key_template(),
+        tink_aead::x_aes256_gcm_192_bit_nonce_key_template(),
+    ] {
+        let kh = tink::keyset::Handle::new(template).unwrap();
+        assert!(tink_aead::new(&kh).is_ok());
+        assert!(tink_aead::new_with_nonce(&kh).is_err());
+    }
+}
+
+#[test]
+fn test_aead_with_nonce_wrapper_requires_primary() {
+    use tink::registry::PrimitiveWrapper;
+    let result = tink_aead::AeadWithNonceWrapper.wrap(tink::primitiveset::PrimitiveSet::new());
+    assert!(matches!(result, Err(e) if e.kind() == tink::ErrorKind::InvalidKeyset));
+}
@@ -61,6 +61,44 @@ fn check_aes_gcm_key_template(
     Ok(())
 }
 
+#[test]
+fn test_aes_gcm_committing_key_templates() {
+    for (template, key_size) in &[
+        (tink_aead::aes128_gcm_committing_key_template(), 16),
+        (tink_aead::aes256_gcm_committing_key_template(), 32),
+    ] {
+        assert_eq!(template.type_url, tink_aead::AES_GCM_COMMITTING_TYPE_URL);
+        assert_eq!(
+            template.output_prefix_type,
+            tink::proto::OutputPrefixType::Tink as i32
+        );
+        let key_format =
+            tink::proto::AesGcmCommittingKeyFormat::decode(template.value.as_ref()).unwrap();
+        assert_eq!(key_format.key_size, *key_size);
+        test_encrypt_decrypt(template, tink_aead::AES_GCM_COMMITTING_TYPE_URL).unwrap();
+    }
+}
+
+#[test]
+fn test_x_aes_gcm_key_templates() {
+    for (template, output_prefix_type) in &[
+        (
+            tink_aead::x_aes256_gcm_192_bit_nonce_key_template(),
+            tink::proto::OutputPrefixType::Tink,
+        ),
+        (
+            tink_aead::x_aes256_gcm_192_bit_nonce_no_prefix_key_template(),
+            tink::proto::OutputPrefixType::Raw,
+        ),
+    ] {
+        assert_eq!(template.type_url, tink_aead::X_AES_GCM_TYPE_URL);
+        assert_eq!(template.output_prefix_type, *output_prefix_type as i32);
+        let key_format = tink::proto::XAesGcmKeyFormat::decode(template.value.as_ref()).unwrap();
+        assert_eq!(key_format.params.unwrap().salt_size, 12);
+        test_encrypt_decrypt(template, tink_aead::X_AES_GCM_TYPE_URL).unwrap();
+    }
+}
+
 #[test]
 fn test_aes_gcm_siv_key_templates() {
     // AES-GCM-SIV 128 bit
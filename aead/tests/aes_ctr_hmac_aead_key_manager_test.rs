@@ -38,12 +38,12 @@ fn test_new_key_multiple_times() {
         keys.insert(key.aes_ctr_key.as_ref().unwrap().key_value.clone());
         keys.insert(key.hmac_key.as_ref().unwrap().key_value.clone());
         assert_eq!(
-            key.aes_ctr_key.unwrap().key_value.len(),
+            key.aes_ctr_key.as_ref().unwrap().key_value.len(),
             16,
             "unexpected AES key size"
         );
         assert_eq!(
-            key.hmac_key.unwrap().key_value.len(),
+            key.hmac_key.as_ref().unwrap().key_value.len(),
             32,
             "unexpected HMAC key size"
         );
@@ -0,0 +1,150 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+use prost::Message;
+use std::collections::HashSet;
+use tink::{subtle::random::get_random_bytes, TinkError};
+use tink_aead::{AES_GCM_COMMITTING_KEY_VERSION, AES_GCM_COMMITTING_TYPE_URL};
+use tink_testutil::proto_encode;
+
+const KEY_SIZES: &[u32] = &[16, 32];
+
+#[test]
+fn test_aes_gcm_committing_get_primitive_basic() {
+    tink_aead::init();
+    let key_manager = tink::registry::get_key_manager(AES_GCM_COMMITTING_TYPE_URL)
+        .expect("cannot obtain AES-GCM committing key manager");
+    assert_eq!(key_manager.type_url(), AES_GCM_COMMITTING_TYPE_URL);
+    assert_eq!(
+        key_manager.key_material_type(),
+        tink::proto::key_data::KeyMaterialType::Symmetric
+    );
+    assert!(key_manager.does_support(AES_GCM_COMMITTING_TYPE_URL));
+    assert!(!key_manager.does_support(tink_testutil::AES_GCM_TYPE_URL));
+    for key_size in KEY_SIZES {
+        let key = new_key(AES_GCM_COMMITTING_KEY_VERSION, *key_size);
+        let p = key_manager.primitive(&proto_encode(&key)).unwrap();
+        validate_primitive(p).unwrap();
+    }
+}
+
+#[test]
+fn test_aes_gcm_committing_get_primitive_with_invalid_input() {
+    tink_aead::init();
+    let key_manager = tink::registry::get_key_manager(AES_GCM_COMMITTING_TYPE_URL).unwrap();
+    let invalid_keys = vec![
+        // not an AesGcmCommittingKey
+        proto_encode(&new_key_format(32)),
+        // bad key size
+        proto_encode(&new_key(AES_GCM_COMMITTING_KEY_VERSION, 17)),
+        proto_encode(&new_key(AES_GCM_COMMITTING_KEY_VERSION, 25)),
+        proto_encode(&new_key(AES_GCM_COMMITTING_KEY_VERSION, 33)),
+        // bad version
+        proto_encode(&new_key(AES_GCM_COMMITTING_KEY_VERSION + 1, 16)),
+        // empty array
+        vec![],
+    ];
+    for (i, serialized_key) in invalid_keys.iter().enumerate() {
+        assert!(
+            key_manager.primitive(serialized_key).is_err(),
+            "expect an error in test case {}",
+            i
+        );
+    }
+}
+
+#[test]
+fn test_aes_gcm_committing_new_key() {
+    tink_aead::init();
+    let key_manager = tink::registry::get_key_manager(AES_GCM_COMMITTING_TYPE_URL).unwrap();
+    for key_size in KEY_SIZES {
+        let serialized_format = proto_encode(&new_key_format(*key_size));
+        let mut keys = HashSet::new();
+        for _ in 0..10 {
+            let serialized_key = key_manager.new_key(&serialized_format).unwrap();
+            let key = tink::proto::AesGcmCommittingKey::decode(serialized_key.as_ref()).unwrap();
+            assert_eq!(key.version, AES_GCM_COMMITTING_KEY_VERSION);
+            assert_eq!(key.key_value.len(), *key_size as usize);
+            validate_primitive(key_manager.primitive(&serialized_key).unwrap()).unwrap();
+            keys.insert(serialized_key);
+        }
+        assert_eq!(keys.len(), 10, "key is repeated");
+    }
+}
+
+#[test]
+fn test_aes_gcm_committing_new_key_with_invalid_input() {
+    tink_aead::init();
+    let key_manager = tink::registry::get_key_manager(AES_GCM_COMMITTING_TYPE_URL).unwrap();
+    let invalid_formats = vec![
+        // not an AesGcmCommittingKeyFormat
+        proto_encode(&new_key(AES_GCM_COMMITTING_KEY_VERSION, 16)),
+        // invalid key size
+        proto_encode(&new_key_format(15)),
+        proto_encode(&new_key_format(23)),
+        proto_encode(&new_key_format(31)),
+        // empty array
+        vec![],
+    ];
+    for (i, serialized_format) in invalid_formats.iter().enumerate() {
+        key_manager
+            .new_key(serialized_format)
+            .expect_err(&format!("expect an error in test case {}", i));
+    }
+}
+
+// Keys in a keyset share no ciphertexts: a ciphertext from one keyset is rejected by a keyset
+// holding a different key, even when both use RAW output prefixes.
+#[test]
+fn test_aes_gcm_committing_keyset_keys_do_not_share_ciphertexts() {
+    tink_aead::init();
+    let mut template = tink_aead::aes256_gcm_committing_key_template();
+    template.output_prefix_type = tink::proto::OutputPrefixType::Raw as i32;
+    let a1 = tink_aead::new(&tink::keyset::Handle::new(&template).unwrap()).unwrap();
+    let a2 = tink_aead::new(&tink::keyset::Handle::new(&template).unwrap()).unwrap();
+    let ct = a1.encrypt(b"plaintext", b"aad").unwrap();
+    assert_eq!(a1.decrypt(&ct, b"aad").unwrap(), b"plaintext");
+    let result = a2.decrypt(&ct, b"aad");
+    assert!(matches!(result, Err(e) if e.kind() == tink::ErrorKind::Authentication));
+}
+
+fn new_key(version: u32, key_size: u32) -> tink::proto::AesGcmCommittingKey {
+    tink::proto::AesGcmCommittingKey {
+        version,
+        key_value: get_random_bytes(key_size as usize),
+    }
+}
+
+fn new_key_format(key_size: u32) -> tink::proto::AesGcmCommittingKeyFormat {
+    tink::proto::AesGcmCommittingKeyFormat {
+        key_size,
+        version: AES_GCM_COMMITTING_KEY_VERSION,
+    }
+}
+
+fn validate_primitive(p: tink::Primitive) -> Result<(), TinkError> {
+    let cipher = match p {
+        tink::Primitive::Aead(p) => p,
+        _ => return Err("key and primitive don't match".into()),
+    };
+    let pt = get_random_bytes(32);
+    let aad = get_random_bytes(32);
+    let ct = cipher.encrypt(&pt, &aad)?;
+    if cipher.decrypt(&ct, &aad)? != pt {
+        return Err("decryption failed".into());
+    }
+    Ok(())
+}
@@ -75,7 +75,7 @@ fn test_aes_gcm_new_key_multiple_times() {
         keys.insert(serialized_key);
 
         let key_data = key_manager.new_key_data(&serialized_format).unwrap();
-        let serialized_key = key_data.value;
+        let serialized_key = key_data.value.clone();
         keys.insert(serialized_key);
     }
     assert_eq!(keys.len(), n_test * 2, "key is repeated");
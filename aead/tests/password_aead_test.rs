@@ -0,0 +1,156 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+use tink::Aead;
+use tink_aead::{PasswordAead, PasswordKdf};
+
+// Cheap parameters, to keep the tests fast.
+const ARGON2ID: PasswordKdf = PasswordKdf::Argon2id {
+    memory_kib: 64,
+    iterations: 1,
+    parallelism: 1,
+};
+const SCRYPT: PasswordKdf = PasswordKdf::Scrypt {
+    log_n: 4,
+    r: 8,
+    p: 1,
+};
+
+#[test]
+fn test_password_aead_roundtrip() {
+    for kdf in &[ARGON2ID, SCRYPT] {
+        let a = PasswordAead::new_with_kdf(b"correct horse battery staple", *kdf).unwrap();
+        for pt_len in &[0, 1, 100] {
+            let pt = tink::subtle::random::get_random_bytes(*pt_len);
+            let ct = a.encrypt(&pt, b"aad").expect("failed to encrypt");
+            assert_eq!(a.decrypt(&ct, b"aad").expect("failed to decrypt"), pt);
+            assert!(a.decrypt(&ct, b"other aad").is_err());
+
+            // Each encryption uses a fresh salt.
+            let ct2 = a.encrypt(&pt, b"aad").unwrap();
+            assert_ne!(ct[..30], ct2[..30]);
+        }
+    }
+}
+
+#[test]
+fn test_password_aead_wrong_password() {
+    for kdf in &[ARGON2ID, SCRYPT] {
+        let a = PasswordAead::new_with_kdf(b"password", *kdf).unwrap();
+        let b = PasswordAead::new_with_kdf(b"passw0rd", *kdf).unwrap();
+        let ct = a.encrypt(b"plaintext", b"").unwrap();
+        assert_eq!(
+            b.decrypt(&ct, b"").unwrap_err().kind(),
+            tink::ErrorKind::Authentication
+        );
+    }
+}
+
+#[test]
+fn test_password_aead_uses_stored_parameters() {
+    let a = PasswordAead::new_with_kdf(b"password", ARGON2ID).unwrap();
+    let b = PasswordAead::new_with_kdf(b"password", SCRYPT).unwrap();
+    let ct = a.encrypt(b"plaintext", b"").unwrap();
+    assert_eq!(b.decrypt(&ct, b"").unwrap(), b"plaintext");
+}
+
+#[test]
+fn test_password_aead_modified_ciphertext() {
+    let a = PasswordAead::new_with_kdf(b"password", SCRYPT).unwrap();
+    let ct = a.encrypt(b"plaintext", b"").unwrap();
+    for i in 0..ct.len() {
+        if (2..14).contains(&i) {
+            // Modified KDF parameters may be valid but expensive, so are covered separately.
+            continue;
+        }
+        let mut modified = ct.clone();
+        modified[i] ^= 0x01;
+        assert!(a.decrypt(&modified, b"").is_err(), "modified byte {}", i);
+    }
+    for len in 0..ct.len() {
+        assert!(a.decrypt(&ct[..len], b"").is_err(), "truncated to {}", len);
+    }
+}
+
+#[test]
+fn test_password_aead_rejects_excessive_parameters() {
+    let a = PasswordAead::new_with_kdf(b"password", ARGON2ID).unwrap();
+    let mut ct = a.encrypt(b"plaintext", b"").unwrap();
+    // Set the Argon2id memory size to 4 TiB.
+    ct[2..6].copy_from_slice(&u32::MAX.to_be_bytes());
+    assert!(a.decrypt(&ct, b"").is_err());
+}
+
+#[test]
+fn test_password_aead_invalid_input() {
+    assert!(PasswordAead::new(b"").is_err());
+    let invalid = [
+        PasswordKdf::Argon2id {
+            memory_kib: 64,
+            iterations: 0,
+            parallelism: 1,
+        },
+        PasswordKdf::Argon2id {
+            memory_kib: 4,
+            iterations: 1,
+            parallelism: 1,
+        },
+        PasswordKdf::Argon2id {
+            memory_kib: u32::MAX,
+            iterations: 1,
+            parallelism: 1,
+        },
+        PasswordKdf::Scrypt {
+            log_n: 0,
+            r: 8,
+            p: 1,
+        },
+        PasswordKdf::Scrypt {
+            log_n: 30,
+            r: 8,
+            p: 1,
+        },
+        PasswordKdf::Scrypt {
+            log_n: 4,
+            r: 0,
+            p: 1,
+        },
+    ];
+    for kdf in &invalid {
+        assert!(
+            PasswordAead::new_with_kdf(b"password", *kdf).is_err(),
+            "{:?} should be rejected",
+            kdf
+        );
+    }
+    assert!(PasswordAead::new(b"password").is_ok());
+    assert!(PasswordAead::new_with_kdf(b"password", PasswordKdf::scrypt()).is_ok());
+}
+
+#[test]
+fn test_password_aead_keyset_roundtrip() {
+    tink_aead::init();
+    let kh = tink::keyset::Handle::new(&tink_aead::aes128_gcm_key_template()).unwrap();
+    let master_key = Box::new(PasswordAead::new_with_kdf(b"password", ARGON2ID).unwrap());
+
+    let mut mem_keyset = tink::keyset::MemReaderWriter::default();
+    kh.write(&mut mem_keyset, master_key.clone()).unwrap();
+    let kh2 = tink::keyset::Handle::read(&mut mem_keyset, master_key).unwrap();
+    assert_eq!(kh.keyset_info(), kh2.keyset_info());
+
+    let wrong_key = Box::new(PasswordAead::new_with_kdf(b"wrong", ARGON2ID).unwrap());
+    assert!(tink::keyset::Handle::read(&mut mem_keyset, wrong_key).is_err());
+}
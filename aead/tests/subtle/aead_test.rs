@@ -33,3 +33,125 @@ fn test_validate_aes_key_size() {
         }
     }
 }
+
+/// Check the in-place and buffer-reusing methods of an AEAD against `encrypt`/`decrypt`.
+fn check_in_place_and_buffer_apis(a: &dyn tink::Aead) {
+    let pt = b"some plaintext to encrypt in place".to_vec();
+    let aad = b"additional data";
+
+    let mut buf = pt.clone();
+    a.encrypt_in_place(&mut buf, aad).unwrap();
+    assert_ne!(buf, pt);
+    assert_eq!(a.decrypt(&buf, aad).unwrap(), pt);
+    a.decrypt_in_place(&mut buf, aad).unwrap();
+    assert_eq!(buf, pt);
+
+    let mut buf = a.encrypt(&pt, aad).unwrap();
+    a.decrypt_in_place(&mut buf, aad).unwrap();
+    assert_eq!(buf, pt);
+
+    // The prefix is written in front of the ciphertext.
+    let mut buf = pt.clone();
+    a.encrypt_in_place_with_prefix(b"prefix", &mut buf, aad)
+        .unwrap();
+    assert_eq!(&buf[..6], b"prefix");
+    assert_eq!(a.decrypt(&buf[6..], aad).unwrap(), pt);
+
+    // Output is appended to existing buffer contents.
+    let mut out = b"header".to_vec();
+    a.encrypt_to(&pt, aad, &mut out).unwrap();
+    assert_eq!(&out[..6], b"header");
+    assert_eq!(a.decrypt(&out[6..], aad).unwrap(), pt);
+    let mut dec = b"header".to_vec();
+    a.decrypt_to(&out[6..], aad, &mut dec).unwrap();
+    assert_eq!(&dec[..6], b"header");
+    assert_eq!(&dec[6..], &pt[..]);
+
+    // Failed decryption leaves the buffers unchanged.
+    let mut ct = a.encrypt(&pt, aad).unwrap();
+    let last = ct.len() - 1;
+    ct[last] ^= 1;
+    let mut buf = ct.clone();
+    assert!(a.decrypt_in_place(&mut buf, aad).is_err());
+    assert_eq!(buf, ct);
+    let mut out = b"header".to_vec();
+    assert!(a.decrypt_to(&ct, aad, &mut out).is_err());
+    assert_eq!(out, b"header");
+    let mut buf = vec![0; 3];
+    assert!(a.decrypt_in_place(&mut buf, aad).is_err());
+    assert_eq!(buf, vec![0; 3]);
+
+    // Empty plaintext.
+    let mut buf = Vec::new();
+    a.encrypt_in_place(&mut buf, aad).unwrap();
+    a.decrypt_in_place(&mut buf, aad).unwrap();
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn test_in_place_and_buffer_apis() {
+    let key = tink::subtle::random::get_random_bytes(32);
+    check_in_place_and_buffer_apis(&tink_aead::subtle::AesGcm::new(&key[..16]).unwrap());
+    check_in_place_and_buffer_apis(&tink_aead::subtle::AesGcm::new(&key).unwrap());
+    check_in_place_and_buffer_apis(&tink_aead::subtle::AesGcmSiv::new(&key[..16]).unwrap());
+    check_in_place_and_buffer_apis(&tink_aead::subtle::AesGcmSiv::new(&key).unwrap());
+    check_in_place_and_buffer_apis(&tink_aead::subtle::ChaCha20Poly1305::new(&key).unwrap());
+    check_in_place_and_buffer_apis(&tink_aead::subtle::XChaCha20Poly1305::new(&key).unwrap());
+    check_in_place_and_buffer_apis(&tink_aead::subtle::XAesGcm::new(&key).unwrap());
+
+    let ctr = tink_aead::subtle::AesCtr::new(&key[..16], 16).unwrap();
+    let mac = tink_mac::subtle::Hmac::new(tink::proto::HashType::Sha256, &key, 16).unwrap();
+    let eta =
+        tink_aead::subtle::EncryptThenAuthenticate::new(Box::new(ctr), Box::new(mac), 16).unwrap();
+    check_in_place_and_buffer_apis(&eta);
+}
+
+fn check_with_nonce<A: tink::Aead + tink_aead::AeadWithNonce>(a: &A) {
+    let pt = tink::subtle::random::get_random_bytes(100);
+    let aad = tink::subtle::random::get_random_bytes(20);
+    let nonce = tink::subtle::random::get_random_bytes(a.nonce_size());
+
+    let ct = a.encrypt_with_nonce(&nonce, &pt, &aad).unwrap();
+    assert_eq!(ct.len(), pt.len() + 16);
+    assert_eq!(a.decrypt_with_nonce(&nonce, &ct, &aad).unwrap(), pt);
+    assert_eq!(
+        ct,
+        a.encrypt_with_nonce(&nonce, &pt, &aad).unwrap(),
+        "encryption with the same nonce differs"
+    );
+
+    // The output is the same as for the random-nonce API, without the nonce.
+    let mut combined_ct = nonce.clone();
+    combined_ct.extend_from_slice(&ct);
+    assert_eq!(a.decrypt(&combined_ct, &aad).unwrap(), pt);
+    let combined_ct = a.encrypt(&pt, &aad).unwrap();
+    let (ct_nonce, ct_rest) = combined_ct.split_at(a.nonce_size());
+    assert_eq!(a.decrypt_with_nonce(ct_nonce, ct_rest, &aad).unwrap(), pt);
+
+    let mut wrong_nonce = nonce.clone();
+    wrong_nonce[0] ^= 0x01;
+    let result = a.decrypt_with_nonce(&wrong_nonce, &ct, &aad);
+    assert!(matches!(result, Err(e) if e.kind() == tink::ErrorKind::Authentication));
+    assert!(a.decrypt_with_nonce(&nonce, &ct, b"wrong aad").is_err());
+    assert!(a.decrypt_with_nonce(&nonce, &ct[..15], &aad).is_err());
+
+    for bad_size in &[0, a.nonce_size() - 1, a.nonce_size() + 1] {
+        let bad_nonce = vec![0; *bad_size];
+        let result = a.encrypt_with_nonce(&bad_nonce, &pt, &aad);
+        assert!(matches!(result, Err(e) if e.kind() == tink::ErrorKind::InvalidArgument));
+        let result = a.decrypt_with_nonce(&bad_nonce, &ct, &aad);
+        assert!(matches!(result, Err(e) if e.kind() == tink::ErrorKind::InvalidArgument));
+    }
+}
+
+#[test]
+fn test_with_nonce() {
+    let key = tink::subtle::random::get_random_bytes(32);
+    check_with_nonce(&tink_aead::subtle::AesGcm::new(&key[..16]).unwrap());
+    check_with_nonce(&tink_aead::subtle::AesGcm::new(&key).unwrap());
+    check_with_nonce(&tink_aead::subtle::AesGcmSiv::new(&key[..16]).unwrap());
+    check_with_nonce(&tink_aead::subtle::AesGcmSiv::new(&key).unwrap());
+    check_with_nonce(&tink_aead::subtle::ChaCha20Poly1305::new(&key).unwrap());
+    check_with_nonce(&tink_aead::subtle::XChaCha20Poly1305::new(&key).unwrap());
+    check_with_nonce(&tink_aead::subtle::XAesGcm::new(&key).unwrap());
+}
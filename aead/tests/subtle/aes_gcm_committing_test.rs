@@ -0,0 +1,212 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+use aes::{cipher::generic_array::GenericArray, Aes128, Aes256, BlockCipher, NewBlockCipher};
+use tink::{proto::HashType, subtle::random::get_random_bytes, Aead, ErrorKind};
+use tink_aead::subtle;
+
+const KEY_SIZES: &[usize] = &[16, 32];
+const OVERHEAD: usize =
+    subtle::AES_GCM_IV_SIZE + subtle::AES_GCM_COMMITMENT_SIZE + subtle::AES_GCM_TAG_SIZE;
+
+#[test]
+fn test_aes_gcm_committing_encrypt_decrypt() {
+    for key_size in KEY_SIZES {
+        let key = get_random_bytes(*key_size);
+        let a = subtle::AesGcmCommitting::new(&key).unwrap();
+        let ad = get_random_bytes(5);
+        for pt_size in 0..75 {
+            let pt = get_random_bytes(pt_size);
+            let ct = a.encrypt(&pt, &ad).unwrap();
+            assert_eq!(ct.len(), pt_size + OVERHEAD);
+            assert_eq!(
+                a.decrypt(&ct, &ad).unwrap(),
+                pt,
+                "decrypted text and plaintext don't match: key_size {}, pt_size {}",
+                key_size,
+                pt_size
+            );
+        }
+    }
+}
+
+#[test]
+fn test_aes_gcm_committing_key_size() {
+    for key_size in &[0, 1, 15, 17, 24, 31, 33, 64] {
+        assert!(subtle::AesGcmCommitting::new(&vec![0; *key_size]).is_err());
+    }
+}
+
+#[test]
+fn test_aes_gcm_committing_modify_ciphertext() {
+    let key = get_random_bytes(16);
+    let a = subtle::AesGcmCommitting::new(&key).unwrap();
+    let ad = get_random_bytes(33);
+    let pt = get_random_bytes(32);
+    let ct = a.encrypt(&pt, &ad).unwrap();
+
+    // Flipping any bit of the nonce, commitment, ciphertext or tag fails.
+    for i in 0..ct.len() {
+        for j in 0..8 {
+            let mut modified_ct = ct.clone();
+            modified_ct[i] ^= 1 << j;
+            let result = a.decrypt(&modified_ct, &ad);
+            assert!(
+                matches!(result, Err(e) if e.kind() == ErrorKind::Authentication),
+                "decrypting modified ciphertext should fail: i {}, j {}",
+                i,
+                j
+            );
+        }
+    }
+    // Truncating the ciphertext fails.
+    for length in 0..ct.len() {
+        assert!(a.decrypt(&ct[..length], &ad).is_err());
+    }
+    // Modifying the additional data fails.
+    let mut modified_ad = ad.clone();
+    modified_ad[0] ^= 1;
+    assert!(a.decrypt(&ct, &modified_ad).is_err());
+}
+
+#[test]
+fn test_aes_gcm_committing_wrong_key() {
+    for key_size in KEY_SIZES {
+        let a1 = subtle::AesGcmCommitting::new(&get_random_bytes(*key_size)).unwrap();
+        let a2 = subtle::AesGcmCommitting::new(&get_random_bytes(*key_size)).unwrap();
+        let ct = a1.encrypt(b"plaintext", b"aad").unwrap();
+        let result = a2.decrypt(&ct, b"aad");
+        assert!(matches!(result, Err(e) if e.kind() == ErrorKind::Authentication));
+    }
+}
+
+// A two-block AES-GCM ciphertext can be built that decrypts (to different plaintexts) under two
+// different keys; check that this is possible for `AesGcm`, but that the commitment tag stops the
+// same ciphertext being accepted under two keys by `AesGcmCommitting`.
+#[test]
+fn test_aes_gcm_committing_multi_key_ciphertext() {
+    use tink_aead::AeadWithNonce;
+    for key_size in KEY_SIZES {
+        let nonce = get_random_bytes(subtle::AES_GCM_IV_SIZE);
+
+        let key1 = get_random_bytes(*key_size);
+        let key2 = get_random_bytes(*key_size);
+        let ct = gcm_multi_key_ciphertext(&key1, &key2, &nonce);
+        let gcm1 = subtle::AesGcm::new(&key1).unwrap();
+        let gcm2 = subtle::AesGcm::new(&key2).unwrap();
+        let pt1 = gcm1.decrypt_with_nonce(&nonce, &ct, &[]).unwrap();
+        let pt2 = gcm2.decrypt_with_nonce(&nonce, &ct, &[]).unwrap();
+        assert_ne!(pt1, pt2);
+
+        // Repeat for the AES-GCM keys and commitment tags derived for the nonce.
+        let (derived_key1, commitment1) = derive(&key1, &nonce);
+        let (derived_key2, commitment2) = derive(&key2, &nonce);
+        let gcm_ct = gcm_multi_key_ciphertext(&derived_key1, &derived_key2, &nonce);
+        let a1 = subtle::AesGcmCommitting::new(&key1).unwrap();
+        let a2 = subtle::AesGcmCommitting::new(&key2).unwrap();
+        let ct1 = [&nonce[..], &commitment1, &gcm_ct].concat();
+        let ct2 = [&nonce[..], &commitment2, &gcm_ct].concat();
+        a1.decrypt(&ct1, &[]).unwrap();
+        a2.decrypt(&ct2, &[]).unwrap();
+        let result = a2.decrypt(&ct1, &[]);
+        assert!(matches!(result, Err(e) if e.kind() == ErrorKind::Authentication));
+        let result = a1.decrypt(&ct2, &[]);
+        assert!(matches!(result, Err(e) if e.kind() == ErrorKind::Authentication));
+    }
+}
+
+/// Return the per-message AES-GCM key and commitment tag for `key` and `nonce`.
+fn derive(key: &[u8], nonce: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let derived_key = tink::subtle::compute_hkdf(
+        HashType::Sha256,
+        key,
+        nonce,
+        b"tink-rust AesGcmCommitting key",
+        key.len(),
+    )
+    .unwrap();
+    let commitment = tink::subtle::compute_hkdf(
+        HashType::Sha256,
+        key,
+        nonce,
+        b"tink-rust AesGcmCommitting commitment",
+        subtle::AES_GCM_COMMITMENT_SIZE,
+    )
+    .unwrap();
+    (derived_key, commitment)
+}
+
+/// Build a two-block AES-GCM ciphertext (followed by its tag) with no additional data that is
+/// valid under both `key1` and `key2` for the given `nonce`.
+fn gcm_multi_key_ciphertext(key1: &[u8], key2: &[u8], nonce: &[u8]) -> Vec<u8> {
+    let mut j0 = [0u8; 16];
+    j0[..12].copy_from_slice(nonce);
+    j0[15] = 1;
+    let (h1, e1) = (encrypt_block(key1, [0; 16]), encrypt_block(key1, j0));
+    let (h2, e2) = (encrypt_block(key2, [0; 16]), encrypt_block(key2, j0));
+    // Length block: no additional data, 256 bits of ciphertext.
+    let l = 256u128;
+
+    // The tag for ciphertext blocks c1, c2 under key i is c1*hi^3 + c2*hi^2 + l*hi + ei, so pick
+    // c1 at random and solve for the c2 that makes the tags equal.
+    let mut c1 = [0; 16];
+    c1.copy_from_slice(&get_random_bytes(16));
+    let c1 = u128::from_be_bytes(c1);
+    let cube = |h| gf_mul(gf_mul(h, h), h);
+    let rhs = gf_mul(c1, cube(h1) ^ cube(h2)) ^ gf_mul(l, h1 ^ h2) ^ e1 ^ e2;
+    let c2 = gf_mul(rhs, gf_inv(gf_mul(h1, h1) ^ gf_mul(h2, h2)));
+    let tag = gf_mul(c1, cube(h1)) ^ gf_mul(c2, gf_mul(h1, h1)) ^ gf_mul(l, h1) ^ e1;
+    [c1.to_be_bytes(), c2.to_be_bytes(), tag.to_be_bytes()].concat()
+}
+
+/// Encrypt a single AES block.
+fn encrypt_block(key: &[u8], block: [u8; 16]) -> u128 {
+    let mut block = GenericArray::clone_from_slice(&block);
+    match key.len() {
+        16 => Aes128::new(GenericArray::from_slice(key)).encrypt_block(&mut block),
+        32 => Aes256::new(GenericArray::from_slice(key)).encrypt_block(&mut block),
+        l => panic!("invalid AES key size {}", l),
+    }
+    u128::from_be_bytes(block.into())
+}
+
+/// Multiply in GF(2^128) with the GCM bit ordering.
+fn gf_mul(x: u128, y: u128) -> u128 {
+    let mut z = 0;
+    let mut v = y;
+    for i in 0..128 {
+        if (x >> (127 - i)) & 1 == 1 {
+            z ^= v;
+        }
+        v = if v & 1 == 1 {
+            (v >> 1) ^ (0xe1 << 120)
+        } else {
+            v >> 1
+        };
+    }
+    z
+}
+
+/// Invert in GF(2^128), as x^(2^128 - 2).
+fn gf_inv(x: u128) -> u128 {
+    let mut result = 1 << 127;
+    let mut power = gf_mul(x, x);
+    for _ in 1..128 {
+        result = gf_mul(result, power);
+        power = gf_mul(power, power);
+    }
+    result
+}
@@ -166,6 +166,31 @@ fn test_aes_gcm_random_nonce() {
     }
 }
 
+// Test case 3 from the GCM specification.
+#[test]
+fn test_aes_gcm_encrypt_with_nonce() {
+    use tink_aead::AeadWithNonce;
+    let key = hex::decode("feffe9928665731c6d6a8f9467308308").unwrap();
+    let nonce = hex::decode("cafebabefacedbaddecaf888").unwrap();
+    let pt = hex::decode(
+        "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+         1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255",
+    )
+    .unwrap();
+    let want = hex::decode(
+        "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+         21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091473f5985\
+         4d5c2af327cd64a62cf35abd2ba6fab4",
+    )
+    .unwrap();
+
+    let a = subtle::AesGcm::new(&key).unwrap();
+    assert_eq!(a.nonce_size(), subtle::AES_GCM_IV_SIZE);
+    let ct = a.encrypt_with_nonce(&nonce, &pt, &[]).unwrap();
+    assert_eq!(hex::encode(&ct), hex::encode(&want));
+    assert_eq!(a.decrypt_with_nonce(&nonce, &want, &[]).unwrap(), pt);
+}
+
 #[test]
 fn test_aes_gcm_vectors() {
     let filename = "testvectors/aes_gcm_test.json";
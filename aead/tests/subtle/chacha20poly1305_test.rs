@@ -282,3 +282,25 @@ fn test_cha_cha20_poly1305_wycheproof_vectors() {
         }
     }
 }
+
+#[test]
+fn test_cha_cha20_poly1305_encrypt_with_nonce() {
+    use tink_aead::AeadWithNonce;
+    for (i, test) in CHA_CHA20_POLY1305_TESTS.iter().enumerate() {
+        let key = hex::decode(&test.key).unwrap();
+        let pt = hex::decode(&test.plaintext).unwrap();
+        let aad = hex::decode(&test.aad).unwrap();
+        let nonce = hex::decode(&test.nonce).unwrap();
+        let out = hex::decode(&test.out).unwrap();
+
+        let ca = subtle::ChaCha20Poly1305::new(&key).unwrap();
+        let ct = ca
+            .encrypt_with_nonce(&nonce, &pt, &aad)
+            .unwrap_or_else(|e| panic!("#{}, unexpected encryption error: {:?}", i, e));
+        assert_eq!(hex::encode(&ct), test.out, "#{}, unexpected ciphertext", i);
+        let got = ca
+            .decrypt_with_nonce(&nonce, &out, &aad)
+            .unwrap_or_else(|e| panic!("#{}, unexpected decryption error: {:?}", i, e));
+        assert_eq!(got, pt, "#{}, plaintexts don't match", i);
+    }
+}
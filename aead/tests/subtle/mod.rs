@@ -16,11 +16,13 @@
 
 mod aead_test;
 mod aes_ctr_test;
+mod aes_gcm_committing_test;
 mod aes_gcm_siv_test;
 mod aes_gcm_test;
 mod chacha20poly1305_test;
 mod chacha20poly1305_vectors;
 mod encrypt_then_authenticate_test;
 mod wycheproof;
+mod x_aes_gcm_test;
 mod xchacha20poly1305_test;
 mod xchacha20poly1305_vectors;
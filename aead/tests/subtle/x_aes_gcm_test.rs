@@ -0,0 +1,136 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+use tink::{subtle::random::get_random_bytes, Aead, ErrorKind};
+use tink_aead::{subtle, AeadWithNonce};
+
+struct XAesGcmTest {
+    key: &'static str,
+    nonce: &'static str,
+    plaintext: &'static str,
+    aad: &'static str,
+    out: &'static str,
+}
+
+// Test vectors from https://c2sp.org/XAES-256-GCM.
+const X_AES_GCM_TESTS: &[XAesGcmTest] = &[
+    XAesGcmTest {
+        key: "0101010101010101010101010101010101010101010101010101010101010101",
+        nonce: "4142434445464748494a4b4c4d4e4f505152535455565758",
+        plaintext: "584145532d3235362d47434d",
+        aad: "",
+        out: "ce546ef63c9cc60765923609b33a9a1974e96e52daf2fcf7075e2271",
+    },
+    XAesGcmTest {
+        key: "0303030303030303030303030303030303030303030303030303030303030303",
+        nonce: "4142434445464748494a4b4c4d4e4f505152535455565758",
+        plaintext: "584145532d3235362d47434d",
+        aad: "633273702e6f72672f584145532d3235362d47434d",
+        out: "986ec1832593df5443a179437fd083bf3fdb41abd740a21f71eb769d",
+    },
+];
+
+#[test]
+fn test_x_aes_gcm_vectors() {
+    for (i, test) in X_AES_GCM_TESTS.iter().enumerate() {
+        let key = hex::decode(test.key).unwrap();
+        let nonce = hex::decode(test.nonce).unwrap();
+        let pt = hex::decode(test.plaintext).unwrap();
+        let aad = hex::decode(test.aad).unwrap();
+        let out = hex::decode(test.out).unwrap();
+
+        let x = subtle::XAesGcm::new(&key).unwrap();
+        let ct = x
+            .encrypt_with_nonce(&nonce, &pt, &aad)
+            .unwrap_or_else(|e| panic!("#{}, unexpected encryption error: {:?}", i, e));
+        assert_eq!(hex::encode(&ct), test.out, "#{}, unexpected ciphertext", i);
+        let got = x
+            .decrypt_with_nonce(&nonce, &out, &aad)
+            .unwrap_or_else(|e| panic!("#{}, unexpected decryption error: {:?}", i, e));
+        assert_eq!(got, pt, "#{}, plaintexts don't match", i);
+
+        let mut combined_ct = nonce.clone();
+        combined_ct.extend_from_slice(&out);
+        let got = x
+            .decrypt(&combined_ct, &aad)
+            .unwrap_or_else(|e| panic!("#{}, unexpected decryption error: {:?}", i, e));
+        assert_eq!(got, pt, "#{}, plaintexts don't match", i);
+    }
+}
+
+#[test]
+fn test_x_aes_gcm_encrypt_decrypt() {
+    let key = get_random_bytes(subtle::X_AES_GCM_KEY_SIZE);
+    let x = subtle::XAesGcm::new(&key).unwrap();
+    let aad = get_random_bytes(5);
+    for pt_size in 0..75 {
+        let pt = get_random_bytes(pt_size);
+        let ct = x.encrypt(&pt, &aad).unwrap();
+        assert_eq!(
+            ct.len(),
+            pt_size + subtle::X_AES_GCM_NONCE_SIZE + subtle::AES_GCM_TAG_SIZE
+        );
+        assert_eq!(x.decrypt(&ct, &aad).unwrap(), pt, "pt_size {}", pt_size);
+    }
+}
+
+#[test]
+fn test_x_aes_gcm_invalid_key_size() {
+    for key_size in &[0, 1, 16, 24, 31, 33, 64] {
+        assert!(subtle::XAesGcm::new(&vec![0; *key_size]).is_err());
+    }
+}
+
+#[test]
+fn test_x_aes_gcm_modify_ciphertext() {
+    let x = subtle::XAesGcm::new(&get_random_bytes(subtle::X_AES_GCM_KEY_SIZE)).unwrap();
+    let aad = get_random_bytes(16);
+    let ct = x.encrypt(&get_random_bytes(32), &aad).unwrap();
+
+    // Flipping any bit of the nonce, ciphertext or tag fails.
+    for i in 0..ct.len() {
+        for j in 0..8 {
+            let mut modified_ct = ct.clone();
+            modified_ct[i] ^= 1 << j;
+            let result = x.decrypt(&modified_ct, &aad);
+            assert!(
+                matches!(result, Err(e) if e.kind() == ErrorKind::Authentication),
+                "decrypting modified ciphertext should fail: i {}, j {}",
+                i,
+                j
+            );
+        }
+    }
+    // Truncating the ciphertext fails.
+    for length in 0..ct.len() {
+        assert!(x.decrypt(&ct[..length], &aad).is_err());
+    }
+    // Modifying the additional data fails.
+    assert!(x.decrypt(&ct, b"wrong aad").is_err());
+}
+
+#[test]
+fn test_x_aes_gcm_random_nonce() {
+    let x = subtle::XAesGcm::new(&get_random_bytes(subtle::X_AES_GCM_KEY_SIZE)).unwrap();
+    let mut nonces = std::collections::HashSet::new();
+    for _ in 0..100 {
+        let ct = x.encrypt(&[], &[]).unwrap();
+        assert!(
+            nonces.insert(ct[..subtle::X_AES_GCM_NONCE_SIZE].to_vec()),
+            "repeated nonce"
+        );
+    }
+}
@@ -283,3 +283,26 @@ fn test_x_cha_cha20_poly1305_wycheproof_vectors() {
         }
     }
 }
+
+#[test]
+fn test_x_cha_cha20_poly1305_encrypt_with_nonce() {
+    use tink_aead::AeadWithNonce;
+    for (i, test) in X_CHA_CHA20_POLY1305_TESTS.iter().enumerate() {
+        let key = hex::decode(&test.key).unwrap();
+        let pt = hex::decode(&test.plaintext).unwrap();
+        let aad = hex::decode(&test.aad).unwrap();
+        let nonce = hex::decode(&test.nonce).unwrap();
+        let mut out = hex::decode(&test.out).unwrap();
+        out.extend_from_slice(&hex::decode(&test.tag).unwrap());
+
+        let x = subtle::XChaCha20Poly1305::new(&key).unwrap();
+        let ct = x
+            .encrypt_with_nonce(&nonce, &pt, &aad)
+            .unwrap_or_else(|e| panic!("#{}, unexpected encryption error: {:?}", i, e));
+        assert_eq!(ct, out, "#{}, unexpected ciphertext", i);
+        let got = x
+            .decrypt_with_nonce(&nonce, &out, &aad)
+            .unwrap_or_else(|e| panic!("#{}, unexpected decryption error: {:?}", i, e));
+        assert_eq!(got, pt, "#{}, plaintexts don't match", i);
+    }
+}
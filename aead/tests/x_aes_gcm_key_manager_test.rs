@@ -0,0 +1,138 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+use prost::Message;
+use std::collections::HashSet;
+use tink::{subtle::random::get_random_bytes, TinkError};
+use tink_aead::{X_AES_GCM_KEY_VERSION, X_AES_GCM_TYPE_URL};
+use tink_testutil::proto_encode;
+
+#[test]
+fn test_x_aes_gcm_get_primitive_basic() {
+    tink_aead::init();
+    let key_manager = tink::registry::get_key_manager(X_AES_GCM_TYPE_URL)
+        .expect("cannot obtain XAES-256-GCM key manager");
+    assert_eq!(key_manager.type_url(), X_AES_GCM_TYPE_URL);
+    assert_eq!(
+        key_manager.key_material_type(),
+        tink::proto::key_data::KeyMaterialType::Symmetric
+    );
+    assert!(key_manager.does_support(X_AES_GCM_TYPE_URL));
+    assert!(!key_manager.does_support(tink_testutil::AES_GCM_TYPE_URL));
+    assert!(key_manager.fips_compatible());
+    let key = new_key(X_AES_GCM_KEY_VERSION, 12, 32);
+    let p = key_manager.primitive(&proto_encode(&key)).unwrap();
+    validate_primitive(p).unwrap();
+}
+
+#[test]
+fn test_x_aes_gcm_get_primitive_with_invalid_input() {
+    tink_aead::init();
+    let key_manager = tink::registry::get_key_manager(X_AES_GCM_TYPE_URL).unwrap();
+    let mut no_params_key = new_key(X_AES_GCM_KEY_VERSION, 12, 32);
+    no_params_key.params = None;
+    let invalid_keys = vec![
+        // bad key size
+        proto_encode(&new_key(X_AES_GCM_KEY_VERSION, 12, 16)),
+        proto_encode(&new_key(X_AES_GCM_KEY_VERSION, 12, 33)),
+        // bad version
+        proto_encode(&new_key(X_AES_GCM_KEY_VERSION + 1, 12, 32)),
+        // unsupported salt size
+        proto_encode(&new_key(X_AES_GCM_KEY_VERSION, 8, 32)),
+        proto_encode(&new_key(X_AES_GCM_KEY_VERSION, 16, 32)),
+        // missing params
+        proto_encode(&no_params_key),
+        // empty array
+        vec![],
+    ];
+    for (i, serialized_key) in invalid_keys.iter().enumerate() {
+        assert!(
+            key_manager.primitive(serialized_key).is_err(),
+            "expect an error in test case {}",
+            i
+        );
+    }
+}
+
+#[test]
+fn test_x_aes_gcm_new_key() {
+    tink_aead::init();
+    let key_manager = tink::registry::get_key_manager(X_AES_GCM_TYPE_URL).unwrap();
+    let serialized_format = proto_encode(&new_key_format(12));
+    let mut keys = HashSet::new();
+    for _ in 0..10 {
+        let serialized_key = key_manager.new_key(&serialized_format).unwrap();
+        let key = tink::proto::XAesGcmKey::decode(serialized_key.as_ref()).unwrap();
+        assert_eq!(key.version, X_AES_GCM_KEY_VERSION);
+        assert_eq!(key.params.as_ref().unwrap().salt_size, 12);
+        assert_eq!(key.key_value.len(), 32);
+        validate_primitive(key_manager.primitive(&serialized_key).unwrap()).unwrap();
+        keys.insert(serialized_key);
+    }
+    assert_eq!(keys.len(), 10, "key is repeated");
+}
+
+#[test]
+fn test_x_aes_gcm_new_key_with_invalid_input() {
+    tink_aead::init();
+    let key_manager = tink::registry::get_key_manager(X_AES_GCM_TYPE_URL).unwrap();
+    let mut no_params_format = new_key_format(12);
+    no_params_format.params = None;
+    let invalid_formats = vec![
+        // unsupported salt size
+        proto_encode(&new_key_format(0)),
+        proto_encode(&new_key_format(8)),
+        proto_encode(&new_key_format(13)),
+        // missing params
+        proto_encode(&no_params_format),
+        // empty array
+        vec![],
+    ];
+    for (i, serialized_format) in invalid_formats.iter().enumerate() {
+        key_manager
+            .new_key(serialized_format)
+            .expect_err(&format!("expect an error in test case {}", i));
+    }
+}
+
+fn new_key(version: u32, salt_size: u32, key_size: usize) -> tink::proto::XAesGcmKey {
+    tink::proto::XAesGcmKey {
+        version,
+        params: Some(tink::proto::XAesGcmParams { salt_size }),
+        key_value: get_random_bytes(key_size),
+    }
+}
+
+fn new_key_format(salt_size: u32) -> tink::proto::XAesGcmKeyFormat {
+    tink::proto::XAesGcmKeyFormat {
+        version: X_AES_GCM_KEY_VERSION,
+        params: Some(tink::proto::XAesGcmParams { salt_size }),
+    }
+}
+
+fn validate_primitive(p: tink::Primitive) -> Result<(), TinkError> {
+    let cipher = match p {
+        tink::Primitive::Aead(p) => p,
+        _ => return Err("key and primitive don't match".into()),
+    };
+    let pt = get_random_bytes(32);
+    let aad = get_random_bytes(32);
+    let ct = cipher.encrypt(&pt, &aad)?;
+    if cipher.decrypt(&ct, &aad)? != pt {
+        return Err("decryption failed".into());
+    }
+    Ok(())
+}
@@ -28,6 +28,28 @@ fn setup(kt: tink::proto::KeyTemplate) -> (Box<dyn tink::DeterministicAead>, Vec
     (a, ct)
 }
 
+/// Build a deterministic AEAD from a keyset with several rotated keys, returning ciphertexts
+/// produced by the primary key and by a RAW key.
+fn setup_rotated(
+    kt: tink::proto::KeyTemplate,
+) -> (Box<dyn tink::DeterministicAead>, Vec<u8>, Vec<u8>) {
+    tink_daead::init();
+    let mut raw_kt = kt.clone();
+    raw_kt.output_prefix_type = tink::proto::OutputPrefixType::Raw as i32;
+    let mut ksm = tink::keyset::Manager::new();
+    ksm.rotate(&raw_kt).unwrap();
+    let raw_ct = tink_daead::new(&ksm.handle().unwrap())
+        .unwrap()
+        .encrypt_deterministically(MSG, AAD)
+        .unwrap();
+    for _ in 0..8 {
+        ksm.rotate(&kt).unwrap();
+    }
+    let a = tink_daead::new(&ksm.handle().unwrap()).unwrap();
+    let ct = a.encrypt_deterministically(MSG, AAD).unwrap();
+    (a, ct, raw_ct)
+}
+
 #[bench]
 fn bench_aes_siv_encrypt(b: &mut Bencher) {
     let (d, _ct) = setup(tink_daead::aes_siv_key_template());
@@ -39,3 +61,15 @@ fn bench_aes_siv_decrypt(b: &mut Bencher) {
     let (d, ct) = setup(tink_daead::aes_siv_key_template());
     b.iter(|| d.decrypt_deterministically(&ct, AAD).unwrap());
 }
+
+#[bench]
+fn bench_rotated_keyset_decrypt(b: &mut Bencher) {
+    let (d, ct, _raw_ct) = setup_rotated(tink_daead::aes_siv_key_template());
+    b.iter(|| d.decrypt_deterministically(&ct, AAD).unwrap());
+}
+
+#[bench]
+fn bench_rotated_keyset_decrypt_raw(b: &mut Bencher) {
+    let (d, _ct, raw_ct) = setup_rotated(tink_daead::aes_siv_key_template());
+    b.iter(|| d.decrypt_deterministically(&raw_ct, AAD).unwrap());
+}
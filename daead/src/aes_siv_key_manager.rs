@@ -82,6 +82,14 @@ impl KeyManager for AesSivKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
+
+    fn version(&self) -> u32 {
+        AES_SIV_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "DeterministicAead"
+    }
 }
 
 /// Validate the given [`AesSivKey`](tink::proto::AesSivKey).
@@ -17,7 +17,11 @@
 //! Provides an implementation of deterministic AEAD using a set of underlying implementations.
 
 use std::sync::Arc;
-use tink::utils::{wrap_err, TinkError};
+use tink::{
+    monitoring::Logger,
+    utils::{wrap_err, TinkError},
+    ErrorKind,
+};
 
 /// Return a [`tink::DeterministicAead`] primitive from the given keyset handle.
 pub fn new(h: &tink::keyset::Handle) -> Result<Box<dyn tink::DeterministicAead>, TinkError> {
@@ -38,87 +42,184 @@ pub fn new_with_key_manager(
     Ok(Box::new(ret))
 }
 
+/// Return a [`tink::DeterministicAead`] primitive from the given keyset handle, using the key
+/// managers in the given registry configuration.
+pub fn new_with_config(
+    h: &tink::keyset::Handle,
+    config: &tink::registry::Registry,
+) -> Result<Box<dyn tink::DeterministicAead>, TinkError> {
+    let ps = h
+        .primitives_with_config(config)
+        .map_err(|e| wrap_err("daead::factory: cannot obtain primitive set", e))?;
+
+    let ret = WrappedDeterministicAead::new(ps)?;
+    Ok(Box::new(ret))
+}
+
+/// [`PrimitiveWrapper`](tink::registry::PrimitiveWrapper) that combines the primitives for the keys
+/// in a keyset into a single [`tink::DeterministicAead`] primitive. Registered by
+/// [`init`](crate::init).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeterministicAeadWrapper;
+
+impl tink::registry::PrimitiveWrapper for DeterministicAeadWrapper {
+    type Primitive = dyn tink::DeterministicAead;
+
+    fn wrap(
+        &self,
+        ps: tink::primitiveset::PrimitiveSet,
+    ) -> Result<Box<dyn tink::DeterministicAead>, TinkError> {
+        Ok(Box::new(WrappedDeterministicAead::new(ps)?))
+    }
+}
+
 /// A [`tink::DeterministicAead`] implementation that uses the underlying primitive set
 /// for deterministic encryption and decryption.
 #[derive(Clone)]
 struct WrappedDeterministicAead {
     ps: tink::primitiveset::PrimitiveSet,
+    encrypt_logger: Arc<dyn Logger>,
+    decrypt_logger: Arc<dyn Logger>,
 }
 
 impl WrappedDeterministicAead {
     fn new(ps: tink::primitiveset::PrimitiveSet) -> Result<WrappedDeterministicAead, TinkError> {
         let entry = match &ps.primary {
-            None => return Err("daead::factory: no primary primitive".into()),
+            None => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "daead::factory: no primary primitive",
+                ))
+            }
             Some(p) => p,
         };
         match entry.primitive {
             tink::Primitive::DeterministicAead(_) => {}
-            _ => return Err("daead::factory: not a DeterministicAEAD primitive".into()),
+            _ => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "daead::factory: not a DeterministicAEAD primitive",
+                ))
+            }
         };
-        for (_, primitives) in ps.entries.iter() {
+        for (_, primitives) in ps.entries().iter() {
             for p in primitives {
                 match p.primitive {
                     tink::Primitive::DeterministicAead(_) => {}
-                    _ => return Err("daead::factory: not a DeterministicAEAD primitive".into()),
+                    _ => {
+                        return Err(TinkError::new_with_kind(
+                            ErrorKind::InvalidKeyset,
+                            "daead::factory: not a DeterministicAEAD primitive",
+                        ))
+                    }
                 };
             }
         }
-        Ok(WrappedDeterministicAead { ps })
+        let encrypt_logger = tink::monitoring::new_logger(&ps, "daead", "encrypt")?;
+        let decrypt_logger = tink::monitoring::new_logger(&ps, "daead", "decrypt")?;
+        Ok(WrappedDeterministicAead {
+            ps,
+            encrypt_logger,
+            decrypt_logger,
+        })
     }
 }
 
 impl tink::DeterministicAead for WrappedDeterministicAead {
     fn encrypt_deterministically(&self, pt: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+        self.encrypt_deterministically_with_ad_vector(pt, &[aad])
+    }
+
+    fn decrypt_deterministically(&self, ct: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+        self.decrypt_deterministically_with_ad_vector(ct, &[aad])
+    }
+
+    fn encrypt_deterministically_with_ad_vector(
+        &self,
+        pt: &[u8],
+        aad: &[&[u8]],
+    ) -> Result<Vec<u8>, TinkError> {
         let primary = self
             .ps
             .primary
             .as_ref()
-            .ok_or_else(|| TinkError::new("no primary"))?;
+            .ok_or_else(|| TinkError::new_with_kind(ErrorKind::InvalidKeyset, "no primary"))?;
 
         match &primary.primitive {
             tink::Primitive::DeterministicAead(p) => {
-                let ct = p.encrypt_deterministically(pt, aad)?;
+                let ct = match p.encrypt_deterministically_with_ad_vector(pt, aad) {
+                    Ok(ct) => ct,
+                    Err(e) => {
+                        self.encrypt_logger.log_failure();
+                        return Err(e);
+                    }
+                };
+                self.encrypt_logger.log(primary.key_id, pt.len());
 
                 let mut ret = Vec::with_capacity(primary.prefix.len() + ct.len());
                 ret.extend_from_slice(&primary.prefix);
                 ret.extend_from_slice(&ct);
                 Ok(ret)
             }
-            _ => Err("daead::factory: not a DeterministicAEAD primitive".into()),
+            _ => Err(TinkError::new_with_kind(
+                ErrorKind::InvalidKeyset,
+                "daead::factory: not a DeterministicAEAD primitive",
+            )),
         }
     }
 
-    fn decrypt_deterministically(&self, ct: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+    fn decrypt_deterministically_with_ad_vector(
+        &self,
+        ct: &[u8],
+        aad: &[&[u8]],
+    ) -> Result<Vec<u8>, TinkError> {
+        let mut tried = false;
         // try non-raw keys
         let prefix_size = tink::cryptofmt::NON_RAW_PREFIX_SIZE;
         if ct.len() > prefix_size {
             let prefix = &ct[..prefix_size];
             let ct_no_prefix = &ct[prefix_size..];
-            let entries = self.ps.entries_for_prefix(&prefix);
-            for entry in &entries {
+            for entry in self.ps.entries_for_prefix(prefix) {
                 if let tink::Primitive::DeterministicAead(p) = &entry.primitive {
-                    if let Ok(pt) = p.decrypt_deterministically(ct_no_prefix, aad) {
+                    tried = true;
+                    if let Ok(pt) = p.decrypt_deterministically_with_ad_vector(ct_no_prefix, aad) {
+                        self.decrypt_logger.log(entry.key_id, ct_no_prefix.len());
                         return Ok(pt);
                     }
                 } else {
-                    return Err("daead::factory: not a DeterministicAEAD primitive".into());
+                    return Err(TinkError::new_with_kind(
+                        ErrorKind::InvalidKeyset,
+                        "daead::factory: not a DeterministicAEAD primitive",
+                    ));
                 }
             }
         }
 
         // try raw keys
-        let entries = self.ps.raw_entries();
-        for entry in &entries {
+        for entry in self.ps.raw_entries() {
             if let tink::Primitive::DeterministicAead(p) = &entry.primitive {
-                if let Ok(pt) = p.decrypt_deterministically(ct, aad) {
+                tried = true;
+                if let Ok(pt) = p.decrypt_deterministically_with_ad_vector(ct, aad) {
+                    self.decrypt_logger.log(entry.key_id, ct.len());
                     return Ok(pt);
                 }
             } else {
-                return Err("daead::factory: not a DeterministicAEAD primitive".into());
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "daead::factory: not a DeterministicAEAD primitive",
+                ));
             }
         }
 
         // nothing worked
-        Err("daead::factory: decryption failed".into())
+        self.decrypt_logger.log_failure();
+        Err(TinkError::new_with_kind(
+            if tried {
+                ErrorKind::Authentication
+            } else {
+                ErrorKind::KeyNotFound
+            },
+            "daead::factory: decryption failed",
+        ))
     }
 }
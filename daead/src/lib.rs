@@ -42,6 +42,9 @@ static INIT: Once = Once::new();
 /// tink.
 pub fn init() {
     INIT.call_once(|| {
+        tink::registry::register_primitive_wrapper(DeterministicAeadWrapper)
+            .expect("tink_daead::init() failed"); // safe: init
+
         tink::registry::register_key_manager(std::sync::Arc::new(AesSivKeyManager::default()))
             .expect("tink_daead::init() failed"); // safe: init
 
@@ -17,8 +17,8 @@
 //! Provides subtle implementations of the `DeterministicAEAD` primitive using AES-SIV.
 
 use aes_siv::{aead::generic_array::GenericArray, siv::Aes256Siv};
-use std::{cell::RefCell, rc::Rc};
-use tink::{utils::wrap_err, TinkError};
+use std::sync::{Arc, Mutex};
+use tink::{utils::wrap_err, ErrorKind, TinkError};
 
 const AES_BLOCK_SIZE: usize = 16;
 
@@ -26,8 +26,11 @@ const AES_BLOCK_SIZE: usize = 16;
 /// [RFC 5297](https://tools.ietf.org/html/rfc5297).
 ///
 /// `AesSiv` implements a deterministic encryption with additional data (i.e. the
-/// `DeterministicAEAD` trait). Hence the implementation below is restricted
-/// to one AD component.
+/// `DeterministicAEAD` trait).  The single additional data argument of
+/// [`encrypt_deterministically`](tink::DeterministicAead::encrypt_deterministically) is
+/// processed as a vector with one AD component; up to [`AES_SIV_MAX_AD_COMPONENTS`] components
+/// can be provided with
+/// [`encrypt_deterministically_with_ad_vector`](tink::DeterministicAead::encrypt_deterministically_with_ad_vector).
 ///
 /// # Security Note:
 ///
@@ -43,16 +46,25 @@ const AES_BLOCK_SIZE: usize = 16;
 /// Since 192-bit AES keys are not supported by tink for voodoo reasons
 /// and RFC 5297 only supports same size encryption and MAC keys this
 /// implies that keys must be 64 bytes (2*256 bits) long.
+///
+/// # Zeroization
+///
+/// The encryption key held by an `AesSiv` is wiped on drop, but the AES key schedule for the MAC
+/// key is not, as the `cmac` and `aes` crates do not (yet) support zeroization.
 #[derive(Clone)]
 pub struct AesSiv {
     // Need to use interior mutability because `aes_siv::siv::Siv` operations
-    // take a `&mut self` parameter.
-    cipher: Rc<RefCell<Aes256Siv>>,
+    // take a `&mut self` parameter, and a `Mutex` (rather than a `RefCell`) so
+    // that the primitive is `Send + Sync`.
+    cipher: Arc<Mutex<Aes256Siv>>,
 }
 
 /// Key size in bytes.
 pub const AES_SIV_KEY_SIZE: usize = 64; // 512 bits
 
+/// Maximum number of associated data components, as allowed by RFC 5297 section 2.4.
+pub const AES_SIV_MAX_AD_COMPONENTS: usize = aes_siv::siv::MAX_HEADERS;
+
 impl AesSiv {
     /// Return an [`AesSiv`] instance.
     pub fn new(key: &[u8]) -> Result<AesSiv, TinkError> {
@@ -61,7 +73,7 @@ impl AesSiv {
         }
 
         Ok(AesSiv {
-            cipher: Rc::new(RefCell::new(Aes256Siv::new(*GenericArray::from_slice(key)))),
+            cipher: Arc::new(Mutex::new(Aes256Siv::new(*GenericArray::from_slice(key)))),
         })
     }
 }
@@ -72,26 +84,62 @@ impl tink::DeterministicAead for AesSiv {
         plaintext: &[u8],
         additional_data: &[u8],
     ) -> Result<Vec<u8>, TinkError> {
+        self.encrypt_deterministically_with_ad_vector(plaintext, &[additional_data])
+    }
+
+    fn decrypt_deterministically(
+        &self,
+        ciphertext: &[u8],
+        additional_data: &[u8],
+    ) -> Result<Vec<u8>, TinkError> {
+        self.decrypt_deterministically_with_ad_vector(ciphertext, &[additional_data])
+    }
+
+    fn encrypt_deterministically_with_ad_vector(
+        &self,
+        plaintext: &[u8],
+        additional_data: &[&[u8]],
+    ) -> Result<Vec<u8>, TinkError> {
+        check_ad_components(additional_data)?;
         if plaintext.len() > (isize::MAX as usize) - AES_BLOCK_SIZE {
             return Err("AesSiv: plaintext too long".into());
         }
         self.cipher
-            .borrow_mut()
-            .encrypt(&[additional_data], plaintext)
+            .lock()
+            .map_err(|_| TinkError::new("AesSiv: lock poisoned"))?
+            .encrypt(additional_data, plaintext)
             .map_err(|e| wrap_err("AesSiv: encrypt failed", e))
     }
 
-    fn decrypt_deterministically(
+    fn decrypt_deterministically_with_ad_vector(
         &self,
         ciphertext: &[u8],
-        additional_data: &[u8],
+        additional_data: &[&[u8]],
     ) -> Result<Vec<u8>, TinkError> {
+        check_ad_components(additional_data)?;
         if ciphertext.len() < aes_siv::siv::IV_SIZE {
-            return Err("AesSiv: ciphertext is too short".into());
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "AesSiv: ciphertext is too short",
+            ));
         }
         self.cipher
-            .borrow_mut()
-            .decrypt(&[additional_data], ciphertext)
-            .map_err(|e| wrap_err("AesSiv: decrypt failed", e))
+            .lock()
+            .map_err(|_| TinkError::new("AesSiv: lock poisoned"))?
+            .decrypt(additional_data, ciphertext)
+            .map_err(|e| wrap_err("AesSiv: decrypt failed", e).with_kind(ErrorKind::Authentication))
+    }
+}
+
+fn check_ad_components(additional_data: &[&[u8]]) -> Result<(), TinkError> {
+    if additional_data.len() > AES_SIV_MAX_AD_COMPONENTS {
+        return Err(TinkError::new_with_kind(
+            ErrorKind::InvalidArgument,
+            &format!(
+                "AesSiv: too many associated data components {}",
+                additional_data.len()
+            ),
+        ));
     }
+    Ok(())
 }
@@ -71,6 +71,77 @@ fn test_aes_siv_empty_additional_data() {
     assert!(pt.is_empty(), "Mismatched plaintexts");
 }
 
+#[test]
+fn test_aes_siv_ad_vector() {
+    let key = get_random_bytes(tink_daead::subtle::AES_SIV_KEY_SIZE);
+    let a = tink_daead::subtle::AesSiv::new(&key).unwrap();
+    let msg = b"Some data to encrypt.";
+    let (table, column, row): (&[u8], &[u8], &[u8]) = (b"users", b"email", b"1234");
+
+    let ct = a
+        .encrypt_deterministically_with_ad_vector(msg, &[table, column, row])
+        .unwrap();
+    let pt = a
+        .decrypt_deterministically_with_ad_vector(&ct, &[table, column, row])
+        .unwrap();
+    assert_eq!(pt, msg);
+    assert_eq!(
+        ct,
+        a.encrypt_deterministically_with_ad_vector(msg, &[table, column, row])
+            .unwrap(),
+        "encryption is not deterministic"
+    );
+
+    // A single component is the same as the single additional data form.
+    let single = a.encrypt_deterministically(msg, table).unwrap();
+    assert_eq!(
+        single,
+        a.encrypt_deterministically_with_ad_vector(msg, &[table])
+            .unwrap()
+    );
+    assert_eq!(
+        a.decrypt_deterministically_with_ad_vector(&single, &[table])
+            .unwrap(),
+        msg
+    );
+
+    // Components are not concatenated, and their order and number matter.
+    for wrong_ad in vec![
+        vec![&b"usersemail1234"[..]],
+        vec![table, b"email1", b"234"],
+        vec![column, table, row],
+        vec![table, column],
+        vec![table, column, row, b""],
+        vec![],
+    ] {
+        assert!(a
+            .decrypt_deterministically_with_ad_vector(&ct, &wrong_ad)
+            .is_err());
+        assert_ne!(
+            ct,
+            a.encrypt_deterministically_with_ad_vector(msg, &wrong_ad)
+                .unwrap()
+        );
+    }
+
+    let max_ad = vec![table; tink_daead::subtle::AES_SIV_MAX_AD_COMPONENTS];
+    let ct = a
+        .encrypt_deterministically_with_ad_vector(msg, &max_ad)
+        .unwrap();
+    assert_eq!(
+        a.decrypt_deterministically_with_ad_vector(&ct, &max_ad)
+            .unwrap(),
+        msg
+    );
+    let too_many_ad = vec![table; tink_daead::subtle::AES_SIV_MAX_AD_COMPONENTS + 1];
+    assert!(a
+        .encrypt_deterministically_with_ad_vector(msg, &too_many_ad)
+        .is_err());
+    assert!(a
+        .decrypt_deterministically_with_ad_vector(&ct, &too_many_ad)
+        .is_err());
+}
+
 #[test]
 fn test_aes_siv_key_sizes() {
     let key_str =
@@ -81,6 +81,48 @@ fn test_factory_raw_key_as_primary() {
     assert!(validate_daead_factory_cipher(&d, &d, &tink::cryptofmt::RAW_PREFIX).is_ok());
 }
 
+#[test]
+fn test_factory_ad_vector() {
+    tink_daead::init();
+    let keyset = tink_testutil::new_test_aes_siv_keyset(tink::proto::OutputPrefixType::Tink);
+    let raw_key = keyset.key[1].clone();
+    let keyset_handle = tink::keyset::insecure::new_handle(keyset).unwrap();
+    let d = tink_daead::new(&keyset_handle).unwrap();
+    let keyset2 = tink_testutil::new_keyset(raw_key.key_id, vec![raw_key]);
+    let keyset_handle2 = tink::keyset::insecure::new_handle(keyset2).unwrap();
+    let d2 = tink_daead::new(&keyset_handle2).unwrap();
+
+    let pt = get_random_bytes(20);
+    let ad: Vec<&[u8]> = vec![b"table", b"column", b"row"];
+    for encrypt_cipher in &[&d, &d2] {
+        let ct = encrypt_cipher
+            .encrypt_deterministically_with_ad_vector(&pt, &ad)
+            .unwrap();
+        assert_eq!(
+            d.decrypt_deterministically_with_ad_vector(&ct, &ad)
+                .unwrap(),
+            pt
+        );
+        assert!(d
+            .decrypt_deterministically_with_ad_vector(&ct, &ad[..2])
+            .is_err());
+        assert!(d.decrypt_deterministically(&ct, b"tablecolumnrow").is_err());
+    }
+
+    // The single additional data form is compatible with a one-component vector.
+    let ct = d.encrypt_deterministically(&pt, ad[0]).unwrap();
+    assert_eq!(
+        ct,
+        d.encrypt_deterministically_with_ad_vector(&pt, &ad[..1])
+            .unwrap()
+    );
+    assert_eq!(
+        d.decrypt_deterministically_with_ad_vector(&ct, &ad[..1])
+            .unwrap(),
+        pt
+    );
+}
+
 // Return an `Err` if decryption fails, panic if something else goes wrong.
 #[allow(clippy::borrowed_box)]
 fn validate_daead_factory_cipher<T: ?Sized>(
@@ -17,68 +17,67 @@
 //! AEAD functionality via AWS Cloud KMS.
 
 use rusoto_kms::Kms;
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
-use tink::utils::wrap_err;
+use std::{collections::HashMap, sync::Arc};
+use tink::{ErrorKind, TinkError};
+
+/// Name of the encryption context entry that holds (hex-encoded) associated data.
+const ADDITIONAL_DATA_CONTEXT_NAME: &str = "additionalData";
 
 /// `AwsAead` represents a AWS KMS service to a particular URI.
 #[derive(Clone)]
 pub struct AwsAead {
     key_uri: String,
     kms: rusoto_kms::KmsClient,
-    // The Tokio runtime to execute KMS requests on, wrapped in:
-    //  - a `RefCell` for interior mutability (the [`tink::Aead`] trait's methods take `&self`)
-    //  - an `Rc` to allow `Clone`, as required by the trait bound on [`tink::Aead`].
-    runtime: Rc<RefCell<tokio::runtime::Runtime>>,
+    // Encryption context entries included in every request.
+    encryption_context: HashMap<String, String>,
+    // The Tokio runtime to execute KMS requests on, shared with the [`AwsClient`](crate::AwsClient)
+    // that created this AEAD (and so with all other AEADs from the same client), because
+    // connections pooled by `kms` are driven by the runtime they were created on.  The runtime
+    // uses the threaded scheduler, so requests from different threads run concurrently.
+    runtime: Arc<tokio::runtime::Runtime>,
+    retry_policy: crate::RetryPolicy,
 }
 
 impl AwsAead {
     /// Return a new AWS KMS service.
-    /// `key_uri` must have the following format: `arn:<partition>:kms:<region>:[:path]`.
+    /// `key_uri` must be a key ARN (`arn:<partition>:kms:<region>:<account>:key/<id>`), an alias
+    /// ARN (`arn:<partition>:kms:<region>:<account>:alias/<name>`) or an alias name
+    /// (`alias/<name>`).
     /// See http://docs.aws.amazon.com/general/latest/gr/aws-arns-and-namespaces.html.
     pub(crate) fn new(
         key_uri: &str,
         kms: rusoto_kms::KmsClient,
-    ) -> Result<AwsAead, tink::TinkError> {
-        Ok(AwsAead {
+        encryption_context: HashMap<String, String>,
+        runtime: Arc<tokio::runtime::Runtime>,
+        retry_policy: crate::RetryPolicy,
+    ) -> AwsAead {
+        AwsAead {
             key_uri: key_uri.to_string(),
             kms,
-            runtime: Rc::new(RefCell::new(
-                tokio::runtime::Builder::new()
-                    .basic_scheduler()
-                    .enable_all()
-                    .build()
-                    .map_err(|e| wrap_err("failed to build tokio runtime", e))?,
-            )),
-        })
+            encryption_context,
+            runtime,
+            retry_policy,
+        }
     }
-}
 
-impl tink::Aead for AwsAead {
-    fn encrypt(
+    /// Encrypt `plaintext` with the given encryption context, which is merged with the
+    /// encryption context configured for the client.  The same encryption context must be
+    /// provided for decryption.
+    pub fn encrypt_with_context(
         &self,
         plaintext: &[u8],
-        additional_data: &[u8],
-    ) -> Result<Vec<u8>, tink::TinkError> {
-        let ad = hex::encode(additional_data);
-        let encryption_context = if ad.is_empty() {
-            None
-        } else {
-            let mut context = HashMap::new();
-            context.insert("additionalData".to_string(), ad);
-            Some(context)
-        };
+        context: &HashMap<String, String>,
+    ) -> Result<Vec<u8>, TinkError> {
         let req = rusoto_kms::EncryptRequest {
             encryption_algorithm: None, // use default
             grant_tokens: None,
             key_id: self.key_uri.clone(),
-            encryption_context,
+            encryption_context: self.merge_context(context)?,
             plaintext: plaintext.to_vec().into(),
         };
         let rsp = self
-            .runtime
-            .borrow_mut()
-            .block_on(self.kms.encrypt(req))
-            .map_err(|e| wrap_err("request failed", e))?;
+            .retry_policy
+            .run(&self.runtime, || self.kms.encrypt(req.clone()))?;
 
         match rsp.ciphertext_blob {
             None => Err("no ciphertext".into()),
@@ -86,34 +85,29 @@ impl tink::Aead for AwsAead {
         }
     }
 
-    fn decrypt(
+    /// Decrypt `ciphertext` with the given encryption context, which is merged with the
+    /// encryption context configured for the client.
+    pub fn decrypt_with_context(
         &self,
         ciphertext: &[u8],
-        additional_data: &[u8],
-    ) -> Result<Vec<u8>, tink::TinkError> {
-        let ad = hex::encode(additional_data);
-        let encryption_context = if ad.is_empty() {
-            None
-        } else {
-            let mut context = HashMap::new();
-            context.insert("additionalData".to_string(), ad);
-            Some(context)
-        };
+        context: &HashMap<String, String>,
+    ) -> Result<Vec<u8>, TinkError> {
         let req = rusoto_kms::DecryptRequest {
             ciphertext_blob: ciphertext.to_vec().into(),
             encryption_algorithm: None, // use default
-            encryption_context,
+            encryption_context: self.merge_context(context)?,
             grant_tokens: None,
             key_id: Some(self.key_uri.clone()),
         };
         let rsp = self
-            .runtime
-            .borrow_mut()
-            .block_on(self.kms.decrypt(req))
-            .map_err(|e| wrap_err("request failed", e))?;
+            .retry_policy
+            .run(&self.runtime, || self.kms.decrypt(req.clone()))?;
         if let Some(key_id) = rsp.key_id {
-            if key_id != self.key_uri {
-                return Err("decryption failed: wrong key id".into());
+            if !key_id_matches(&self.key_uri, &key_id) {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::Authentication,
+                    "decryption failed: wrong key id",
+                ));
             }
         } else {
             return Err("decryption failed: no key id".into());
@@ -123,4 +117,95 @@ impl tink::Aead for AwsAead {
             Some(b) => Ok(b.to_vec()),
         }
     }
+
+    /// Combine the client's encryption context with `context`, refusing to let `context`
+    /// override any of the client's entries.
+    fn merge_context(
+        &self,
+        context: &HashMap<String, String>,
+    ) -> Result<Option<HashMap<String, String>>, TinkError> {
+        let mut merged = self.encryption_context.clone();
+        for (name, value) in context {
+            match merged.get(name) {
+                Some(existing) if existing != value => {
+                    return Err(TinkError::new_with_kind(
+                        ErrorKind::InvalidArgument,
+                        &format!("conflicting encryption context entry {}", name),
+                    ))
+                }
+                _ => {
+                    merged.insert(name.clone(), value.clone());
+                }
+            }
+        }
+        if merged.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(merged))
+        }
+    }
+}
+
+/// Return the encryption context corresponding to `additional_data`.
+fn additional_data_context(additional_data: &[u8]) -> HashMap<String, String> {
+    let mut context = HashMap::new();
+    if !additional_data.is_empty() {
+        context.insert(
+            ADDITIONAL_DATA_CONTEXT_NAME.to_string(),
+            hex::encode(additional_data),
+        );
+    }
+    context
+}
+
+/// Check whether the key ARN `returned` by KMS for a decryption operation corresponds to the
+/// `requested` key identifier.
+///
+/// KMS resolves aliases itself (and refuses to decrypt with a key other than the one requested),
+/// so any key is accepted for an alias.  Replicas of a multi-region key share the same key
+/// identifier across regions, so the region is ignored when comparing multi-region key ARNs.
+fn key_id_matches(requested: &str, returned: &str) -> bool {
+    if requested == returned || is_alias(requested) {
+        return true;
+    }
+    match (split_arn(requested), split_arn(returned)) {
+        (
+            Some((req_partition, _, req_account, req_resource)),
+            Some((partition, _, account, resource)),
+        ) => {
+            req_resource.starts_with("key/mrk-")
+                && req_partition == partition
+                && req_account == account
+                && req_resource == resource
+        }
+        _ => false,
+    }
+}
+
+/// Indicate whether `key_id` identifies a key by alias, either as an alias name or an alias ARN.
+fn is_alias(key_id: &str) -> bool {
+    key_id.starts_with("alias/")
+        || matches!(split_arn(key_id), Some((_, _, _, resource)) if resource.starts_with("alias/"))
+}
+
+/// Split a KMS ARN of the form `arn:<partition>:kms:<region>:<account>:<resource>` into its
+/// partition, region, account and resource components.
+fn split_arn(arn: &str) -> Option<(&str, &str, &str, &str)> {
+    let parts: Vec<&str> = arn.splitn(6, ':').collect();
+    match parts.as_slice() {
+        ["arn", partition, "kms", region, account, resource] => {
+            Some((partition, region, account, resource))
+        }
+        _ => None,
+    }
+}
+
+impl tink::Aead for AwsAead {
+    fn encrypt(&self, plaintext: &[u8], additional_data: &[u8]) -> Result<Vec<u8>, TinkError> {
+        self.encrypt_with_context(plaintext, &additional_data_context(additional_data))
+    }
+
+    fn decrypt(&self, ciphertext: &[u8], additional_data: &[u8]) -> Result<Vec<u8>, TinkError> {
+        self.decrypt_with_context(ciphertext, &additional_data_context(additional_data))
+    }
 }
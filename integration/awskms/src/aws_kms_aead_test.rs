@@ -12,8 +12,8 @@
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-use std::path::PathBuf;
-use tink::{subtle::random::get_random_bytes, TinkError};
+use std::{collections::HashMap, path::PathBuf};
+use tink::{registry::KmsClient, subtle::random::get_random_bytes, Aead, TinkError};
 
 const KEY_URI: &str =
     "aws-kms://arn:aws:kms:us-east-2:235739564943:key/3ee50705-5a82-4f5b-9753-05c4f473922f";
@@ -91,3 +91,172 @@ fn test_basic_aead_without_additional_data() {
         }
     }
 }
+
+const FAKE_KEY_ARN: &str =
+    "arn:aws:kms:us-east-2:235739564943:key/3ee50705-5a82-4f5b-9753-05c4f473922f";
+const FAKE_MRK_ARN: &str =
+    "arn:aws:kms:us-east-2:235739564943:key/mrk-1234abcd12ab34cd56ef1234567890ab";
+
+fn fake_client(fake: &crate::fake_kms::FakeKms, uri_prefix: &str) -> crate::AwsClient {
+    crate::AwsClient::builder(uri_prefix)
+        .credential_path(CRED_FILE)
+        .region(fake.region())
+        .endpoint(fake.endpoint())
+        .build()
+        .expect("error setting up aws client")
+}
+
+#[test]
+fn test_fake_kms_aead() {
+    let fake = crate::fake_kms::FakeKms::start("us-east-2", &[FAKE_KEY_ARN]);
+    let client = fake_client(&fake, "aws-kms://");
+    let a = client
+        .get_aead(&format!("aws-kms://{}", FAKE_KEY_ARN))
+        .expect("error getting AEAD");
+    assert!(
+        basic_aead_test(a.box_clone()).is_ok(),
+        "error in basic aead tests"
+    );
+
+    let ct = a.encrypt(b"plaintext", b"aad").unwrap();
+    assert!(a.decrypt(&ct, b"other aad").is_err());
+    assert!(a.decrypt(&ct, &[]).is_err());
+    assert_eq!(fake.request_count("Encrypt"), 11);
+}
+
+#[test]
+fn test_fake_kms_encryption_context() {
+    let fake = crate::fake_kms::FakeKms::start("us-east-2", &[FAKE_KEY_ARN]);
+    let mut service_context = HashMap::new();
+    service_context.insert("service".to_string(), "billing".to_string());
+    let client = crate::AwsClient::builder("aws-kms://")
+        .credential_path(CRED_FILE)
+        .endpoint(fake.endpoint())
+        .region(fake.region())
+        .encryption_context(service_context.clone())
+        .build()
+        .unwrap();
+    let key_uri = format!("aws-kms://{}", FAKE_KEY_ARN);
+    let a = client.get_aws_aead(&key_uri).unwrap();
+
+    let mut context = HashMap::new();
+    context.insert("table".to_string(), "users".to_string());
+    context.insert("column".to_string(), "email".to_string());
+    let ct = a.encrypt_with_context(b"secret", &context).unwrap();
+    assert_eq!(a.decrypt_with_context(&ct, &context).unwrap(), b"secret");
+
+    // Decryption fails if any part of the context differs.
+    let mut wrong_context = context.clone();
+    wrong_context.insert("column".to_string(), "phone".to_string());
+    assert!(a.decrypt_with_context(&ct, &wrong_context).is_err());
+    assert!(a.decrypt_with_context(&ct, &HashMap::new()).is_err());
+
+    // A client without the service-level context cannot decrypt.
+    let other = fake_client(&fake, "aws-kms://")
+        .get_aws_aead(&key_uri)
+        .unwrap();
+    assert!(other.decrypt_with_context(&ct, &context).is_err());
+    let mut full_context = context.clone();
+    full_context.extend(service_context);
+    assert_eq!(
+        other.decrypt_with_context(&ct, &full_context).unwrap(),
+        b"secret"
+    );
+
+    // Per-call entries cannot override client-level entries.
+    let mut conflict = HashMap::new();
+    conflict.insert("service".to_string(), "payroll".to_string());
+    assert!(a.encrypt_with_context(b"secret", &conflict).is_err());
+
+    // Associated data for the `tink::Aead` interface is added to the client-level context.
+    let ct = a.encrypt(b"secret", b"aad").unwrap();
+    assert_eq!(a.decrypt(&ct, b"aad").unwrap(), b"secret");
+    let mut ad_context = HashMap::new();
+    ad_context.insert("additionalData".to_string(), hex::encode(b"aad"));
+    assert_eq!(a.decrypt_with_context(&ct, &ad_context).unwrap(), b"secret");
+}
+
+#[test]
+fn test_fake_kms_alias() {
+    let fake = crate::fake_kms::FakeKms::start("us-east-2", &[FAKE_KEY_ARN]);
+    let alias_arn = "arn:aws:kms:us-east-2:235739564943:alias/tink-test";
+    fake.add_alias("alias/tink-test", FAKE_KEY_ARN);
+    fake.add_alias(alias_arn, FAKE_KEY_ARN);
+    let client = fake_client(&fake, "aws-kms://");
+
+    for alias_uri in &[
+        "aws-kms://alias/tink-test".to_string(),
+        format!("aws-kms://{}", alias_arn),
+    ] {
+        let a = client.get_aead(alias_uri).unwrap();
+        assert!(basic_aead_test(a).is_ok(), "error in basic aead tests");
+    }
+
+    // Ciphertexts produced via the alias can be decrypted via the key ARN and vice versa.
+    let by_alias = client.get_aead("aws-kms://alias/tink-test").unwrap();
+    let by_arn = client
+        .get_aead(&format!("aws-kms://{}", FAKE_KEY_ARN))
+        .unwrap();
+    let ct = by_alias.encrypt(b"plaintext", b"aad").unwrap();
+    assert_eq!(by_arn.decrypt(&ct, b"aad").unwrap(), b"plaintext");
+    let ct = by_arn.encrypt(b"plaintext", b"aad").unwrap();
+    assert_eq!(by_alias.decrypt(&ct, b"aad").unwrap(), b"plaintext");
+}
+
+#[test]
+fn test_fake_kms_multi_region_key() {
+    let east = crate::fake_kms::FakeKms::start("us-east-2", &[FAKE_MRK_ARN]);
+    let ct = fake_client(&east, "aws-kms://")
+        .get_aead(&format!("aws-kms://{}", FAKE_MRK_ARN))
+        .unwrap()
+        .encrypt(b"plaintext", b"aad")
+        .unwrap();
+    assert_eq!(
+        fake_client(&east, "aws-kms://")
+            .get_aead(&format!("aws-kms://{}", FAKE_MRK_ARN))
+            .unwrap()
+            .decrypt(&ct, b"aad")
+            .unwrap(),
+        b"plaintext"
+    );
+
+    // A server for another region responds with the ARN of its replica of the key.
+    let west = crate::fake_kms::FakeKms::start("us-west-2", &[FAKE_MRK_ARN]);
+    let a = fake_client(&west, "aws-kms://")
+        .get_aead(&format!("aws-kms://{}", FAKE_MRK_ARN))
+        .unwrap();
+    let ct = a.encrypt(b"plaintext", b"aad").unwrap();
+    assert_eq!(a.decrypt(&ct, b"aad").unwrap(), b"plaintext");
+}
+
+#[test]
+fn test_fake_kms_wrong_key() {
+    let other_key_arn =
+        "arn:aws:kms:us-east-2:235739564943:key/00000000-5a82-4f5b-9753-05c4f473922f";
+    let fake = crate::fake_kms::FakeKms::start("us-east-2", &[FAKE_KEY_ARN, other_key_arn]);
+    let client = fake_client(&fake, "aws-kms://");
+    let a = client
+        .get_aead(&format!("aws-kms://{}", FAKE_KEY_ARN))
+        .unwrap();
+    let b = client
+        .get_aead(&format!("aws-kms://{}", other_key_arn))
+        .unwrap();
+    let ct = a.encrypt(b"plaintext", b"aad").unwrap();
+    assert!(b.decrypt(&ct, b"aad").is_err());
+}
+
+#[test]
+fn test_fake_kms_envelope_aead() {
+    init();
+    let fake = crate::fake_kms::FakeKms::start("us-east-2", &[FAKE_KEY_ARN]);
+    let client = fake_client(&fake, "aws-kms://");
+    let key_uri = format!("aws-kms://{}", FAKE_KEY_ARN);
+    let a = tink_aead::KmsEnvelopeAead::new(
+        tink_aead::aes128_gcm_key_template(),
+        client.get_aead(&key_uri).unwrap(),
+    );
+    assert!(
+        basic_aead_test(Box::new(a)).is_ok(),
+        "error in basic aead tests"
+    );
+}
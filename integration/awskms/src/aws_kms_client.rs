@@ -19,8 +19,8 @@
 use regex::Regex;
 use rusoto_core::region::Region;
 use rusoto_credential::AwsCredentials;
-use std::str::FromStr;
-use tink::{utils::wrap_err, TinkError};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+use tink::{utils::wrap_err, ErrorKind, TinkError};
 
 pub const AWS_PREFIX: &str = "aws-kms://";
 
@@ -28,12 +28,17 @@ pub const AWS_PREFIX: &str = "aws-kms://";
 pub struct AwsClient {
     key_uri_prefix: String,
     kms: rusoto_kms::KmsClient,
+    encryption_context: HashMap<String, String>,
+    runtime: Arc<tokio::runtime::Runtime>,
+    retry_policy: crate::RetryPolicy,
 }
 
 impl std::fmt::Debug for AwsClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Point")
+        f.debug_struct("AwsClient")
             .field("key_uri_prefix", &self.key_uri_prefix)
+            .field("encryption_context", &self.encryption_context)
+            .field("retry_policy", &self.retry_policy)
             .finish()
     }
 }
@@ -44,10 +49,7 @@ impl AwsClient {
     /// `aws-kms://arn:<partition>:kms:<region>:[:path]`
     /// See http://docs.aws.amazon.com/general/latest/gr/aws-arns-and-namespaces.html.
     pub fn new(uri_prefix: &str) -> Result<AwsClient, TinkError> {
-        let r = get_region(uri_prefix)?;
-
-        let kms = rusoto_kms::KmsClient::new(r);
-        Self::new_with_kms(uri_prefix, kms)
+        Self::builder(uri_prefix).build()
     }
 
     /// Return a new AWS KMS client which will use given credentials to handle keys with
@@ -58,30 +60,9 @@ impl AwsClient {
         uri_prefix: &str,
         credential_path: &str,
     ) -> Result<AwsClient, TinkError> {
-        if credential_path.is_empty() {
-            return Err("invalid credential path".into());
-        }
-        let region = get_region(uri_prefix)?;
-        let request_dispatcher = rusoto_core::request::HttpClient::new()
-            .map_err(|e| wrap_err("failed to create AWS HTTP client", e))?;
-
-        let kms = match extract_creds_csv(credential_path) {
-            Ok(c) => {
-                let creds_provider = rusoto_credential::StaticProvider::from(c);
-                rusoto_kms::KmsClient::new_with(request_dispatcher, creds_provider, region)
-            }
-            Err(CredentialsErr::BadFile) => return Err("cannot open credential path".into()),
-            Err(CredentialsErr::CredCsv) => return Err("malformed credential csv file".into()),
-            Err(_) => {
-                // fallback to load the credential path as .ini shared credentials.
-                let creds_provider = rusoto_credential::ProfileProvider::with_configuration(
-                    credential_path,
-                    "default",
-                );
-                rusoto_kms::KmsClient::new_with(request_dispatcher, creds_provider, region)
-            }
-        };
-        Self::new_with_kms(uri_prefix, kms)
+        Self::builder(uri_prefix)
+            .credential_path(credential_path)
+            .build()
     }
 
     /// Return a new AWS KMS client with user created KMS client.  Client is responsible for keeping
@@ -92,17 +73,76 @@ impl AwsClient {
         uri_prefix: &str,
         kms: rusoto_kms::KmsClient,
     ) -> Result<AwsClient, TinkError> {
-        if !uri_prefix.to_lowercase().starts_with(AWS_PREFIX) {
-            return Err(format!(
-                "uri_prefix must start with {}, but got {}",
-                AWS_PREFIX, uri_prefix
-            )
-            .into());
+        Self::builder(uri_prefix).kms(kms).build()
+    }
+
+    /// Return a builder for an AWS KMS client that handles keys with `uri_prefix` prefix, allowing
+    /// control over credentials, region, endpoint, encryption context and retries.
+    pub fn builder(uri_prefix: &str) -> AwsClientBuilder {
+        AwsClientBuilder {
+            uri_prefix: uri_prefix.to_string(),
+            ..Default::default()
         }
+    }
 
-        Ok(AwsClient {
-            key_uri_prefix: uri_prefix.to_string(),
-            kms,
+    /// Get an [`AwsAead`](crate::AwsAead) backed by `key_uri`, which (unlike the
+    /// [`tink::Aead`] returned by [`get_aead`](tink::registry::KmsClient::get_aead)) also allows
+    /// the use of structured encryption contexts.
+    /// `key_uri` must have the following format: `aws-kms://<key-id>`, where `<key-id>` is a key
+    /// ARN, an alias ARN or an alias name (`alias/<name>`).
+    pub fn get_aws_aead(&self, key_uri: &str) -> Result<crate::AwsAead, TinkError> {
+        Ok(crate::AwsAead::new(
+            self.key_id(key_uri)?,
+            self.kms.clone(),
+            self.encryption_context.clone(),
+            self.runtime.clone(),
+            self.retry_policy.clone(),
+        ))
+    }
+
+    /// Get a [`tink::Signer`] backed by the asymmetric KMS key identified by `key_uri`.  Signing
+    /// operations are performed remotely by KMS, so the private key never leaves KMS.
+    /// `key_uri` must have the following format: `aws-kms://<key-id>`, where `<key-id>` is a key
+    /// ARN, an alias ARN or an alias name (`alias/<name>`).
+    pub fn get_signer(&self, key_uri: &str) -> Result<Box<dyn tink::Signer>, TinkError> {
+        let key_id = self.key_id(key_uri)?;
+        let key_info = crate::get_public_key(&self.kms, &self.runtime, &self.retry_policy, key_id)?;
+        Ok(Box::new(crate::AwsSigner::new(
+            key_id,
+            &key_info,
+            self.kms.clone(),
+            self.runtime.clone(),
+            self.retry_policy.clone(),
+        )?))
+    }
+
+    /// Get a [`tink::Verifier`] for the asymmetric KMS key identified by `key_uri`.  The public
+    /// key is retrieved from KMS once, and verification is then performed locally.
+    /// `key_uri` must have the following format: `aws-kms://<key-id>`, where `<key-id>` is a key
+    /// ARN, an alias ARN or an alias name (`alias/<name>`).
+    pub fn get_verifier(&self, key_uri: &str) -> Result<Box<dyn tink::Verifier>, TinkError> {
+        let key_id = self.key_id(key_uri)?;
+        let key_info = crate::get_public_key(&self.kms, &self.runtime, &self.retry_policy, key_id)?;
+        crate::new_verifier(&key_info)
+    }
+
+    /// Check that `key_uri` is supported by this client, and return the KMS key identifier
+    /// that it holds.
+    fn key_id<'a>(&self, key_uri: &'a str) -> Result<&'a str, TinkError> {
+        if !tink::registry::KmsClient::supported(self, key_uri) {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Unsupported,
+                &format!(
+                    "key_uri must start with prefix {}, but got {}",
+                    self.key_uri_prefix, key_uri
+                ),
+            ));
+        }
+
+        Ok(if let Some(stripped) = key_uri.strip_prefix(AWS_PREFIX) {
+            stripped
+        } else {
+            key_uri
         })
     }
 }
@@ -116,20 +156,147 @@ impl tink::registry::KmsClient for AwsClient {
     /// `key_uri` must have the following format: `aws-kms://arn:<partition>:kms:<region>:[:path]`.
     /// See http://docs.aws.amazon.com/general/latest/gr/aws-arns-and-namespaces.html.
     fn get_aead(&self, key_uri: &str) -> Result<Box<dyn tink::Aead>, tink::TinkError> {
-        if !self.supported(key_uri) {
+        Ok(Box::new(self.get_aws_aead(key_uri)?))
+    }
+}
+
+/// `AwsClientBuilder` allows an [`AwsClient`] to be configured before use.
+///
+/// By default, the region is extracted from the key URI prefix, the regional AWS endpoint is used,
+/// default credentials are used, and requests are retried according to
+/// [`RetryPolicy::default`](crate::RetryPolicy::default).
+#[derive(Default)]
+pub struct AwsClientBuilder {
+    uri_prefix: String,
+    credential_path: Option<String>,
+    region: Option<String>,
+    endpoint: Option<String>,
+    encryption_context: HashMap<String, String>,
+    kms: Option<rusoto_kms::KmsClient>,
+    retry_policy: crate::RetryPolicy,
+}
+
+impl AwsClientBuilder {
+    /// Use the credentials held in the file at `credential_path`, which may be either a CSV file as
+    /// generated by the AWS IAM console or a `.ini` shared credentials file.
+    pub fn credential_path(mut self, credential_path: &str) -> Self {
+        self.credential_path = Some(credential_path.to_string());
+        self
+    }
+
+    /// Use the given region, rather than extracting the region from the key URI prefix.  This is
+    /// required for key URIs that do not include a region, such as `aws-kms://alias/<name>`.
+    pub fn region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    /// Send requests to `endpoint` (e.g. `http://localhost:4566` for LocalStack) rather than to
+    /// the regional AWS KMS endpoint.
+    pub fn endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = Some(endpoint.to_string());
+        self
+    }
+
+    /// Include the given entries in the encryption context of every request made by AEADs
+    /// obtained from the client, in addition to any entries derived from associated data.
+    pub fn encryption_context(mut self, context: HashMap<String, String>) -> Self {
+        self.encryption_context = context;
+        self
+    }
+
+    /// Use a user created KMS client. The caller is responsible for keeping the region consistent
+    /// between the key URI and the KMS client; any credential path, region or endpoint settings are
+    /// ignored.
+    pub fn kms(mut self, kms: rusoto_kms::KmsClient) -> Self {
+        self.kms = Some(kms);
+        self
+    }
+
+    /// Retry requests that fail with a transient error according to `policy`.
+    pub fn retry_policy(mut self, policy: crate::RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Build the [`AwsClient`].
+    pub fn build(self) -> Result<AwsClient, TinkError> {
+        if !self.uri_prefix.to_lowercase().starts_with(AWS_PREFIX) {
             return Err(format!(
-                "key_uri must start with prefix {}, but got {}",
-                self.key_uri_prefix, key_uri
+                "uri_prefix must start with {}, but got {}",
+                AWS_PREFIX, self.uri_prefix
             )
             .into());
         }
-
-        let uri = if let Some(stripped) = key_uri.strip_prefix(AWS_PREFIX) {
-            stripped
-        } else {
-            key_uri
+        let kms = match self.kms {
+            Some(kms) => kms,
+            None => {
+                let region = match &self.region {
+                    Some(r) => Region::from_str(r).map_err(|e| wrap_err("unknown region", e))?,
+                    None => get_region(&self.uri_prefix)?,
+                };
+                let region = match &self.endpoint {
+                    Some(endpoint) => Region::Custom {
+                        name: region.name().to_string(),
+                        endpoint: endpoint.to_string(),
+                    },
+                    None => region,
+                };
+                match &self.credential_path {
+                    Some(credential_path) => new_kms_with_credentials(credential_path, region)?,
+                    None => rusoto_kms::KmsClient::new(region),
+                }
+            }
         };
-        Ok(Box::new(crate::AwsAead::new(uri, self.kms.clone())?))
+        // Use the threaded scheduler so that concurrent requests from the client (and from the
+        // primitives it creates) do not have to take turns driving the runtime.
+        let runtime = tokio::runtime::Builder::new()
+            .threaded_scheduler()
+            .enable_all()
+            .build()
+            .map_err(|e| wrap_err("failed to build tokio runtime", e))?;
+        Ok(AwsClient {
+            key_uri_prefix: self.uri_prefix,
+            kms,
+            encryption_context: self.encryption_context,
+            runtime: Arc::new(runtime),
+            retry_policy: self.retry_policy,
+        })
+    }
+}
+
+/// Create a KMS client for `region` using the credentials held in the file at `credential_path`.
+fn new_kms_with_credentials(
+    credential_path: &str,
+    region: Region,
+) -> Result<rusoto_kms::KmsClient, TinkError> {
+    if credential_path.is_empty() {
+        return Err("invalid credential path".into());
+    }
+    let request_dispatcher = rusoto_core::request::HttpClient::new()
+        .map_err(|e| wrap_err("failed to create AWS HTTP client", e))?;
+
+    match extract_creds_csv(credential_path) {
+        Ok(c) => {
+            let creds_provider = rusoto_credential::StaticProvider::from(c);
+            Ok(rusoto_kms::KmsClient::new_with(
+                request_dispatcher,
+                creds_provider,
+                region,
+            ))
+        }
+        Err(CredentialsErr::BadFile) => Err("cannot open credential path".into()),
+        Err(CredentialsErr::CredCsv) => Err("malformed credential csv file".into()),
+        Err(_) => {
+            // fallback to load the credential path as .ini shared credentials.
+            let creds_provider =
+                rusoto_credential::ProfileProvider::with_configuration(credential_path, "default");
+            Ok(rusoto_kms::KmsClient::new_with(
+                request_dispatcher,
+                creds_provider,
+                region,
+            ))
+        }
     }
 }
 
@@ -164,3 +164,62 @@ fn test_get_aead_non_supported_uri() {
         non_supported_key_uri
     );
 }
+
+#[test]
+fn test_builder_alias_uri_prefix_needs_region() {
+    let uri_prefix = "aws-kms://alias/";
+    assert!(
+        AwsClient::builder(uri_prefix).build().is_err(),
+        "client without region should reject URI prefix without region {}",
+        uri_prefix
+    );
+    assert!(
+        AwsClient::builder(uri_prefix)
+            .region("us-east-2")
+            .build()
+            .is_ok(),
+        "client with region should support URI prefix {}",
+        uri_prefix
+    );
+}
+
+#[test]
+fn test_builder_bad_region() {
+    let uri_prefix = "aws-kms://alias/";
+    assert!(
+        AwsClient::builder(uri_prefix)
+            .region("us-east-DOES-NOT-EXIST")
+            .build()
+            .is_err(),
+        "client should reject unknown region"
+    );
+}
+
+#[test]
+fn test_builder_endpoint() {
+    let uri_prefix = "aws-kms://arn:aws:kms:us-east-2:235739564943:key/";
+    let supported_key_uri =
+        "aws-kms://arn:aws:kms:us-east-2:235739564943:key/3ee50705-5a82-4f5b-9753-05c4f473922f";
+    let client = AwsClient::builder(uri_prefix)
+        .endpoint("http://localhost:4566")
+        .build()
+        .unwrap();
+    assert!(
+        client.get_aead(supported_key_uri).is_ok(),
+        "client with custom endpoint should support key URI {}",
+        supported_key_uri
+    );
+}
+
+#[test]
+fn test_builder_bad_uri_prefix() {
+    let uri_prefix = "bad-prefix://alias/";
+    assert!(
+        AwsClient::builder(uri_prefix)
+            .region("us-east-2")
+            .build()
+            .is_err(),
+        "does not reject bad URI prefix: {}",
+        uri_prefix
+    );
+}
@@ -0,0 +1,202 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Retry handling for AWS Cloud KMS requests.
+
+use rand::Rng;
+use rusoto_core::RusotoError;
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+use tink::{utils::wrap_err, ErrorKind, TinkError};
+
+/// `RetryPolicy` controls how an [`AwsClient`](crate::AwsClient) retries KMS requests that fail
+/// with a transient error: throttling, KMS internal errors or timeouts, and failures to send the
+/// request or receive the response.
+///
+/// Retries use exponential backoff with full jitter: before the `n`-th retry, the client waits
+/// for a random duration between zero and `min(max_backoff, initial_backoff * 2^(n-1))`.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts for a request, including the first attempt.
+    pub max_attempts: u32,
+    /// Upper bound for the wait before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound for the wait before any retry.
+    pub max_backoff: Duration,
+    /// Deadline for each call, covering all attempts and the waits between them.  If `None`,
+    /// a call can take an unbounded amount of time.
+    pub deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            deadline: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Return a policy that makes a single attempt for each request, subject to the default
+    /// deadline.
+    pub fn no_retries() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Return a random wait duration to use before retry number `retry` (starting at 1).
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let shift = std::cmp::min(retry.saturating_sub(1), 31);
+        let cap = self
+            .initial_backoff
+            .checked_mul(1 << shift)
+            .map_or(self.max_backoff, |b| std::cmp::min(b, self.max_backoff));
+        let cap_nanos = std::cmp::min(cap.as_nanos(), u64::MAX as u128) as u64;
+        Duration::from_nanos(rand::thread_rng().gen_range(0, cap_nanos.saturating_add(1)))
+    }
+
+    /// Execute the request generated by `op` on `runtime`, retrying transient failures according
+    /// to the policy.  Failures are reported with the [`ErrorKind`] of the last error.
+    ///
+    /// The `runtime` must use the threaded scheduler, so that concurrent calls (including their
+    /// backoff waits) proceed independently.
+    pub(crate) fn run<T, E, F, Fut>(
+        &self,
+        runtime: &tokio::runtime::Runtime,
+        op: F,
+    ) -> Result<T, TinkError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, RusotoError<E>>>,
+        E: ServiceError + std::error::Error + Send + Sync + 'static,
+    {
+        let start = Instant::now();
+        runtime.handle().block_on(async {
+            let mut attempt = 1;
+            loop {
+                let result = match self.remaining(start) {
+                    None => op().await,
+                    Some(remaining) => match tokio::time::timeout(remaining, op()).await {
+                        Ok(result) => result,
+                        Err(_) => {
+                            return Err(TinkError::new_with_kind(
+                                ErrorKind::Unavailable,
+                                "request failed: deadline exceeded",
+                            ))
+                        }
+                    },
+                };
+                let err = match result {
+                    Ok(rsp) => return Ok(rsp),
+                    Err(e) => e,
+                };
+                let kind = error_kind(&err);
+                if attempt >= self.max_attempts || kind != ErrorKind::Unavailable {
+                    return Err(wrap_err("request failed", err).with_kind(kind));
+                }
+                let delay = self.backoff(attempt);
+                if let Some(remaining) = self.remaining(start) {
+                    if delay >= remaining {
+                        return Err(wrap_err("request failed before deadline", err).with_kind(kind));
+                    }
+                }
+                tokio::time::delay_for(delay).await;
+                attempt += 1;
+            }
+        })
+    }
+
+    /// Return the time remaining before the deadline for a call that started at `start`, or
+    /// `None` if there is no deadline.
+    fn remaining(&self, start: Instant) -> Option<Duration> {
+        self.deadline
+            .map(|d| d.checked_sub(start.elapsed()).unwrap_or_default())
+    }
+}
+
+/// Classify a service-specific error reported by KMS.
+pub(crate) trait ServiceError {
+    fn kind(&self) -> ErrorKind;
+}
+
+macro_rules! impl_service_error {
+    ($error:ident $(, $variant:ident => $kind:ident)*) => {
+        impl ServiceError for rusoto_kms::$error {
+            #[allow(unreachable_patterns)]
+            fn kind(&self) -> ErrorKind {
+                match self {
+                    $(rusoto_kms::$error::$variant(_) => ErrorKind::$kind,)*
+                    rusoto_kms::$error::DependencyTimeout(_)
+                    | rusoto_kms::$error::KMSInternal(_)
+                    | rusoto_kms::$error::KeyUnavailable(_) => ErrorKind::Unavailable,
+                    rusoto_kms::$error::NotFound(_) => ErrorKind::KeyNotFound,
+                    rusoto_kms::$error::Disabled(_)
+                    | rusoto_kms::$error::KMSInvalidState(_)
+                    | rusoto_kms::$error::InvalidKeyUsage(_) => ErrorKind::InvalidKey,
+                    rusoto_kms::$error::InvalidGrantToken(_) => ErrorKind::PermissionDenied,
+                    _ => ErrorKind::Other,
+                }
+            }
+        }
+    };
+}
+
+impl_service_error!(EncryptError);
+impl_service_error!(
+    DecryptError,
+    IncorrectKey => Authentication,
+    InvalidCiphertext => Authentication
+);
+impl_service_error!(SignError);
+impl_service_error!(
+    GetPublicKeyError,
+    InvalidArn => InvalidArgument,
+    UnsupportedOperation => Unsupported
+);
+
+/// Classify a failed request.  Requests that fail with [`ErrorKind::Unavailable`] are transient,
+/// and may succeed if retried.
+fn error_kind<E: ServiceError>(err: &RusotoError<E>) -> ErrorKind {
+    match err {
+        RusotoError::Service(e) => e.kind(),
+        RusotoError::HttpDispatch(_) => ErrorKind::Unavailable,
+        RusotoError::Credentials(_) => ErrorKind::PermissionDenied,
+        RusotoError::Validation(_) => ErrorKind::InvalidArgument,
+        // Error types that are not specific to an operation (such as throttling or access
+        // denied) are not parsed by rusoto, so classify them by the raw response.
+        RusotoError::Unknown(rsp) => {
+            let body = std::str::from_utf8(&rsp.body).unwrap_or("");
+            if rsp.status.as_u16() == 429
+                || rsp.status.is_server_error()
+                || body.contains("ThrottlingException")
+            {
+                ErrorKind::Unavailable
+            } else if rsp.status.as_u16() == 403 || body.contains("AccessDeniedException") {
+                ErrorKind::PermissionDenied
+            } else {
+                ErrorKind::Other
+            }
+        }
+        _ => ErrorKind::Other,
+    }
+}
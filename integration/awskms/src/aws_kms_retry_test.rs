@@ -0,0 +1,205 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+use crate::{
+    fake_kms::{FakeKms, Fault},
+    AwsClient, RetryPolicy,
+};
+use std::time::{Duration, Instant};
+use tink::{Aead, ErrorKind};
+
+const CRED_FILE: &str = "../../testdata/credentials_aws.csv";
+const KEY_ARN: &str = "arn:aws:kms:us-east-2:235739564943:key/3ee50705-5a82-4f5b-9753-05c4f473922f";
+const SIGNING_KEY_ARN: &str =
+    "arn:aws:kms:us-east-2:235739564943:key/4fb5ef3b-1c4d-4b6e-9e4a-6a0c2d8e1f35";
+
+const THROTTLED: Fault = Fault::Error("400 Bad Request", "ThrottlingException");
+const INTERNAL: Fault = Fault::Error("500 Internal Server Error", "KMSInternalException");
+const UNAVAILABLE: Fault = Fault::Error("500 Internal Server Error", "KeyUnavailableException");
+const DISABLED: Fault = Fault::Error("400 Bad Request", "DisabledException");
+
+fn fast_policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(10),
+        deadline: Some(Duration::from_secs(10)),
+    }
+}
+
+fn setup(policy: RetryPolicy) -> (FakeKms, AwsClient) {
+    let fake = FakeKms::start("us-east-2", &[KEY_ARN]);
+    fake.add_signing_key(SIGNING_KEY_ARN);
+    let client = AwsClient::builder("aws-kms://")
+        .credential_path(CRED_FILE)
+        .endpoint(fake.endpoint())
+        .region(fake.region())
+        .retry_policy(policy)
+        .build()
+        .expect("error setting up aws client");
+    (fake, client)
+}
+
+#[test]
+fn test_retry_transient_errors() {
+    let (fake, client) = setup(fast_policy(3));
+    let aead = client
+        .get_aws_aead(&format!("aws-kms://{}", KEY_ARN))
+        .unwrap();
+
+    for fault in &[THROTTLED, INTERNAL, UNAVAILABLE] {
+        fake.inject_faults(&[fault.clone(), fault.clone()]);
+        let before = fake.request_count("Encrypt");
+        let ciphertext = aead
+            .encrypt(b"plaintext", b"")
+            .unwrap_or_else(|e| panic!("encryption should succeed after {:?}: {:?}", fault, e));
+        assert_eq!(fake.request_count("Encrypt") - before, 3);
+        assert_eq!(aead.decrypt(&ciphertext, b"").unwrap(), b"plaintext");
+    }
+}
+
+#[test]
+fn test_retry_gives_up_after_max_attempts() {
+    let (fake, client) = setup(fast_policy(3));
+    let aead = client
+        .get_aws_aead(&format!("aws-kms://{}", KEY_ARN))
+        .unwrap();
+    fake.inject_faults(&[THROTTLED, THROTTLED, THROTTLED, THROTTLED]);
+    let err = aead.encrypt(b"plaintext", b"").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Unavailable);
+    assert_eq!(fake.request_count("Encrypt"), 3);
+    // The remaining fault is consumed by the next call, which then succeeds on retry.
+    assert!(aead.encrypt(b"plaintext", b"").is_ok());
+    assert_eq!(fake.request_count("Encrypt"), 5);
+}
+
+#[test]
+fn test_no_retry_for_permanent_errors() {
+    let (fake, client) = setup(fast_policy(3));
+    let aead = client
+        .get_aws_aead(&format!("aws-kms://{}", KEY_ARN))
+        .unwrap();
+    fake.inject_faults(&[DISABLED]);
+    let err = aead.encrypt(b"plaintext", b"").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidKey);
+    assert_eq!(fake.request_count("Encrypt"), 1);
+
+    // Decryption of an unknown ciphertext fails permanently.
+    let err = aead.decrypt(b"not a ciphertext", b"").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Authentication);
+    assert_eq!(fake.request_count("Decrypt"), 1);
+
+    // Requests for unknown keys fail permanently.
+    let unknown = client
+        .get_aws_aead(&format!(
+            "aws-kms://{}",
+            SIGNING_KEY_ARN.replace("4fb5", "0000")
+        ))
+        .unwrap();
+    let err = unknown.encrypt(b"plaintext", b"").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::KeyNotFound);
+    assert_eq!(fake.request_count("Encrypt"), 2);
+}
+
+#[test]
+fn test_no_retries_policy() {
+    let (fake, client) = setup(RetryPolicy::no_retries());
+    let aead = client
+        .get_aws_aead(&format!("aws-kms://{}", KEY_ARN))
+        .unwrap();
+    fake.inject_faults(&[THROTTLED]);
+    assert!(aead.encrypt(b"plaintext", b"").is_err());
+    assert_eq!(fake.request_count("Encrypt"), 1);
+}
+
+#[test]
+fn test_retry_signer_and_verifier() {
+    let (fake, client) = setup(fast_policy(2));
+    let key_uri = format!("aws-kms://{}", SIGNING_KEY_ARN);
+    fake.inject_faults(&[INTERNAL]);
+    let verifier = client.get_verifier(&key_uri).unwrap();
+    fake.inject_faults(&[THROTTLED]);
+    let signer = client.get_signer(&key_uri).unwrap();
+    assert_eq!(fake.request_count("GetPublicKey"), 4);
+
+    fake.inject_faults(&[UNAVAILABLE]);
+    let signature = signer.sign(b"data").unwrap();
+    assert_eq!(fake.request_count("Sign"), 2);
+    assert!(verifier.verify(&signature, b"data").is_ok());
+}
+
+#[test]
+fn test_deadline() {
+    let (fake, client) = setup(RetryPolicy {
+        deadline: Some(Duration::from_millis(200)),
+        ..fast_policy(5)
+    });
+    let aead = client
+        .get_aws_aead(&format!("aws-kms://{}", KEY_ARN))
+        .unwrap();
+
+    // A slow response is abandoned at the deadline.
+    fake.inject_faults(&[Fault::Delay(Duration::from_secs(2))]);
+    let start = Instant::now();
+    let err = aead.encrypt(b"plaintext", b"").unwrap_err();
+    assert!(format!("{:?}", err).contains("deadline"));
+    assert_eq!(err.kind(), ErrorKind::Unavailable);
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    // Responses within the deadline are unaffected.
+    fake.inject_faults(&[Fault::Delay(Duration::from_millis(10))]);
+    assert!(aead.encrypt(b"plaintext", b"").is_ok());
+}
+
+#[test]
+fn test_concurrent_requests_not_serialized() {
+    let (fake, client) = setup(fast_policy(3));
+    let aead = client
+        .get_aws_aead(&format!("aws-kms://{}", KEY_ARN))
+        .unwrap();
+
+    // A slow request on one thread does not hold up requests on other threads that share the
+    // client's runtime.
+    fake.inject_faults(&[Fault::Delay(Duration::from_secs(2))]);
+    let slow_aead = aead.clone();
+    let slow = std::thread::spawn(move || slow_aead.encrypt(b"slow", b""));
+    while fake.request_count("Encrypt") == 0 {
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    let start = Instant::now();
+    assert!(aead.encrypt(b"plaintext", b"").is_ok());
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(slow.join().unwrap().is_ok());
+}
+
+#[test]
+fn test_backoff_bounds() {
+    let policy = RetryPolicy {
+        max_attempts: 10,
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(1000),
+        deadline: None,
+    };
+    for retry in 1..40 {
+        let cap = std::cmp::min(
+            Duration::from_millis(100 << std::cmp::min(retry - 1, 20)),
+            Duration::from_millis(1000),
+        );
+        for _ in 0..10 {
+            assert!(policy.backoff(retry) <= cap, "retry {}", retry);
+        }
+    }
+}
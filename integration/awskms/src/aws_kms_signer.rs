@@ -0,0 +1,189 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Digital signature functionality via AWS Cloud KMS asymmetric keys.
+
+use rusoto_kms::Kms;
+use sha2::Digest;
+use std::sync::Arc;
+use tink::{
+    proto::{EcdsaSignatureEncoding, EllipticCurveType, HashType},
+    ErrorKind, TinkError,
+};
+
+/// Description of an asymmetric KMS key type that can be used for signatures.
+struct SigningKeySpec {
+    /// KMS name for the key type.
+    key_spec: &'static str,
+    /// KMS name for the signing algorithm used with the key type.
+    signing_algorithm: &'static str,
+    hash: HashType,
+    curve: EllipticCurveType,
+    /// DER encoding of the start of a `SubjectPublicKeyInfo` structure holding a public key of
+    /// this type, up to and including the uncompressed point marker.
+    spki_prefix: &'static [u8],
+}
+
+/// Asymmetric KMS key types that are supported.  Other key types (`ECC_NIST_P384`,
+/// `ECC_NIST_P521`, `ECC_SECG_P256K1`, `RSA_*`) are rejected, as `tink-signature` has no
+/// verifier for them.
+const SIGNING_KEY_SPECS: &[SigningKeySpec] = &[SigningKeySpec {
+    key_spec: "ECC_NIST_P256",
+    signing_algorithm: "ECDSA_SHA_256",
+    hash: HashType::Sha256,
+    curve: EllipticCurveType::NistP256,
+    // SEQUENCE {
+    //   SEQUENCE { OID id-ecPublicKey, OID prime256v1 }
+    //   BIT STRING { 0x04 || x || y }
+    // }
+    spki_prefix: &[
+        0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08,
+        0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00, 0x04,
+    ],
+}];
+
+/// `AwsSigner` produces signatures using an asymmetric AWS KMS key, whose private key never
+/// leaves KMS. Signatures are ASN.1 DER encoded.
+#[derive(Clone)]
+pub struct AwsSigner {
+    key_uri: String,
+    signing_algorithm: &'static str,
+    hash: HashType,
+    kms: rusoto_kms::KmsClient,
+    // The Tokio runtime to execute KMS requests on, shared with the [`AwsClient`](crate::AwsClient)
+    // that created this signer.
+    runtime: Arc<tokio::runtime::Runtime>,
+    retry_policy: crate::RetryPolicy,
+}
+
+impl AwsSigner {
+    /// Return a new signer for the key identified by `key_uri`, whose public key information has
+    /// already been retrieved.
+    pub(crate) fn new(
+        key_uri: &str,
+        key_info: &rusoto_kms::GetPublicKeyResponse,
+        kms: rusoto_kms::KmsClient,
+        runtime: Arc<tokio::runtime::Runtime>,
+        retry_policy: crate::RetryPolicy,
+    ) -> Result<AwsSigner, TinkError> {
+        let spec = signing_key_spec(key_info)?;
+        Ok(AwsSigner {
+            key_uri: key_uri.to_string(),
+            signing_algorithm: spec.signing_algorithm,
+            hash: spec.hash,
+            kms,
+            runtime,
+            retry_policy,
+        })
+    }
+}
+
+impl tink::Signer for AwsSigner {
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, TinkError> {
+        // Send a digest of the data rather than the data itself, as KMS limits the size of
+        // messages that it will sign.
+        let digest = match self.hash {
+            HashType::Sha256 => sha2::Sha256::digest(data).to_vec(),
+            _ => return Err("AwsSigner: unsupported hash".into()),
+        };
+        let req = rusoto_kms::SignRequest {
+            grant_tokens: None,
+            key_id: self.key_uri.clone(),
+            message: digest.into(),
+            message_type: Some("DIGEST".to_string()),
+            signing_algorithm: self.signing_algorithm.to_string(),
+        };
+        let rsp = self
+            .retry_policy
+            .run(&self.runtime, || self.kms.sign(req.clone()))?;
+        match rsp.signature {
+            None => Err("no signature in response".into()),
+            Some(sig) => Ok(sig.to_vec()),
+        }
+    }
+}
+
+/// Retrieve the public key information for `key_id` from KMS.
+pub(crate) fn get_public_key(
+    kms: &rusoto_kms::KmsClient,
+    runtime: &tokio::runtime::Runtime,
+    retry_policy: &crate::RetryPolicy,
+    key_id: &str,
+) -> Result<rusoto_kms::GetPublicKeyResponse, TinkError> {
+    let req = rusoto_kms::GetPublicKeyRequest {
+        grant_tokens: None,
+        key_id: key_id.to_string(),
+    };
+    retry_policy.run(runtime, || kms.get_public_key(req.clone()))
+}
+
+/// Create a local [`tink::Verifier`] for the public key described by `key_info`.
+pub(crate) fn new_verifier(
+    key_info: &rusoto_kms::GetPublicKeyResponse,
+) -> Result<Box<dyn tink::Verifier>, TinkError> {
+    let spec = signing_key_spec(key_info)?;
+    let spki = key_info
+        .public_key
+        .as_ref()
+        .ok_or_else(|| TinkError::new("no public key in response"))?;
+    if !spki.starts_with(spec.spki_prefix) {
+        return Err("invalid public key in response".into());
+    }
+    let point = &spki[spec.spki_prefix.len()..];
+    if point.len() % 2 != 0 {
+        return Err("invalid public key in response".into());
+    }
+    let (x, y) = point.split_at(point.len() / 2);
+    Ok(Box::new(tink_signature::subtle::EcdsaVerifier::new(
+        spec.hash,
+        spec.curve,
+        EcdsaSignatureEncoding::Der,
+        x,
+        y,
+    )?))
+}
+
+/// Find the supported key type that matches the public key information retrieved from KMS.
+fn signing_key_spec(
+    key_info: &rusoto_kms::GetPublicKeyResponse,
+) -> Result<&'static SigningKeySpec, TinkError> {
+    if key_info.key_usage.as_deref() != Some("SIGN_VERIFY") {
+        return Err(TinkError::new_with_kind(
+            ErrorKind::Unsupported,
+            &format!(
+                "key usage must be SIGN_VERIFY, but got {}",
+                key_info.key_usage.as_deref().unwrap_or("none")
+            ),
+        ));
+    }
+    let key_spec = key_info.customer_master_key_spec.as_deref().unwrap_or("");
+    let spec = SIGNING_KEY_SPECS
+        .iter()
+        .find(|s| s.key_spec == key_spec)
+        .ok_or_else(|| {
+            TinkError::new_with_kind(
+                ErrorKind::Unsupported,
+                &format!("unsupported key spec {}", key_spec),
+            )
+        })?;
+    match &key_info.signing_algorithms {
+        Some(algs) if algs.iter().any(|a| a == spec.signing_algorithm) => Ok(spec),
+        _ => Err(TinkError::new_with_kind(
+            ErrorKind::Unsupported,
+            &format!("key does not support {}", spec.signing_algorithm),
+        )),
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+use crate::{fake_kms::FakeKms, AwsClient};
+use tink::subtle::random::get_random_bytes;
+
+const CRED_FILE: &str = "../../testdata/credentials_aws.csv";
+const SIGNING_KEY_ARN: &str =
+    "arn:aws:kms:us-east-2:235739564943:key/4fb5ef3b-1c4d-4b6e-9e4a-6a0c2d8e1f35";
+const OTHER_SIGNING_KEY_ARN: &str =
+    "arn:aws:kms:us-east-2:235739564943:key/0b6d9a8e-2f4c-4a1b-8c3d-7e5f6a4b3c2d";
+const SYMMETRIC_KEY_ARN: &str =
+    "arn:aws:kms:us-east-2:235739564943:key/3ee50705-5a82-4f5b-9753-05c4f473922f";
+
+fn setup() -> (FakeKms, AwsClient) {
+    let fake = FakeKms::start("us-east-2", &[SYMMETRIC_KEY_ARN]);
+    fake.add_signing_key(SIGNING_KEY_ARN);
+    fake.add_signing_key(OTHER_SIGNING_KEY_ARN);
+    let client = AwsClient::builder("aws-kms://")
+        .credential_path(CRED_FILE)
+        .endpoint(fake.endpoint())
+        .region(fake.region())
+        .build()
+        .expect("error setting up aws client");
+    (fake, client)
+}
+
+#[test]
+fn test_sign_verify() {
+    let (fake, client) = setup();
+    let key_uri = format!("aws-kms://{}", SIGNING_KEY_ARN);
+    let signer = client.get_signer(&key_uri).expect("error getting signer");
+    let verifier = client
+        .get_verifier(&key_uri)
+        .expect("error getting verifier");
+    assert_eq!(fake.request_count("GetPublicKey"), 2);
+
+    for data_len in &[0, 1, 100, 10000] {
+        let data = get_random_bytes(*data_len);
+        let signature = signer.sign(&data).expect("error signing");
+        verifier
+            .verify(&signature, &data)
+            .expect("error verifying signature");
+
+        let mut modified_data = data.clone();
+        modified_data.push(0x01);
+        assert!(
+            verifier.verify(&signature, &modified_data).is_err(),
+            "verification should fail for modified data"
+        );
+        let mut modified_signature = signature.clone();
+        let last = modified_signature.len() - 1;
+        modified_signature[last] ^= 0x01;
+        assert!(
+            verifier.verify(&modified_signature, &data).is_err(),
+            "verification should fail for modified signature"
+        );
+    }
+    // Verification happens locally.
+    assert_eq!(fake.request_count("Sign"), 4);
+    assert_eq!(fake.request_count("GetPublicKey"), 2);
+}
+
+#[test]
+fn test_sign_verify_with_alias() {
+    let (fake, client) = setup();
+    fake.add_alias("alias/release-signing", SIGNING_KEY_ARN);
+    let signer = client
+        .get_signer("aws-kms://alias/release-signing")
+        .expect("error getting signer");
+    let verifier = client
+        .get_verifier(&format!("aws-kms://{}", SIGNING_KEY_ARN))
+        .expect("error getting verifier");
+    let signature = signer.sign(b"data").unwrap();
+    assert!(verifier.verify(&signature, b"data").is_ok());
+}
+
+#[test]
+fn test_verify_with_wrong_key() {
+    let (_fake, client) = setup();
+    let signer = client
+        .get_signer(&format!("aws-kms://{}", SIGNING_KEY_ARN))
+        .unwrap();
+    let verifier = client
+        .get_verifier(&format!("aws-kms://{}", OTHER_SIGNING_KEY_ARN))
+        .unwrap();
+    let signature = signer.sign(b"data").unwrap();
+    assert!(verifier.verify(&signature, b"data").is_err());
+}
+
+#[test]
+fn test_signature_is_der_encoded() {
+    let (_fake, client) = setup();
+    let key_uri = format!("aws-kms://{}", SIGNING_KEY_ARN);
+    let signer = client.get_signer(&key_uri).unwrap();
+    let signature = signer.sign(b"data").unwrap();
+    assert_eq!(signature[0], 0x30, "signature should be a DER SEQUENCE");
+    let verifier = client.get_verifier(&key_uri).unwrap();
+    assert!(verifier.verify(&signature, b"data").is_ok());
+    assert!(verifier.verify(&signature[1..], b"data").is_err());
+}
+
+#[test]
+fn test_unknown_or_unsuitable_keys() {
+    let (_fake, client) = setup();
+    let unknown_key_uri =
+        "aws-kms://arn:aws:kms:us-east-2:235739564943:key/00000000-0000-0000-0000-000000000000";
+    assert!(client.get_signer(unknown_key_uri).is_err());
+    assert!(client.get_verifier(unknown_key_uri).is_err());
+
+    // Symmetric keys cannot be used for signatures.
+    let symmetric_key_uri = format!("aws-kms://{}", SYMMETRIC_KEY_ARN);
+    assert!(client.get_signer(&symmetric_key_uri).is_err());
+    assert!(client.get_verifier(&symmetric_key_uri).is_err());
+}
+
+#[test]
+fn test_get_signer_non_supported_uri() {
+    let uri_prefix = "aws-kms://arn:aws-us-gov:kms:us-gov-east-1:235739564943:key/";
+    let non_supported_key_uri = "aws-kms://arn:aws-us-gov:kms:us-gov-east-DOES-NOT-EXIST:key/";
+
+    let client = AwsClient::new(uri_prefix).unwrap();
+    assert!(
+        client.get_signer(non_supported_key_uri).is_err(),
+        "client with URI prefix {} should NOT support key URI {}",
+        uri_prefix,
+        non_supported_key_uri
+    );
+    assert!(
+        client.get_verifier(non_supported_key_uri).is_err(),
+        "client with URI prefix {} should NOT support key URI {}",
+        uri_prefix,
+        non_supported_key_uri
+    );
+}
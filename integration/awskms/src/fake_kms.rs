@@ -0,0 +1,363 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Minimal local stand-in for the AWS KMS JSON API, allowing tests to run offline.
+//!
+//! Ciphertexts are opaque handles into the server's state, so the server enforces the same
+//! key and encryption context binding as the real service.
+
+use ecdsa::hazmat::SignPrimitive;
+use p256::{
+    elliptic_curve::{ff::Field, sec1::ToEncodedPoint},
+    ProjectivePoint, Scalar,
+};
+use serde_json::{json, Value};
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Record of a ciphertext issued by the fake KMS.
+struct Record {
+    key_arn: String,
+    plaintext: Vec<u8>,
+    context: Value,
+}
+
+/// Fault to inject into the handling of a request.
+#[derive(Clone, Debug)]
+pub(crate) enum Fault {
+    /// Fail the request with the given HTTP status and KMS error type.
+    Error(&'static str, &'static str),
+    /// Wait for the given duration before handling the request normally.
+    Delay(Duration),
+}
+
+#[derive(Default)]
+struct State {
+    /// Known symmetric key ARNs.
+    keys: Vec<String>,
+    /// Known ECDSA P-256 signing keys, indexed by key ARN.
+    signing_keys: HashMap<String, Scalar>,
+    /// Map from alias (name or ARN) to key ARN.
+    aliases: HashMap<String, String>,
+    /// Issued ciphertexts.
+    ciphertexts: HashMap<Vec<u8>, Record>,
+    /// Number of requests received, indexed by operation name.
+    requests: HashMap<String, usize>,
+    /// Faults to inject, one per request, in order of arrival.
+    faults: VecDeque<Fault>,
+}
+
+/// Fake KMS server listening on a local port.
+pub(crate) struct FakeKms {
+    endpoint: String,
+    region: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeKms {
+    /// Start a fake KMS server for the given `region`, holding the given keys.
+    pub(crate) fn start(region: &str, key_arns: &[&str]) -> FakeKms {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("failed to bind fake KMS listener");
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(State {
+            keys: key_arns.iter().map(|k| k.to_string()).collect(),
+            ..Default::default()
+        }));
+        let server_state = state.clone();
+        let server_region = region.to_string();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(s) => s,
+                    Err(_) => return,
+                };
+                let state = server_state.clone();
+                let region = server_region.clone();
+                std::thread::spawn(move || serve_connection(stream, &region, &state));
+            }
+        });
+        FakeKms {
+            endpoint,
+            region: region.to_string(),
+            state,
+        }
+    }
+
+    /// Endpoint URL for the server.
+    pub(crate) fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Region that the server simulates.
+    pub(crate) fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// Make `alias` (an alias name or alias ARN) refer to `key_arn`.
+    pub(crate) fn add_alias(&self, alias: &str, key_arn: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.aliases.insert(alias.to_string(), key_arn.to_string());
+    }
+
+    /// Add an ECDSA P-256 signing key with the given ARN.
+    pub(crate) fn add_signing_key(&self, key_arn: &str) {
+        let mut state = self.state.lock().unwrap();
+        let private_key = Scalar::random(rand::rngs::OsRng);
+        state.signing_keys.insert(key_arn.to_string(), private_key);
+    }
+
+    /// Inject `faults` into the handling of the next requests, one fault per request.
+    pub(crate) fn inject_faults(&self, faults: &[Fault]) {
+        let mut state = self.state.lock().unwrap();
+        state.faults.extend(faults.iter().cloned());
+    }
+
+    /// Number of requests received for the given operation (e.g. "Encrypt").
+    pub(crate) fn request_count(&self, operation: &str) -> usize {
+        let state = self.state.lock().unwrap();
+        *state.requests.get(operation).unwrap_or(&0)
+    }
+}
+
+/// Handle the (possibly multiple, keep-alive) requests on a connection.
+fn serve_connection(stream: TcpStream, region: &str, state: &Mutex<State>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut stream = stream;
+    loop {
+        let mut target = String::new();
+        let mut content_length = 0;
+        let mut line = String::new();
+        // Request line.
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+        // Headers.
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).is_err() {
+                return;
+            }
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            let mut parts = header.splitn(2, ':');
+            if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+                match name.to_lowercase().as_ref() {
+                    "x-amz-target" => target = value.trim().to_string(),
+                    "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                    _ => {}
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        if reader.read_exact(&mut body).is_err() {
+            return;
+        }
+        let request: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+        let operation = target.trim_start_matches("TrentService.").to_string();
+        let fault = {
+            let mut state = state.lock().unwrap();
+            *state.requests.entry(operation.clone()).or_insert(0) += 1;
+            state.faults.pop_front()
+        };
+        let (status, response) = match fault {
+            Some(Fault::Error(status, kind)) => (status, json!({"__type": kind, "message": kind})),
+            Some(Fault::Delay(delay)) => {
+                std::thread::sleep(delay);
+                handle(&operation, &request, region, &mut state.lock().unwrap())
+            }
+            None => handle(&operation, &request, region, &mut state.lock().unwrap()),
+        };
+        let payload = response.to_string();
+        let reply = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/x-amz-json-1.1\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            payload.len(),
+            payload
+        );
+        if stream.write_all(reply.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+fn error(kind: &str, msg: &str) -> (&'static str, Value) {
+    ("400 Bad Request", json!({"__type": kind, "message": msg}))
+}
+
+/// Process a single KMS operation.
+fn handle(
+    operation: &str,
+    request: &Value,
+    region: &str,
+    state: &mut State,
+) -> (&'static str, Value) {
+    let key_id = request["KeyId"].as_str().unwrap_or("");
+    match operation {
+        "Encrypt" => {
+            let key_arn = match resolve_key(state, key_id) {
+                Some(k) => k,
+                None => return error("NotFoundException", "key not found"),
+            };
+            let plaintext = base64::decode(request["Plaintext"].as_str().unwrap_or("")).unwrap();
+            let handle = tink::subtle::random::get_random_bytes(32);
+            state.ciphertexts.insert(
+                handle.clone(),
+                Record {
+                    key_arn: key_arn.clone(),
+                    plaintext,
+                    context: request["EncryptionContext"].clone(),
+                },
+            );
+            (
+                "200 OK",
+                json!({"CiphertextBlob": base64::encode(&handle), "KeyId": key_arn}),
+            )
+        }
+        "Decrypt" => {
+            let handle = base64::decode(request["CiphertextBlob"].as_str().unwrap_or("")).unwrap();
+            let record = match state.ciphertexts.get(&handle) {
+                Some(r) => r,
+                None => return error("InvalidCiphertextException", "unknown ciphertext"),
+            };
+            if record.context != request["EncryptionContext"] {
+                return error("InvalidCiphertextException", "encryption context mismatch");
+            }
+            let key_arn = if key_id.is_empty() {
+                record.key_arn.clone()
+            } else {
+                match resolve_key(state, key_id) {
+                    Some(k) => k,
+                    None => return error("NotFoundException", "key not found"),
+                }
+            };
+            // Replicas of a multi-region key can decrypt each other's ciphertexts; the response
+            // identifies the replica in the server's region.
+            let key_arn = if same_multi_region_key(&key_arn, &record.key_arn) {
+                in_region(&key_arn, region)
+            } else if key_arn == record.key_arn {
+                key_arn
+            } else {
+                return error("IncorrectKeyException", "incorrect key");
+            };
+            (
+                "200 OK",
+                json!({"Plaintext": base64::encode(&record.plaintext), "KeyId": key_arn}),
+            )
+        }
+        "GetPublicKey" => {
+            let (key_arn, private_key) = match resolve_signing_key(state, key_id) {
+                Some(k) => k,
+                None => return error("NotFoundException", "key not found"),
+            };
+            let public_key = (ProjectivePoint::generator() * private_key).to_affine();
+            let mut spki =
+                hex::decode("3059301306072a8648ce3d020106082a8648ce3d030107034200").unwrap();
+            spki.extend_from_slice(public_key.to_encoded_point(false).as_bytes());
+            (
+                "200 OK",
+                json!({
+                    "CustomerMasterKeySpec": "ECC_NIST_P256",
+                    "KeyId": key_arn,
+                    "KeyUsage": "SIGN_VERIFY",
+                    "PublicKey": base64::encode(&spki),
+                    "SigningAlgorithms": ["ECDSA_SHA_256"],
+                }),
+            )
+        }
+        "Sign" => {
+            let (key_arn, private_key) = match resolve_signing_key(state, key_id) {
+                Some(k) => k,
+                None => return error("NotFoundException", "key not found"),
+            };
+            if request["SigningAlgorithm"] != "ECDSA_SHA_256" || request["MessageType"] != "DIGEST"
+            {
+                return error("ValidationException", "unsupported signing request");
+            }
+            let digest = base64::decode(request["Message"].as_str().unwrap_or("")).unwrap();
+            if digest.len() != 32 {
+                return error("ValidationException", "invalid digest length");
+            }
+            let z = Scalar::from_bytes_reduced(p256::FieldBytes::from_slice(&digest));
+            let k = Scalar::random(rand::rngs::OsRng);
+            let signature = private_key.try_sign_prehashed(&k, &z).unwrap();
+            (
+                "200 OK",
+                json!({
+                    "KeyId": key_arn,
+                    "Signature": base64::encode(signature.to_asn1().as_bytes()),
+                    "SigningAlgorithm": "ECDSA_SHA_256",
+                }),
+            )
+        }
+        _ => error("UnsupportedOperationException", operation),
+    }
+}
+
+fn resolve_key(state: &State, key_id: &str) -> Option<String> {
+    if let Some(key_arn) = state.aliases.get(key_id) {
+        return Some(key_arn.clone());
+    }
+    state
+        .keys
+        .iter()
+        .find(|k| *k == key_id || same_multi_region_key(k, key_id))
+        .cloned()
+}
+
+fn resolve_signing_key(state: &State, key_id: &str) -> Option<(String, Scalar)> {
+    let key_arn = state
+        .aliases
+        .get(key_id)
+        .map(|k| k.as_str())
+        .unwrap_or(key_id);
+    state
+        .signing_keys
+        .get(key_arn)
+        .map(|k| (key_arn.to_string(), *k))
+}
+
+fn same_multi_region_key(arn1: &str, arn2: &str) -> bool {
+    let strip = |arn: &str| {
+        let parts: Vec<&str> = arn.splitn(6, ':').collect();
+        if parts.len() == 6 && parts[5].starts_with("key/mrk-") {
+            Some((
+                parts[1].to_string(),
+                parts[4].to_string(),
+                parts[5].to_string(),
+            ))
+        } else {
+            None
+        }
+    };
+    match (strip(arn1), strip(arn2)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn in_region(arn: &str, region: &str) -> String {
+    let mut parts: Vec<&str> = arn.splitn(6, ':').collect();
+    parts[3] = region;
+    parts.join(":")
+}
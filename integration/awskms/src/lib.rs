@@ -17,14 +17,24 @@
 //! Provide integration with the AWS Cloud KMS.
 
 mod aws_kms_aead;
-use aws_kms_aead::*;
+pub use aws_kms_aead::*;
 mod aws_kms_client;
 pub use aws_kms_client::*;
+mod aws_kms_retry;
+pub use aws_kms_retry::*;
+mod aws_kms_signer;
+pub use aws_kms_signer::*;
 
 #[cfg(test)]
 mod aws_kms_aead_test;
 #[cfg(test)]
 mod aws_kms_client_test;
+#[cfg(test)]
+mod aws_kms_retry_test;
+#[cfg(test)]
+mod aws_kms_signer_test;
+#[cfg(test)]
+mod fake_kms;
 
 /// The [upstream Tink](https://github.com/google/tink) version that this Rust
 /// port is based on.
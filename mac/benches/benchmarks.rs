@@ -28,6 +28,26 @@ fn setup(kt: tink::proto::KeyTemplate) -> (Box<dyn tink::Mac>, Vec<u8>) {
     (m, tag)
 }
 
+/// Build a MAC from a keyset with several rotated keys, returning tags produced by the primary
+/// key and by a RAW key.
+fn setup_rotated(kt: tink::proto::KeyTemplate) -> (Box<dyn tink::Mac>, Vec<u8>, Vec<u8>) {
+    tink_mac::init();
+    let mut raw_kt = kt.clone();
+    raw_kt.output_prefix_type = tink::proto::OutputPrefixType::Raw as i32;
+    let mut ksm = tink::keyset::Manager::new();
+    ksm.rotate(&raw_kt).unwrap();
+    let raw_tag = tink_mac::new(&ksm.handle().unwrap())
+        .unwrap()
+        .compute_mac(MSG)
+        .unwrap();
+    for _ in 0..8 {
+        ksm.rotate(&kt).unwrap();
+    }
+    let m = tink_mac::new(&ksm.handle().unwrap()).unwrap();
+    let tag = m.compute_mac(MSG).unwrap();
+    (m, tag, raw_tag)
+}
+
 #[bench]
 fn bench_hmac_sha256_tag128_mac_compute(b: &mut Bencher) {
     let (m, _tag) = setup(tink_mac::hmac_sha256_tag128_key_template());
@@ -87,3 +107,15 @@ fn bench_aes_cmac_tag128_mac_verify(b: &mut Bencher) {
     let (m, tag) = setup(tink_mac::aes_cmac_tag128_key_template());
     b.iter(|| m.verify_mac(&tag, MSG).unwrap());
 }
+
+#[bench]
+fn bench_rotated_keyset_mac_verify(b: &mut Bencher) {
+    let (m, tag, _raw_tag) = setup_rotated(tink_mac::hmac_sha256_tag256_key_template());
+    b.iter(|| m.verify_mac(&tag, MSG).unwrap());
+}
+
+#[bench]
+fn bench_rotated_keyset_mac_verify_raw(b: &mut Bencher) {
+    let (m, _tag, raw_tag) = setup_rotated(tink_mac::hmac_sha256_tag256_key_template());
+    b.iter(|| m.verify_mac(&raw_tag, MSG).unwrap());
+}
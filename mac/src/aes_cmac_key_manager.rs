@@ -77,6 +77,14 @@ impl tink::registry::KeyManager for AesCmacKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
+
+    fn version(&self) -> u32 {
+        CMAC_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "Mac"
+    }
 }
 
 /// Validate the given [`AesCmacKey`](tink::proto::AesCmacKey). It only validates the version of the
@@ -17,7 +17,7 @@
 //! Provides an implementation of MAC using a set of underlying implementations.
 
 use std::sync::Arc;
-use tink::{proto::OutputPrefixType, utils::wrap_err, TinkError};
+use tink::{monitoring::Logger, proto::OutputPrefixType, utils::wrap_err, ErrorKind, TinkError};
 
 /// Create a [`tink::Mac`] primitive from the given keyset handle.
 pub fn new(h: &tink::keyset::Handle) -> Result<Box<dyn tink::Mac>, TinkError> {
@@ -37,32 +37,82 @@ pub fn new_with_key_manager(
     Ok(Box::new(ret))
 }
 
+/// Create a [`tink::Mac`] primitive from the given keyset handle, using the key managers in the
+/// given registry configuration.
+pub fn new_with_config(
+    h: &tink::keyset::Handle,
+    config: &tink::registry::Registry,
+) -> Result<Box<dyn tink::Mac>, TinkError> {
+    let ps = h
+        .primitives_with_config(config)
+        .map_err(|e| wrap_err("mac::factory: cannot obtain primitive set", e))?;
+
+    let ret = WrappedMac::new(ps)?;
+    Ok(Box::new(ret))
+}
+
+/// [`PrimitiveWrapper`](tink::registry::PrimitiveWrapper) that combines the primitives for the keys
+/// in a keyset into a single [`tink::Mac`] primitive. Registered by [`init`](crate::init).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MacWrapper;
+
+impl tink::registry::PrimitiveWrapper for MacWrapper {
+    type Primitive = dyn tink::Mac;
+
+    fn wrap(&self, ps: tink::primitiveset::PrimitiveSet) -> Result<Box<dyn tink::Mac>, TinkError> {
+        Ok(Box::new(WrappedMac::new(ps)?))
+    }
+}
+
 /// A [`tink::Mac`] implementation that uses the underlying primitive set to compute and
 /// verify MACs.
 #[derive(Clone)]
 struct WrappedMac {
     ps: tink::primitiveset::PrimitiveSet,
+    compute_logger: Arc<dyn Logger>,
+    verify_logger: Arc<dyn Logger>,
 }
 
 impl WrappedMac {
     fn new(ps: tink::primitiveset::PrimitiveSet) -> Result<WrappedMac, TinkError> {
         let entry = match &ps.primary {
-            None => return Err("mac::factory: no primary primitive".into()),
+            None => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "mac::factory: no primary primitive",
+                ))
+            }
             Some(p) => p,
         };
         match entry.primitive {
             tink::Primitive::Mac(_) => {}
-            _ => return Err("mac::factory: not a Mac primitive".into()),
+            _ => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "mac::factory: not a Mac primitive",
+                ))
+            }
         };
-        for (_, primitives) in ps.entries.iter() {
+        for (_, primitives) in ps.entries().iter() {
             for p in primitives {
                 match p.primitive {
                     tink::Primitive::Mac(_) => {}
-                    _ => return Err("mac::factory: not a Mac primitive".into()),
+                    _ => {
+                        return Err(TinkError::new_with_kind(
+                            ErrorKind::InvalidKeyset,
+                            "mac::factory: not a Mac primitive",
+                        ))
+                    }
                 };
             }
         }
-        Ok(WrappedMac { ps })
+        let compute_logger = tink::monitoring::new_logger(&ps, "mac", "compute")?;
+        let verify_logger = tink::monitoring::new_logger(&ps, "mac", "verify")?;
+        Ok(WrappedMac {
+            ps,
+            compute_logger,
+            verify_logger,
+        })
     }
 }
 
@@ -70,22 +120,40 @@ impl tink::Mac for WrappedMac {
     fn compute_mac(&self, data: &[u8]) -> Result<Vec<u8>, TinkError> {
         let primary = match &self.ps.primary {
             Some(p) => p,
-            None => return Err("mac::factory: no primary primitive".into()),
+            None => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "mac::factory: no primary primitive",
+                ))
+            }
         };
         let primitive = match &primary.primitive {
             tink::Primitive::Mac(p) => p,
-            _ => return Err("mac::factory: not a Mac primitive".into()),
+            _ => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "mac::factory: not a Mac primitive",
+                ))
+            }
         };
-        let mac = if primary.prefix_type == OutputPrefixType::Legacy {
+        let result = if primary.prefix_type == OutputPrefixType::Legacy {
             // This diverges from the upstream Go code (as of v1.5.0), but matches the
             // behaviour of the upstream C++/Java/Python code.
             let mut local_data = Vec::with_capacity(data.len() + 1);
             local_data.extend_from_slice(data);
             local_data.push(tink::cryptofmt::LEGACY_START_BYTE);
-            primitive.compute_mac(&local_data)?
+            primitive.compute_mac(&local_data)
         } else {
-            primitive.compute_mac(data)?
+            primitive.compute_mac(data)
+        };
+        let mac = match result {
+            Ok(mac) => mac,
+            Err(e) => {
+                self.compute_logger.log_failure();
+                return Err(e);
+            }
         };
+        self.compute_logger.log(primary.key_id, data.len());
 
         let mut ret = Vec::with_capacity(primary.prefix.len() + mac.len());
         ret.extend_from_slice(&primary.prefix);
@@ -98,15 +166,20 @@ impl tink::Mac for WrappedMac {
         // clearly insecure, thus should be discouraged.
         let prefix_size = tink::cryptofmt::NON_RAW_PREFIX_SIZE;
         if mac.len() <= prefix_size {
-            return Err("mac::factory: invalid mac".into());
+            self.verify_logger.log_failure();
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "mac::factory: invalid mac",
+            ));
         }
+        let mut tried = false;
 
         // try non raw keys
         let prefix = &mac[..prefix_size];
         let mac_no_prefix = &mac[prefix_size..];
-        let entries = self.ps.entries_for_prefix(&prefix);
-        for entry in &entries {
+        for entry in self.ps.entries_for_prefix(prefix) {
             if let tink::Primitive::Mac(p) = &entry.primitive {
+                tried = true;
                 let result = if entry.prefix_type == OutputPrefixType::Legacy {
                     // This diverges from the upstream Go code (as of v1.5.0), but matches the
                     // behaviour of the upstream C++/Java/Python code.
@@ -118,16 +191,20 @@ impl tink::Mac for WrappedMac {
                     p.verify_mac(mac_no_prefix, data)
                 };
                 if result.is_ok() {
+                    self.verify_logger.log(entry.key_id, data.len());
                     return Ok(());
                 }
             } else {
-                return Err("mac::factory: not a Mac primitive".into());
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "mac::factory: not a Mac primitive",
+                ));
             }
         }
 
-        let entries = self.ps.raw_entries();
-        for entry in &entries {
+        for entry in self.ps.raw_entries() {
             if let tink::Primitive::Mac(p) = &entry.primitive {
+                tried = true;
                 let result = if entry.prefix_type == OutputPrefixType::Legacy {
                     // This diverges from the upstream Go code (as of v1.5.0), but matches the
                     // behaviour of the upstream C++/Java/Python code.
@@ -139,14 +216,211 @@ impl tink::Mac for WrappedMac {
                     p.verify_mac(mac, data)
                 };
                 if result.is_ok() {
+                    self.verify_logger.log(entry.key_id, data.len());
                     return Ok(());
                 }
             } else {
-                return Err("mac::factory: not a Mac primitive".into());
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "mac::factory: not a Mac primitive",
+                ));
             }
         }
 
         // nothing worked
-        Err("mac::factory: decryption failed".into())
+        self.verify_logger.log_failure();
+        Err(TinkError::new_with_kind(
+            if tried {
+                ErrorKind::Authentication
+            } else {
+                ErrorKind::KeyNotFound
+            },
+            "mac::factory: decryption failed",
+        ))
+    }
+
+    fn new_computation(&self) -> Result<Box<dyn tink::MacComputation>, TinkError> {
+        let primary = match &self.ps.primary {
+            Some(p) => p,
+            None => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "mac::factory: no primary primitive",
+                ))
+            }
+        };
+        let computation = match &primary.primitive {
+            tink::Primitive::Mac(p) => p.new_computation(),
+            _ => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "mac::factory: not a Mac primitive",
+                ))
+            }
+        };
+        let computation = match computation {
+            Ok(c) => c,
+            Err(e) => {
+                self.compute_logger.log_failure();
+                return Err(e);
+            }
+        };
+        Ok(Box::new(WrappedMacComputation {
+            entry: primary.clone(),
+            computation,
+            logger: self.compute_logger.clone(),
+            data_len: 0,
+        }))
+    }
+
+    fn new_verification(&self, mac: &[u8]) -> Result<Box<dyn tink::MacVerification>, TinkError> {
+        // As for `verify_mac`, reject MACs of the size of a prefix or less.
+        let prefix_size = tink::cryptofmt::NON_RAW_PREFIX_SIZE;
+        if mac.len() <= prefix_size {
+            self.verify_logger.log_failure();
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "mac::factory: invalid mac",
+            ));
+        }
+
+        // Verify in parallel against every key that could have produced the MAC.
+        let prefix = &mac[..prefix_size];
+        let mac_no_prefix = &mac[prefix_size..];
+        let candidates = self
+            .ps
+            .entries_for_prefix(prefix)
+            .iter()
+            .map(|entry| (entry, mac_no_prefix))
+            .chain(self.ps.raw_entries().iter().map(|entry| (entry, mac)));
+        let mut tried = false;
+        let mut verifications = Vec::new();
+        for (entry, entry_mac) in candidates {
+            let p = match &entry.primitive {
+                tink::Primitive::Mac(p) => p,
+                _ => {
+                    return Err(TinkError::new_with_kind(
+                        ErrorKind::InvalidKeyset,
+                        "mac::factory: not a Mac primitive",
+                    ))
+                }
+            };
+            tried = true;
+            // A MAC that this key cannot start verifying (e.g. one of the wrong length) just means
+            // that it was not produced by this key.
+            if let Ok(verification) = p.new_verification(entry_mac) {
+                verifications.push((entry.clone(), verification));
+            }
+        }
+        if verifications.is_empty() {
+            self.verify_logger.log_failure();
+            return Err(TinkError::new_with_kind(
+                if tried {
+                    ErrorKind::Authentication
+                } else {
+                    ErrorKind::KeyNotFound
+                },
+                "mac::factory: invalid mac",
+            ));
+        }
+        Ok(Box::new(WrappedMacVerification {
+            verifications,
+            logger: self.verify_logger.clone(),
+            data_len: 0,
+        }))
+    }
+}
+
+/// Incremental MAC computation with the primary key of a keyset.
+struct WrappedMacComputation {
+    entry: Arc<tink::primitiveset::Entry>,
+    computation: Box<dyn tink::MacComputation>,
+    logger: Arc<dyn Logger>,
+    data_len: usize,
+}
+
+impl tink::MacComputation for WrappedMacComputation {
+    fn update(&mut self, data: &[u8]) -> Result<(), TinkError> {
+        self.data_len += data.len();
+        self.computation.update(data)
+    }
+
+    fn compute_mac(self: Box<Self>) -> Result<Vec<u8>, TinkError> {
+        let WrappedMacComputation {
+            entry,
+            mut computation,
+            logger,
+            data_len,
+        } = *self;
+        let result = if entry.prefix_type == OutputPrefixType::Legacy {
+            // As for `compute_mac`, LEGACY keys authenticate the data followed by a zero byte.
+            computation
+                .update(&[tink::cryptofmt::LEGACY_START_BYTE])
+                .and_then(|_| computation.compute_mac())
+        } else {
+            computation.compute_mac()
+        };
+        let mac = match result {
+            Ok(mac) => mac,
+            Err(e) => {
+                logger.log_failure();
+                return Err(e);
+            }
+        };
+        logger.log(entry.key_id, data_len);
+
+        let mut ret = Vec::with_capacity(entry.prefix.len() + mac.len());
+        ret.extend_from_slice(&entry.prefix);
+        ret.extend_from_slice(&mac);
+        Ok(ret)
+    }
+}
+
+/// Incremental MAC verification against all the keys of a keyset that match the MAC.
+struct WrappedMacVerification {
+    verifications: Vec<(
+        Arc<tink::primitiveset::Entry>,
+        Box<dyn tink::MacVerification>,
+    )>,
+    logger: Arc<dyn Logger>,
+    data_len: usize,
+}
+
+impl tink::MacVerification for WrappedMacVerification {
+    fn update(&mut self, data: &[u8]) -> Result<(), TinkError> {
+        self.data_len += data.len();
+        // A candidate that fails to process the data cannot verify the MAC, but another one may
+        // still do so, so just drop it (and leave `verify_mac` to fail if none are left).
+        self.verifications = std::mem::take(&mut self.verifications)
+            .into_iter()
+            .filter_map(|(entry, mut verification)| {
+                verification
+                    .update(data)
+                    .ok()
+                    .map(|_| (entry, verification))
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn verify_mac(self: Box<Self>) -> Result<(), TinkError> {
+        for (entry, mut verification) in self.verifications {
+            if entry.prefix_type == OutputPrefixType::Legacy
+                && verification
+                    .update(&[tink::cryptofmt::LEGACY_START_BYTE])
+                    .is_err()
+            {
+                continue;
+            }
+            if verification.verify_mac().is_ok() {
+                self.logger.log(entry.key_id, self.data_len);
+                return Ok(());
+            }
+        }
+        self.logger.log_failure();
+        Err(TinkError::new_with_kind(
+            ErrorKind::Authentication,
+            "mac::factory: verification failed",
+        ))
     }
 }
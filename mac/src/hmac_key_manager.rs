@@ -79,6 +79,25 @@ impl tink::registry::KeyManager for HmacKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
+
+    fn version(&self) -> u32 {
+        HMAC_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "Mac"
+    }
+
+    fn fips_compatible(&self) -> bool {
+        true
+    }
+
+    fn fips_compatible_key(&self, serialized_key: &[u8]) -> bool {
+        tink::proto::HmacKey::decode(serialized_key)
+            .ok()
+            .and_then(|key| key.params.as_ref().map(|params| params.hash))
+            .map_or(false, tink::fips::is_approved_hash)
+    }
 }
 
 /// Validate the given [`HmacKey`](tink::proto::HmacKey). It only validates the version of the
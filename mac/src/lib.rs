@@ -45,6 +45,8 @@ static INIT: Once = Once::new();
 /// Tink.
 pub fn init() {
     INIT.call_once(|| {
+        tink::registry::register_primitive_wrapper(MacWrapper).expect("tink_mac::init() failed"); // safe: init
+
         tink::registry::register_key_manager(std::sync::Arc::new(HmacKeyManager::default()))
             .expect("tink_mac::init() failed"); // safe: init
         tink::registry::register_key_manager(std::sync::Arc::new(AesCmacKeyManager::default()))
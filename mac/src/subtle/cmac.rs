@@ -60,6 +60,30 @@ impl tink::Mac for AesCmac {
     fn compute_mac(&self, data: &[u8]) -> Result<Vec<u8>, TinkError> {
         self.prf.compute_prf(data, self.tag_size)
     }
+
+    fn new_computation(&self) -> Result<Box<dyn tink::MacComputation>, TinkError> {
+        Ok(Box::new(AesCmacComputation {
+            computation: self.prf.new_computation(),
+            tag_size: self.tag_size,
+        }))
+    }
+}
+
+/// Incremental computation of a MAC with [`AesCmac`].
+struct AesCmacComputation {
+    computation: tink_prf::subtle::AesCmacPrfComputation,
+    tag_size: usize,
+}
+
+impl tink::MacComputation for AesCmacComputation {
+    fn update(&mut self, data: &[u8]) -> Result<(), TinkError> {
+        self.computation.update(data);
+        Ok(())
+    }
+
+    fn compute_mac(self: Box<Self>) -> Result<Vec<u8>, TinkError> {
+        self.computation.finalize(self.tag_size)
+    }
 }
 
 /// Validate the parameters for an AES-CMAC against the recommended parameters.
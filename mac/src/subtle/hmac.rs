@@ -68,4 +68,28 @@ impl tink::Mac for Hmac {
     fn compute_mac(&self, data: &[u8]) -> Result<Vec<u8>, TinkError> {
         self.prf.compute_prf(data, self.tag_size)
     }
+
+    fn new_computation(&self) -> Result<Box<dyn tink::MacComputation>, TinkError> {
+        Ok(Box::new(HmacComputation {
+            computation: self.prf.new_computation(),
+            tag_size: self.tag_size,
+        }))
+    }
+}
+
+/// Incremental computation of a MAC with [`Hmac`].
+struct HmacComputation {
+    computation: tink_prf::subtle::HmacPrfComputation,
+    tag_size: usize,
+}
+
+impl tink::MacComputation for HmacComputation {
+    fn update(&mut self, data: &[u8]) -> Result<(), TinkError> {
+        self.computation.update(data);
+        Ok(())
+    }
+
+    fn compute_mac(self: Box<Self>) -> Result<Vec<u8>, TinkError> {
+        self.computation.finalize(self.tag_size)
+    }
 }
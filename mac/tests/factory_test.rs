@@ -144,3 +144,175 @@ fn test_factory_with_valid_primitive_set_type() {
 
     tink_mac::new(&good_kh).expect("calling new() with good keyset::Handle failed");
 }
+
+/// Compute a MAC over `data` in chunks.
+fn chunked_mac(p: &dyn tink::Mac, data: &[u8]) -> Result<Vec<u8>, TinkError> {
+    let mut computation = p.new_computation()?;
+    for chunk in data.chunks(10) {
+        computation.update(chunk)?;
+    }
+    computation.compute_mac()
+}
+
+/// Verify a MAC over `data` in chunks.
+fn chunked_verify(p: &dyn tink::Mac, mac: &[u8], data: &[u8]) -> Result<(), TinkError> {
+    let mut verification = p.new_verification(mac)?;
+    for chunk in data.chunks(10) {
+        verification.update(chunk)?;
+    }
+    verification.verify_mac()
+}
+
+#[test]
+fn test_factory_chunked() {
+    tink_mac::init();
+    let data = tink::subtle::random::get_random_bytes(100);
+    for prefix_type in &[
+        tink::proto::OutputPrefixType::Tink,
+        tink::proto::OutputPrefixType::Legacy,
+        tink::proto::OutputPrefixType::Raw,
+        tink::proto::OutputPrefixType::Crunchy,
+    ] {
+        let keyset = tink_testutil::new_test_hmac_keyset(16, *prefix_type);
+        let keyset_handle = tink::keyset::insecure::new_handle(keyset).unwrap();
+        let p = tink_mac::new(&keyset_handle).unwrap();
+
+        // Chunked and one-shot operations are interchangeable, including the trailing zero byte
+        // used for LEGACY keys.
+        let mac = chunked_mac(p.as_ref(), &data).unwrap();
+        assert_eq!(mac, p.compute_mac(&data).unwrap());
+        p.verify_mac(&mac, &data).unwrap();
+        chunked_verify(p.as_ref(), &mac, &data).unwrap();
+
+        let mut bad_mac = mac.clone();
+        let last = bad_mac.len() - 1;
+        bad_mac[last] ^= 1;
+        assert!(
+            matches!(chunked_verify(p.as_ref(), &bad_mac, &data), Err(e) if e.kind() == tink::ErrorKind::Authentication)
+        );
+        assert!(chunked_verify(p.as_ref(), &mac, &data[1..]).is_err());
+    }
+
+    // MACs from other keysets are rejected.
+    let keyset = tink_testutil::new_test_hmac_keyset(16, tink::proto::OutputPrefixType::Tink);
+    let p = tink_mac::new(&tink::keyset::insecure::new_handle(keyset).unwrap()).unwrap();
+    let keyset = tink_testutil::new_test_hmac_keyset(16, tink::proto::OutputPrefixType::Legacy);
+    let p2 = tink_mac::new(&tink::keyset::insecure::new_handle(keyset).unwrap()).unwrap();
+    let mac = chunked_mac(p2.as_ref(), &data).unwrap();
+    assert!(chunked_verify(p.as_ref(), &mac, &data).is_err());
+    assert!(
+        matches!(p.new_verification(&mac[..5]), Err(e) if e.kind() == tink::ErrorKind::Authentication)
+    );
+}
+
+#[test]
+fn test_buffered_chunked_mac() {
+    // Primitives without native support for chunked operation buffer the data.
+    let p = tink_testutil::DummyMac {
+        name: "dummy".to_string(),
+    };
+    let data = b"some data to authenticate in chunks";
+    let mac = chunked_mac(&p, data).unwrap();
+    assert_eq!(mac, tink::Mac::compute_mac(&p, data).unwrap());
+    chunked_verify(&p, &mac, data).unwrap();
+    assert!(chunked_verify(&p, &mac, &data[1..]).is_err());
+}
+
+/// MAC whose incremental verification fails, either when it is started or when it is given data.
+#[derive(Clone, Debug)]
+struct FailingVerificationMac {
+    fail_on_update: bool,
+}
+
+impl tink::Mac for FailingVerificationMac {
+    fn compute_mac(&self, _data: &[u8]) -> Result<Vec<u8>, TinkError> {
+        Err("not implemented".into())
+    }
+
+    fn new_verification(&self, _mac: &[u8]) -> Result<Box<dyn tink::MacVerification>, TinkError> {
+        if self.fail_on_update {
+            Ok(Box::new(FailingUpdateVerification))
+        } else {
+            Err("cannot verify".into())
+        }
+    }
+}
+
+struct FailingUpdateVerification;
+
+impl tink::MacVerification for FailingUpdateVerification {
+    fn update(&mut self, _data: &[u8]) -> Result<(), TinkError> {
+        Err("cannot update".into())
+    }
+
+    fn verify_mac(self: Box<Self>) -> Result<(), TinkError> {
+        Err("not verified".into())
+    }
+}
+
+const FAILING_VERIFICATION_MAC_TYPE_URL: &str = "type.googleapis.com/test.FailingVerificationMac";
+
+/// Key manager for [`FailingVerificationMac`], whose keys fail on update if their value is
+/// `b"update"`.
+#[derive(Debug)]
+struct FailingVerificationMacKeyManager;
+
+impl tink::registry::KeyManager for FailingVerificationMacKeyManager {
+    fn primitive(&self, serialized_key: &[u8]) -> Result<tink::Primitive, TinkError> {
+        Ok(tink::Primitive::Mac(Box::new(FailingVerificationMac {
+            fail_on_update: serialized_key == b"update",
+        })))
+    }
+
+    fn new_key(&self, _serialized_key_format: &[u8]) -> Result<Vec<u8>, TinkError> {
+        Err("not implemented".into())
+    }
+
+    fn type_url(&self) -> &'static str {
+        FAILING_VERIFICATION_MAC_TYPE_URL
+    }
+
+    fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
+        tink::proto::key_data::KeyMaterialType::Symmetric
+    }
+}
+
+#[test]
+fn test_factory_chunked_skips_failed_candidates() {
+    tink_mac::init();
+    tink::registry::register_key_manager(std::sync::Arc::new(FailingVerificationMacKeyManager))
+        .unwrap();
+    let data = b"some data to authenticate in chunks";
+
+    // A RAW key whose verification cannot be started, or fails part way through, does not stop
+    // another RAW key from verifying the MAC.
+    let hmac_key = tink_testutil::new_key(
+        &tink_testutil::new_hmac_key_data(tink::proto::HashType::Sha256, 16),
+        tink::proto::KeyStatusType::Enabled,
+        1,
+        tink::proto::OutputPrefixType::Raw,
+    );
+    let keyset = tink_testutil::new_keyset(1, vec![hmac_key.clone()]);
+    let hmac = tink_mac::new(&tink::keyset::insecure::new_handle(keyset).unwrap()).unwrap();
+    let mac = hmac.compute_mac(data).unwrap();
+
+    for value in &[&b"start"[..], &b"update"[..]] {
+        let failing_key = tink_testutil::new_key(
+            &tink_testutil::new_key_data(
+                FAILING_VERIFICATION_MAC_TYPE_URL,
+                value,
+                tink::proto::key_data::KeyMaterialType::Symmetric,
+            ),
+            tink::proto::KeyStatusType::Enabled,
+            2,
+            tink::proto::OutputPrefixType::Raw,
+        );
+        let keyset = tink_testutil::new_keyset(1, vec![failing_key, hmac_key.clone()]);
+        let p = tink_mac::new(&tink::keyset::insecure::new_handle(keyset).unwrap()).unwrap();
+
+        chunked_verify(p.as_ref(), &mac, data).unwrap();
+        assert!(
+            matches!(chunked_verify(p.as_ref(), &mac, &data[1..]), Err(e) if e.kind() == tink::ErrorKind::Authentication)
+        );
+    }
+}
@@ -165,6 +165,27 @@ fn test_cmac_basic() {
     }
 }
 
+#[test]
+fn test_cmac_chunked() {
+    let a = tink_mac::subtle::AesCmac::new(KEY_RFC4493, 16).unwrap();
+    for (l, e) in EXPECTED.iter() {
+        let exp = hex::decode(e).unwrap();
+        for chunk_size in &[1, 5, 16, 64] {
+            let mut computation = a.new_computation().unwrap();
+            let mut verification = a.new_verification(&exp).unwrap();
+            for chunk in DATA_RFC4493[..*l].chunks(*chunk_size) {
+                computation.update(chunk).unwrap();
+                verification.update(chunk).unwrap();
+            }
+            assert_eq!(hex::encode(computation.compute_mac().unwrap()), *e);
+            verification.verify_mac().unwrap();
+        }
+        let mut verification = a.new_verification(&exp).unwrap();
+        verification.update(b"x").unwrap();
+        assert!(verification.verify_mac().is_err());
+    }
+}
+
 #[test]
 fn test_new_cmac_with_invalid_input() {
     // key too short
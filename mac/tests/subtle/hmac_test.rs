@@ -73,6 +73,37 @@ fn test_hmac_basic() {
     }
 }
 
+#[test]
+fn test_hmac_chunked() {
+    let data = get_random_bytes(300);
+    for test in HMAC_TESTS {
+        let cipher = tink_mac::subtle::Hmac::new(test.hash_alg, test.key, test.tag_size).unwrap();
+        let expected = cipher.compute_mac(&data).unwrap();
+        for chunk_size in &[1, 7, 64, 300] {
+            let mut computation = cipher.new_computation().unwrap();
+            let mut verification = cipher.new_verification(&expected).unwrap();
+            for chunk in data.chunks(*chunk_size) {
+                computation.update(chunk).unwrap();
+                verification.update(chunk).unwrap();
+            }
+            assert_eq!(computation.compute_mac().unwrap(), expected);
+            verification.verify_mac().unwrap();
+        }
+
+        // Verification fails for different data.
+        let mut verification = cipher.new_verification(&expected).unwrap();
+        verification.update(&data[1..]).unwrap();
+        assert!(verification.verify_mac().is_err());
+
+        // A computation with no data matches the MAC of empty data.
+        let computation = cipher.new_computation().unwrap();
+        assert_eq!(
+            computation.compute_mac().unwrap(),
+            cipher.compute_mac(&[]).unwrap()
+        );
+    }
+}
+
 fn assert_err_with<T>(result: Result<T, TinkError>, needle: &str, msg: &'static str) {
     match result {
         Ok(_) => panic!(msg),
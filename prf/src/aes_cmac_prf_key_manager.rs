@@ -78,6 +78,14 @@ impl tink::registry::KeyManager for AesCmacPrfKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
+
+    fn version(&self) -> u32 {
+        AES_CMAC_PRF_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "Prf"
+    }
 }
 
 /// Validate the given [`AesCmacPrfKey`](tink::proto::AesCmacPrfKey). It only validates the version
@@ -40,6 +40,7 @@ impl tink::registry::KeyManager for HkdfPrfKeyManager {
         validate_key(&key)?;
         let params = key
             .params
+            .as_ref()
             .ok_or_else(|| TinkError::new("HkdfPrfKeyManager: no key parameters"))?;
         let hash = HashType::from_i32(params.hash).unwrap_or(HashType::UnknownHash);
 
@@ -81,6 +82,25 @@ impl tink::registry::KeyManager for HkdfPrfKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
+
+    fn version(&self) -> u32 {
+        HKDF_PRF_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "Prf"
+    }
+
+    fn fips_compatible(&self) -> bool {
+        true
+    }
+
+    fn fips_compatible_key(&self, serialized_key: &[u8]) -> bool {
+        tink::proto::HkdfPrfKey::decode(serialized_key)
+            .ok()
+            .and_then(|key| key.params.as_ref().map(|params| params.hash))
+            .map_or(false, tink::fips::is_approved_hash)
+    }
 }
 
 /// Validate the given [`HkdfPrfKey`](tink::proto::HkdfPrfKey). It only validates the version of the
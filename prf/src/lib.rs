@@ -44,6 +44,8 @@ static INIT: Once = Once::new();
 /// Tink.
 pub fn init() {
     INIT.call_once(|| {
+        tink::registry::register_primitive_wrapper(SetWrapper).expect("tink_prf::init() failed"); // safe: init
+
         register_key_manager(std::sync::Arc::new(HmacPrfKeyManager::default()))
             .expect("tink_prf::init() failed"); // safe: init
         register_key_manager(std::sync::Arc::new(HkdfPrfKeyManager::default()))
@@ -16,13 +16,24 @@
 
 //! Provides an implementation of PRF using a set of underlying implementations.
 
-use std::{collections::HashMap, sync::Arc};
-use tink::{utils::wrap_err, Prf, TinkError};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+use tink::{
+    cryptofmt::{TINK_PREFIX_SIZE, TINK_START_BYTE},
+    utils::wrap_err,
+    ErrorKind, Prf, TinkError,
+};
 
 /// `Set` is a set of PRFs. A [`Keyset`](tink::proto::Keyset) can be converted into a set of PRFs
 /// using this primitive. Every key in the keyset corresponds to a PRF in the prf.Set.
 /// Every PRF in the set is given an ID, which is the same ID as the key id in
 /// the `Keyset`.
+///
+/// For pseudonymisation across key rotation, [`compute_pseudonym`](Set::compute_pseudonym)
+/// produces PRF outputs tagged with the ID of the key that produced them, which
+/// [`verify_pseudonym`](Set::verify_pseudonym) can check against any key in the set.
 pub struct Set {
     /// The key ID marked as primary in the corresponding [`Keyset`](tink::proto::Keyset).
     pub primary_id: u32,
@@ -47,30 +58,143 @@ impl Set {
         wrap_prf_set(ps)
     }
 
+    /// Creates a [`Set`] primitive from the given keyset handle, using the key managers in the
+    /// given registry configuration.
+    pub fn new_with_config(
+        h: &tink::keyset::Handle,
+        config: &tink::registry::Registry,
+    ) -> Result<Set, TinkError> {
+        let ps = h
+            .primitives_with_config(config)
+            .map_err(|e| wrap_err("prf::Set: cannot obtain primitive set", e))?;
+        wrap_prf_set(ps)
+    }
+
     /// Equivalent to `self.prfs[set.primary_id].compute_prf(input, output_length)`.
     pub fn compute_primary_prf(
         &self,
         input: &[u8],
         output_length: usize,
     ) -> Result<Vec<u8>, TinkError> {
-        let prf = self.prfs.get(&self.primary_id).ok_or_else(|| {
-            TinkError::new(&format!(
-                "Could not find primary ID {} in prf.Set",
-                self.primary_id
+        self.prf(self.primary_id)?.compute_prf(input, output_length)
+    }
+
+    /// Return the IDs of the keys in the set, in ascending order.
+    pub fn key_ids(&self) -> Vec<u32> {
+        let mut key_ids: Vec<u32> = self.prfs.keys().copied().collect();
+        key_ids.sort_unstable();
+        key_ids
+    }
+
+    /// Compute the PRF of `input` under every key in the set, returning the first
+    /// `output_length` bytes of output for each key ID.  Fails if the output cannot be computed
+    /// for any of the keys.
+    pub fn compute_all(
+        &self,
+        input: &[u8],
+        output_length: usize,
+    ) -> Result<BTreeMap<u32, Vec<u8>>, TinkError> {
+        self.prfs
+            .iter()
+            .map(|(key_id, prf)| {
+                prf.compute_prf(input, output_length)
+                    .map(|output| (*key_id, output))
+                    .map_err(|e| wrap_err(&format!("prf::Set: failed for key ID {}", key_id), e))
+            })
+            .collect()
+    }
+
+    /// Compute a pseudonym for `input` under the primary key.  The pseudonym consists of the
+    /// primary key's output prefix (as for an [`OutputPrefixType::Tink`] key) followed by the
+    /// first `output_length` bytes of the PRF output, so it stays the same for as long as the key
+    /// is in the set and can be checked with [`verify_pseudonym`](Set::verify_pseudonym) after
+    /// the primary key has been rotated.
+    ///
+    /// [`OutputPrefixType::Tink`]: tink::proto::OutputPrefixType::Tink
+    pub fn compute_pseudonym(
+        &self,
+        input: &[u8],
+        output_length: usize,
+    ) -> Result<Vec<u8>, TinkError> {
+        let output = self.compute_primary_prf(input, output_length)?;
+        let mut pseudonym = Vec::with_capacity(TINK_PREFIX_SIZE + output.len());
+        pseudonym.push(TINK_START_BYTE);
+        pseudonym.extend_from_slice(&self.primary_id.to_be_bytes());
+        pseudonym.extend_from_slice(&output);
+        Ok(pseudonym)
+    }
+
+    /// Verify that `pseudonym` was produced by [`compute_pseudonym`](Set::compute_pseudonym) for
+    /// `input` and `output_length` under the key in the set identified by the pseudonym.  The
+    /// expected `output_length` is supplied by the caller rather than taken from the pseudonym,
+    /// so that truncated pseudonyms are rejected.
+    pub fn verify_pseudonym(
+        &self,
+        pseudonym: &[u8],
+        input: &[u8],
+        output_length: usize,
+    ) -> Result<(), TinkError> {
+        let key_id = Set::pseudonym_key_id(pseudonym)?;
+        let output = &pseudonym[TINK_PREFIX_SIZE..];
+        if output.len() != output_length {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "prf::Set: invalid pseudonym length",
+            ));
+        }
+        let want = self.prf(key_id)?.compute_prf(input, output_length)?;
+        if tink::subtle::constant_time_compare(output, &want) {
+            Ok(())
+        } else {
+            Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "prf::Set: invalid pseudonym",
             ))
-        })?;
-        prf.compute_prf(input, output_length)
+        }
+    }
+
+    /// Return the ID of the key that produced the given pseudonym.
+    pub fn pseudonym_key_id(pseudonym: &[u8]) -> Result<u32, TinkError> {
+        if pseudonym.len() <= TINK_PREFIX_SIZE || pseudonym[0] != TINK_START_BYTE {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::InvalidArgument,
+                "prf::Set: invalid pseudonym format",
+            ));
+        }
+        let mut key_id = [0u8; 4];
+        key_id.copy_from_slice(&pseudonym[1..TINK_PREFIX_SIZE]);
+        Ok(u32::from_be_bytes(key_id))
+    }
+
+    /// Return the PRF for the given key ID.
+    fn prf(&self, key_id: u32) -> Result<&dyn Prf, TinkError> {
+        self.prfs.get(&key_id).map(|p| p.as_ref()).ok_or_else(|| {
+            TinkError::new_with_kind(
+                ErrorKind::KeyNotFound,
+                &format!("Could not find key ID {} in prf.Set", key_id),
+            )
+        })
     }
 }
 
 fn wrap_prf_set(ps: tink::primitiveset::PrimitiveSet) -> Result<Set, TinkError> {
     let entry = match &ps.primary {
-        None => return Err("prf::Set: no primary available".into()),
+        None => {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::InvalidKeyset,
+                "prf::Set: no primary available",
+            ))
+        }
         Some(e) => e,
     };
     match entry.primitive {
         tink::Primitive::Prf(_) => {}
-        _ => return Err("prf::Set: not a PRF primitive".into()),
+        _ => {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::InvalidKeyset,
+                "prf::Set: not a PRF primitive",
+            ))
+        }
     }
     let mut set = Set {
         primary_id: entry.key_id,
@@ -79,18 +203,42 @@ fn wrap_prf_set(ps: tink::primitiveset::PrimitiveSet) -> Result<Set, TinkError>
 
     let entries = ps.raw_entries();
     if entries.is_empty() {
-        return Err("Did not find any raw entries".into());
+        return Err(TinkError::new_with_kind(
+            ErrorKind::InvalidKeyset,
+            "Did not find any raw entries",
+        ));
     }
-    if ps.entries.len() != 1 {
-        return Err("Only raw entries allowed for prf::Set".into());
+    if ps.entries().len() != 1 {
+        return Err(TinkError::new_with_kind(
+            ErrorKind::InvalidKeyset,
+            "Only raw entries allowed for prf::Set",
+        ));
     }
     for entry in entries {
-        let prf = match entry.primitive {
-            tink::Primitive::Prf(prf) => prf,
-            _ => return Err("prf::Set: not a PRF primitive".into()),
+        let prf = match &entry.primitive {
+            tink::Primitive::Prf(prf) => prf.box_clone(),
+            _ => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "prf::Set: not a PRF primitive",
+                ))
+            }
         };
         set.prfs.insert(entry.key_id, prf);
     }
 
     Ok(set)
 }
+
+/// [`PrimitiveWrapper`](tink::registry::PrimitiveWrapper) that combines the PRFs for the keys in
+/// a keyset into a [`Set`]. Registered by [`init`](crate::init).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SetWrapper;
+
+impl tink::registry::PrimitiveWrapper for SetWrapper {
+    type Primitive = Set;
+
+    fn wrap(&self, ps: tink::primitiveset::PrimitiveSet) -> Result<Box<Set>, TinkError> {
+        Ok(Box::new(wrap_prf_set(ps)?))
+    }
+}
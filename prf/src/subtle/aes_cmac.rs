@@ -28,6 +28,7 @@ use tink::TinkError;
 const RECOMMENDED_KEY_SIZE: usize = 32;
 const AES_BLOCK_SIZE_IN_BYTES: usize = 16;
 
+#[derive(Clone)]
 enum AesCmacVariant {
     Aes128(Box<Cmac<Aes128>>),
     Aes192(Box<Cmac<Aes192>>),
@@ -62,6 +63,48 @@ impl AesCmacPrf {
             mac: Arc::new(Mutex::new(aes_cmac)),
         })
     }
+
+    /// Start an incremental AES-CMAC computation, for data that is provided in chunks.
+    pub fn new_computation(&self) -> AesCmacPrfComputation {
+        AesCmacPrfComputation {
+            mac: self.mac.lock().expect("internal lock corrupted").clone(), // safe: lock
+        }
+    }
+}
+
+/// `AesCmacPrfComputation` holds the state of an incremental AES-CMAC computation, as created by
+/// [`AesCmacPrf::new_computation`].
+#[derive(Clone)]
+pub struct AesCmacPrfComputation {
+    mac: AesCmacVariant,
+}
+
+impl AesCmacPrfComputation {
+    /// Process the next chunk of data.
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.mac {
+            AesCmacVariant::Aes128(mac) => mac.update(data),
+            AesCmacVariant::Aes192(mac) => mac.update(data),
+            AesCmacVariant::Aes256(mac) => mac.update(data),
+        }
+    }
+
+    /// Return the first `output_length` bytes of the AES-CMAC of all the data processed.
+    pub fn finalize(self, output_length: usize) -> Result<Vec<u8>, TinkError> {
+        if output_length > AES_BLOCK_SIZE_IN_BYTES {
+            return Err(format!(
+                "AesCmacPrf: output_length must be between 0 and {}",
+                AES_BLOCK_SIZE_IN_BYTES
+            )
+            .into());
+        }
+        let result = match self.mac {
+            AesCmacVariant::Aes128(mac) => mac.finalize().into_bytes(),
+            AesCmacVariant::Aes192(mac) => mac.finalize().into_bytes(),
+            AesCmacVariant::Aes256(mac) => mac.finalize().into_bytes(),
+        };
+        Ok(result[..output_length].to_vec())
+    }
 }
 
 /// Check that the key is the recommended size for AES-CMAC.
@@ -25,6 +25,9 @@ use tink::{proto::HashType, TinkError};
 const MIN_HKDF_KEY_SIZE_IN_BYTES: usize = 32;
 
 /// `HkdfPrf` is a type that can be used to compute several HKDFs with the same key material.
+///
+/// Note that the pseudo-random key held by an `HkdfPrf` (as HMAC state) is not wiped on drop, as
+/// the `hkdf` and `hmac` crates do not (yet) support zeroization.
 #[derive(Clone)]
 pub struct HkdfPrf {
     prk: HkdfPrfVariant,
@@ -33,6 +33,7 @@ pub struct HmacPrf {
     mac_size: usize,
 }
 
+#[derive(Clone)]
 enum HmacPrfVariant {
     Sha1(Hmac<sha1::Sha1>),
     Sha256(Hmac<sha2::Sha256>),
@@ -74,6 +75,52 @@ impl HmacPrf {
             mac_size,
         })
     }
+
+    /// Start an incremental HMAC computation, for data that is provided in chunks.
+    pub fn new_computation(&self) -> HmacPrfComputation {
+        HmacPrfComputation {
+            mac: self.mac.lock().expect("internal lock corrupted").clone(), // safe: lock
+            mac_size: self.mac_size,
+        }
+    }
+}
+
+/// `HmacPrfComputation` holds the state of an incremental HMAC computation, as created by
+/// [`HmacPrf::new_computation`].
+#[derive(Clone)]
+pub struct HmacPrfComputation {
+    mac: HmacPrfVariant,
+    mac_size: usize,
+}
+
+impl HmacPrfComputation {
+    /// Process the next chunk of data.
+    pub fn update(&mut self, data: &[u8]) {
+        match &mut self.mac {
+            HmacPrfVariant::Sha1(mac) => mac.update(data),
+            HmacPrfVariant::Sha256(mac) => mac.update(data),
+            HmacPrfVariant::Sha384(mac) => mac.update(data),
+            HmacPrfVariant::Sha512(mac) => mac.update(data),
+        }
+    }
+
+    /// Return the first `output_length` bytes of the HMAC of all the data processed.
+    pub fn finalize(self, output_length: usize) -> Result<Vec<u8>, TinkError> {
+        if output_length > self.mac_size {
+            return Err(format!(
+                "HmacPrf: output_length must be between 0 and {}",
+                self.mac_size
+            )
+            .into());
+        }
+        let result = match self.mac {
+            HmacPrfVariant::Sha1(mac) => mac.finalize().into_bytes().to_vec(),
+            HmacPrfVariant::Sha256(mac) => mac.finalize().into_bytes().to_vec(),
+            HmacPrfVariant::Sha384(mac) => mac.finalize().into_bytes().to_vec(),
+            HmacPrfVariant::Sha512(mac) => mac.finalize().into_bytes().to_vec(),
+        };
+        Ok(result[..output_length].to_vec())
+    }
 }
 
 /// Validate parameters of HMAC constructor.
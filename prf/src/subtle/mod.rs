@@ -15,6 +15,10 @@
 ////////////////////////////////////////////////////////////////////////////////
 
 //! Provides subtle implementations of the `tink::Prf` primitive.
+//!
+//! Note that the key state held by [`HkdfPrf`], [`HmacPrf`] and [`AesCmacPrf`] is not wiped on
+//! drop, as the underlying `hkdf`, `hmac`, `cmac` and `aes` crates do not (yet) support
+//! zeroization.
 
 mod aes_cmac;
 pub use self::aes_cmac::*;
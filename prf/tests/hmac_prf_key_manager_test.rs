@@ -68,7 +68,7 @@ fn test_new_key_hmac_multiple_times() {
         keys.insert(hex::encode(serialized_key));
 
         let key_data = km.new_key_data(&serialized_format).unwrap();
-        keys.insert(hex::encode(key_data.value));
+        keys.insert(hex::encode(&key_data.value));
     }
     assert_eq!(keys.len(), 2 * n_test, "key is repeated");
 }
@@ -174,6 +174,102 @@ fn test_non_prf_primitives() {
     );
 }
 
+#[test]
+fn test_compute_all_and_key_ids() {
+    tink_prf::init();
+    let mut manager = tink::keyset::Manager::new();
+    let mut ids = Vec::new();
+    for template in &[
+        tink_prf::hmac_sha256_prf_key_template(),
+        tink_prf::hkdf_sha256_prf_key_template(),
+        tink_prf::aes_cmac_prf_key_template(),
+    ] {
+        ids.push(add_key_and_return_id(&mut manager, template).unwrap());
+    }
+    let prf_set = tink_prf::Set::new(&manager.handle().unwrap()).unwrap();
+    ids.sort_unstable();
+    assert_eq!(prf_set.key_ids(), ids);
+    assert_eq!(prf_set.key_ids(), prf_set.key_ids());
+
+    let outputs = prf_set.compute_all(b"The input", 16).unwrap();
+    assert_eq!(outputs.keys().copied().collect::<Vec<_>>(), ids);
+    for (id, output) in &outputs {
+        assert_eq!(
+            output,
+            &prf_set.prfs[id].compute_prf(b"The input", 16).unwrap()
+        );
+    }
+    assert_eq!(
+        outputs[&prf_set.primary_id],
+        prf_set.compute_primary_prf(b"The input", 16).unwrap()
+    );
+
+    // AES-CMAC PRF output is limited to 16 bytes.
+    assert!(prf_set.compute_all(b"The input", 17).is_err());
+}
+
+#[test]
+fn test_pseudonym() {
+    tink_prf::init();
+    let mut manager = tink::keyset::Manager::new();
+    let old_id =
+        add_key_and_return_id(&mut manager, &tink_prf::hmac_sha256_prf_key_template()).unwrap();
+    let old_set = tink_prf::Set::new(&manager.handle().unwrap()).unwrap();
+    let pseudonym = old_set.compute_pseudonym(b"user@example.com", 16).unwrap();
+    assert_eq!(pseudonym.len(), tink::cryptofmt::TINK_PREFIX_SIZE + 16);
+    assert_eq!(tink_prf::Set::pseudonym_key_id(&pseudonym).unwrap(), old_id);
+    assert_eq!(
+        pseudonym,
+        old_set.compute_pseudonym(b"user@example.com", 16).unwrap(),
+        "pseudonyms should be stable"
+    );
+    old_set
+        .verify_pseudonym(&pseudonym, b"user@example.com", 16)
+        .unwrap();
+
+    // After rotation, new pseudonyms use the new primary key, but old pseudonyms still match.
+    let new_id =
+        add_key_and_return_id(&mut manager, &tink_prf::hkdf_sha256_prf_key_template()).unwrap();
+    let new_set = tink_prf::Set::new(&manager.handle().unwrap()).unwrap();
+    let new_pseudonym = new_set.compute_pseudonym(b"user@example.com", 16).unwrap();
+    assert_eq!(
+        tink_prf::Set::pseudonym_key_id(&new_pseudonym).unwrap(),
+        new_id
+    );
+    assert_ne!(new_pseudonym, pseudonym);
+    new_set
+        .verify_pseudonym(&pseudonym, b"user@example.com", 16)
+        .unwrap();
+    new_set
+        .verify_pseudonym(&new_pseudonym, b"user@example.com", 16)
+        .unwrap();
+
+    // Pseudonyms for other inputs, modified pseudonyms and unknown keys are rejected.
+    let result = new_set.verify_pseudonym(&pseudonym, b"other@example.com", 16);
+    assert!(matches!(result, Err(e) if e.kind() == tink::ErrorKind::Authentication));
+    let mut modified = pseudonym.clone();
+    let last = modified.len() - 1;
+    modified[last] ^= 1;
+    let result = new_set.verify_pseudonym(&modified, b"user@example.com", 16);
+    assert!(matches!(result, Err(e) if e.kind() == tink::ErrorKind::Authentication));
+    let result = old_set.verify_pseudonym(&new_pseudonym, b"user@example.com", 16);
+    assert!(matches!(result, Err(e) if e.kind() == tink::ErrorKind::KeyNotFound));
+    // Truncated pseudonyms, and pseudonyms of an unexpected length, are rejected.
+    for len in &[1, 8, 15] {
+        let truncated = &pseudonym[..tink::cryptofmt::TINK_PREFIX_SIZE + len];
+        let result = new_set.verify_pseudonym(truncated, b"user@example.com", 16);
+        assert!(matches!(result, Err(e) if e.kind() == tink::ErrorKind::Authentication));
+    }
+    let result = new_set.verify_pseudonym(&pseudonym, b"user@example.com", 32);
+    assert!(matches!(result, Err(e) if e.kind() == tink::ErrorKind::Authentication));
+    let mut legacy_prefix = pseudonym.clone();
+    legacy_prefix[0] = tink::cryptofmt::LEGACY_START_BYTE;
+    for bad in &[&pseudonym[..5], &legacy_prefix[..], &[][..]] {
+        let result = new_set.verify_pseudonym(bad, b"user@example.com", 16);
+        assert!(matches!(result, Err(e) if e.kind() == tink::ErrorKind::InvalidArgument));
+    }
+}
+
 fn run_z_tests(results: Vec<Vec<u8>>) {
     for (i, result1) in results.iter().enumerate() {
         tink_testutil::z_test_uniform_string(result1)
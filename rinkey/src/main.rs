@@ -127,6 +127,20 @@ struct WrappingOptions {
         default_value = ""
     )]
     credential_path: String,
+
+    #[structopt(
+        long,
+        help = "If --master-key-uri or --password-file is specified, this option specifies associated data that the encrypted keyset is bound to, such as the name of the service or environment that uses the keyset. The same associated data must be given when reading the keyset. If missing, use empty associated data.",
+        default_value = ""
+    )]
+    associated_data: String,
+
+    #[structopt(
+        long,
+        help = "The keyset might be encrypted with a key derived from a password, as an alternative to --master-key-uri. This option specifies the path of a file holding the password; a single trailing newline is ignored.",
+        default_value = ""
+    )]
+    password_file: String,
 }
 
 /// Common args for commands that read from file.
@@ -212,6 +226,20 @@ struct ConvertKeysetOptions {
         default_value = ""
     )]
     new_credential: String,
+
+    #[structopt(
+        long,
+        help = "The associated data to bind the keyset to when encrypting with the new master key",
+        default_value = ""
+    )]
+    new_associated_data: String,
+
+    #[structopt(
+        long,
+        help = "The path of a file holding the password to derive the new master key from",
+        default_value = ""
+    )]
+    new_password_file: String,
 }
 
 /// Options for command to create a keyset.
@@ -314,6 +342,8 @@ fn convert_keyset(opts: ConvertKeysetOptions) {
     let new_wrap_opts = WrappingOptions {
         master_key_uri: opts.new_master_key_uri,
         credential_path: opts.new_credential,
+        associated_data: opts.new_associated_data,
+        password_file: opts.new_password_file,
     };
     write_keyset(opts.out_opts, new_wrap_opts, kh);
 }
@@ -457,14 +487,14 @@ fn read_keyset_with<T: tink::keyset::Reader>(
     mut reader: T,
     wrap_opts: WrappingOptions,
 ) -> tink::keyset::Handle {
-    if wrap_opts.master_key_uri.is_empty() {
-        tink::keyset::insecure::read(&mut reader).expect("Read failure")
-    } else {
-        let kms_client = get_kms_client(&wrap_opts).expect("No KMS client found");
-        let aead = kms_client
-            .get_aead(&wrap_opts.master_key_uri)
-            .expect("Failed to build KMS AEAD");
-        tink::keyset::Handle::read(&mut reader, aead).expect("Read failure")
+    match get_master_key(&wrap_opts) {
+        None => tink::keyset::insecure::read(&mut reader).expect("Read failure"),
+        Some(master_key) => tink::keyset::Handle::read_with_associated_data(
+            &mut reader,
+            master_key,
+            wrap_opts.associated_data.as_bytes(),
+        )
+        .expect("Read failure"),
     }
 }
 
@@ -489,14 +519,54 @@ fn write_keyset_with<T: tink::keyset::Writer>(
     wrap_opts: WrappingOptions,
     kh: tink::keyset::Handle,
 ) {
-    if wrap_opts.master_key_uri.is_empty() {
-        tink::keyset::insecure::write(&kh, &mut writer).expect("Write failure")
+    match get_master_key(&wrap_opts) {
+        None => tink::keyset::insecure::write(&kh, &mut writer).expect("Write failure"),
+        Some(master_key) => kh
+            .write_with_associated_data(
+                &mut writer,
+                master_key,
+                wrap_opts.associated_data.as_bytes(),
+            )
+            .expect("Write failure"),
+    }
+}
+
+/// Return the master key specified by the [`WrappingOptions`], or `None` for cleartext keysets.
+fn get_master_key(wrap_opts: &WrappingOptions) -> Option<Box<dyn tink::Aead>> {
+    match (
+        wrap_opts.master_key_uri.is_empty(),
+        wrap_opts.password_file.is_empty(),
+    ) {
+        (true, true) => None,
+        (false, true) => {
+            let kms_client = get_kms_client(wrap_opts).expect("No KMS client found");
+            Some(
+                kms_client
+                    .get_aead(&wrap_opts.master_key_uri)
+                    .expect("Failed to build KMS AEAD"),
+            )
+        }
+        (true, false) => {
+            let password = zeroize::Zeroizing::new(
+                std::fs::read(&wrap_opts.password_file).expect("Failed to read password file"),
+            );
+            Some(Box::new(
+                tink_aead::PasswordAead::new(strip_newline(&password))
+                    .expect("Failed to build password AEAD"),
+            ))
+        }
+        (false, false) => panic!("Only one of master key URI and password file may be specified"),
+    }
+}
+
+/// Remove a single trailing newline (`\n` or `\r\n`) from `data`.
+fn strip_newline(data: &[u8]) -> &[u8] {
+    if data.ends_with(b"\r\n") {
+        &data[..data.len() - 2]
+    } else if data.ends_with(b"\n") {
+        &data[..data.len() - 1]
     } else {
-        let kms_client = get_kms_client(&wrap_opts).expect("No KMS client found");
-        let aead = kms_client
-            .get_aead(&wrap_opts.master_key_uri)
-            .expect("Failed to build KMS AEAD");
-        kh.write(&mut writer, aead).expect("Write failure")
+        data
     }
 }
 
@@ -126,6 +126,18 @@ impl tink::registry::KeyManager for EcdsaSignerKeyManager {
         tink::proto::key_data::KeyMaterialType::AsymmetricPrivate
     }
 
+    fn version(&self) -> u32 {
+        ECDSA_SIGNER_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "PublicKeySign"
+    }
+
+    fn fips_compatible(&self) -> bool {
+        true
+    }
+
     fn supports_private_keys(&self) -> bool {
         true
     }
@@ -139,6 +151,7 @@ impl tink::registry::KeyManager for EcdsaSignerKeyManager {
         let mut serialized_pub_key = Vec::new();
         priv_key
             .public_key
+            .as_ref()
             .ok_or_else(|| TinkError::new("EcdsaSignerKeyManager: no public key"))?
             .encode(&mut serialized_pub_key)
             .map_err(|e| wrap_err("EcdsaSignerKeyManager: invalid public key", e))?;
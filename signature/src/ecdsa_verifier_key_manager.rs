@@ -57,6 +57,18 @@ impl tink::registry::KeyManager for EcdsaVerifierKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::AsymmetricPublic
     }
+
+    fn version(&self) -> u32 {
+        ECDSA_VERIFIER_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "PublicKeyVerify"
+    }
+
+    fn fips_compatible(&self) -> bool {
+        true
+    }
 }
 
 /// Validate the given [`EcdsaPublicKey`](tink::proto::EcdsaPublicKey) and return
@@ -73,6 +73,14 @@ impl tink::registry::KeyManager for Ed25519SignerKeyManager {
         tink::proto::key_data::KeyMaterialType::AsymmetricPrivate
     }
 
+    fn version(&self) -> u32 {
+        ED25519_SIGNER_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "PublicKeySign"
+    }
+
     fn supports_private_keys(&self) -> bool {
         true
     }
@@ -85,6 +93,7 @@ impl tink::registry::KeyManager for Ed25519SignerKeyManager {
             .map_err(|e| wrap_err("Ed25519SignerKeyManager: invalid key", e))?;
         let mut serialized_pub_key = Vec::new();
         key.public_key
+            .as_ref()
             .ok_or_else(|| TinkError::new("Ed25519SignerKeyManager: invalid key"))?
             .encode(&mut serialized_pub_key)
             .map_err(|e| wrap_err("Ed25519SignerKeyManager: invalid key", e))?;
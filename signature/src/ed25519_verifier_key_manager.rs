@@ -56,6 +56,14 @@ impl tink::registry::KeyManager for Ed25519VerifierKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::AsymmetricPublic
     }
+
+    fn version(&self) -> u32 {
+        ED25519_VERIFIER_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "PublicKeyVerify"
+    }
 }
 
 /// Validate the given [`Ed25519PublicKey`](tink::proto::Ed25519PublicKey).
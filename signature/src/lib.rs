@@ -52,6 +52,11 @@ static INIT: Once = Once::new();
 /// Tink.
 pub fn init() {
     INIT.call_once(|| {
+        tink::registry::register_primitive_wrapper(SignerWrapper)
+            .expect("tink_signature::init() failed"); // safe: init
+        tink::registry::register_primitive_wrapper(VerifierWrapper)
+            .expect("tink_signature::init() failed"); // safe: init
+
         // ECDSA
         register_key_manager(std::sync::Arc::new(EcdsaSignerKeyManager::default()))
             .expect("tink_signature::init() failed"); // safe: init
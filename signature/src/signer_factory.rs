@@ -16,8 +16,8 @@
 
 //! Factory methods for [`tink::Signer`] instances.
 
-use std::sync::Arc;
-use tink::{utils::wrap_err, TinkError};
+use std::{io::Read, sync::Arc};
+use tink::{monitoring::Logger, utils::wrap_err, ErrorKind, TinkError};
 
 /// Return a [`tink::Signer`] primitive from the given keyset handle.
 pub fn new_signer(h: &tink::keyset::Handle) -> Result<Box<dyn tink::Signer>, TinkError> {
@@ -37,59 +37,170 @@ pub fn new_signer_with_key_manager(
     Ok(Box::new(ret))
 }
 
+/// Return a [`tink::Signer`] primitive from the given keyset handle, using the key managers in
+/// the given registry configuration.
+pub fn new_signer_with_config(
+    h: &tink::keyset::Handle,
+    config: &tink::registry::Registry,
+) -> Result<Box<dyn tink::Signer>, TinkError> {
+    let ps = h
+        .primitives_with_config(config)
+        .map_err(|e| wrap_err("signer::factory: cannot obtain primitive set", e))?;
+
+    let ret = WrappedSigner::new(ps)?;
+    Ok(Box::new(ret))
+}
+
+/// [`PrimitiveWrapper`](tink::registry::PrimitiveWrapper) that combines the primitives for the keys
+/// in a keyset into a single [`tink::Signer`] primitive. Registered by [`init`](crate::init).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SignerWrapper;
+
+impl tink::registry::PrimitiveWrapper for SignerWrapper {
+    type Primitive = dyn tink::Signer;
+
+    fn wrap(
+        &self,
+        ps: tink::primitiveset::PrimitiveSet,
+    ) -> Result<Box<dyn tink::Signer>, TinkError> {
+        Ok(Box::new(WrappedSigner::new(ps)?))
+    }
+}
+
 /// A [`tink::Signer`] implementation that uses the underlying primitive set for signing.
 #[derive(Clone)]
 struct WrappedSigner {
     ps: tink::primitiveset::PrimitiveSet,
+    logger: Arc<dyn Logger>,
 }
 
 impl WrappedSigner {
     fn new(ps: tink::primitiveset::PrimitiveSet) -> Result<WrappedSigner, TinkError> {
         let primary = match &ps.primary {
-            None => return Err("signer::factory: no primary primitive".into()),
+            None => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "signer::factory: no primary primitive",
+                ))
+            }
             Some(p) => p,
         };
         match primary.primitive {
             tink::Primitive::Signer(_) => {}
-            _ => return Err("signer::factory: not a Signer primitive".into()),
+            _ => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "signer::factory: not a Signer primitive",
+                ))
+            }
         };
-        for (_, primitives) in ps.entries.iter() {
+        for (_, primitives) in ps.entries().iter() {
             for p in primitives {
                 match p.primitive {
                     tink::Primitive::Signer(_) => {}
-                    _ => return Err("signer::factory: not a Signer primitive".into()),
+                    _ => {
+                        return Err(TinkError::new_with_kind(
+                            ErrorKind::InvalidKeyset,
+                            "signer::factory: not a Signer primitive",
+                        ))
+                    }
                 };
             }
         }
-        Ok(WrappedSigner { ps })
+        let logger = tink::monitoring::new_logger(&ps, "public_key_sign", "sign")?;
+        Ok(WrappedSigner { ps, logger })
     }
 }
 
-impl tink::Signer for WrappedSigner {
-    /// Sign the given data and returns the signature concatenated with the identifier of the
-    /// primary primitive.
-    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, TinkError> {
+impl WrappedSigner {
+    /// Return the primary entry and its signer.
+    fn primary(&self) -> Result<(&tink::primitiveset::Entry, &dyn tink::Signer), TinkError> {
         let primary = match &self.ps.primary {
             Some(p) => p,
-            None => return Err("signer::factory: no primary primitive".into()),
+            None => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "signer::factory: no primary primitive",
+                ))
+            }
         };
-        let primitive = match &primary.primitive {
-            tink::Primitive::Signer(p) => p,
-            _ => return Err("signer::factory: not a Mac primitive".into()),
+        match &primary.primitive {
+            tink::Primitive::Signer(p) => Ok((primary, p.as_ref())),
+            _ => Err(TinkError::new_with_kind(
+                ErrorKind::InvalidKeyset,
+                "signer::factory: not a Signer primitive",
+            )),
+        }
+    }
+
+    /// Log the outcome of a signing operation, and prepend the primary's prefix to a successful
+    /// signature.
+    fn finish(
+        &self,
+        primary: &tink::primitiveset::Entry,
+        result: Result<Vec<u8>, TinkError>,
+        data_len: usize,
+    ) -> Result<Vec<u8>, TinkError> {
+        let signature = match result {
+            Ok(signature) => signature,
+            Err(e) => {
+                self.logger.log_failure();
+                return Err(e);
+            }
         };
+        self.logger.log(primary.key_id, data_len);
 
-        let signature = if primary.prefix_type == tink::proto::OutputPrefixType::Legacy {
+        let mut ret = Vec::with_capacity(primary.prefix.len() + signature.len());
+        ret.extend_from_slice(&primary.prefix);
+        ret.extend_from_slice(&signature);
+        Ok(ret)
+    }
+}
+
+impl tink::Signer for WrappedSigner {
+    /// Sign the given data and returns the signature concatenated with the identifier of the
+    /// primary primitive.
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>, TinkError> {
+        let (primary, primitive) = self.primary()?;
+        let result = if primary.prefix_type == tink::proto::OutputPrefixType::Legacy {
             let mut signed_data_copy = Vec::with_capacity(data.len() + 1);
             signed_data_copy.extend_from_slice(data);
             signed_data_copy.push(tink::cryptofmt::LEGACY_START_BYTE);
-            primitive.sign(&signed_data_copy)?
+            primitive.sign(&signed_data_copy)
         } else {
-            primitive.sign(data)?
+            primitive.sign(data)
         };
+        self.finish(primary, result, data.len())
+    }
 
-        let mut ret = Vec::with_capacity(primary.prefix.len() + signature.len());
-        ret.extend_from_slice(&primary.prefix);
-        ret.extend_from_slice(&signature);
-        Ok(ret)
+    /// Sign the data available from `reader` with the primary primitive, and return the
+    /// signature concatenated with the identifier of the primary primitive.
+    fn sign_reader(&self, reader: &mut dyn Read) -> Result<Vec<u8>, TinkError> {
+        let (primary, primitive) = self.primary()?;
+        let mut reader = CountingReader {
+            inner: reader,
+            count: 0,
+        };
+        let result = if primary.prefix_type == tink::proto::OutputPrefixType::Legacy {
+            let legacy_suffix = [tink::cryptofmt::LEGACY_START_BYTE];
+            primitive.sign_reader(&mut (&mut reader).chain(&legacy_suffix[..]))
+        } else {
+            primitive.sign_reader(&mut reader)
+        };
+        self.finish(primary, result, reader.count)
+    }
+}
+
+/// Reader that counts the bytes read from an inner reader.
+struct CountingReader<'a> {
+    inner: &'a mut dyn Read,
+    count: usize,
+}
+
+impl<'a> Read for CountingReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n;
+        Ok(n)
     }
 }
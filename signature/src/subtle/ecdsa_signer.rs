@@ -16,14 +16,16 @@
 
 use generic_array::typenum::Unsigned;
 use p256::{
-    ecdsa::signature::{RandomizedSigner, Signature},
+    ecdsa::signature::{RandomizedDigestSigner, RandomizedSigner, Signature},
     elliptic_curve,
 };
+use sha2::Digest;
 use tink::{
     proto::{EcdsaSignatureEncoding, EllipticCurveType, HashType},
     utils::wrap_err,
     TinkError,
 };
+use zeroize::Zeroize;
 
 // An ECDSA private key.
 pub enum EcdsaPrivateKey {
@@ -35,9 +37,27 @@ impl Clone for EcdsaPrivateKey {
     fn clone(&self) -> Self {
         match self {
             EcdsaPrivateKey::NistP256(k) => {
-                EcdsaPrivateKey::NistP256(
-                    p256::ecdsa::SigningKey::from_bytes(&k.to_bytes()).unwrap(), /* safe: round-trip */
-                )
+                let mut bytes = k.to_bytes();
+                let key = p256::ecdsa::SigningKey::from_bytes(&bytes).unwrap(); /* safe: round-trip */
+                bytes.as_mut_slice().zeroize();
+                EcdsaPrivateKey::NistP256(key)
+            }
+        }
+    }
+}
+
+/// Manual implementation of [`Debug`](std::fmt::Debug) that redacts the private key.
+impl std::fmt::Debug for EcdsaPrivateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EcdsaPrivateKey::NistP256(k) => {
+                let mut bytes = k.to_bytes();
+                let result = f
+                    .debug_tuple("NistP256")
+                    .field(&tink::utils::SecretBytes(&bytes))
+                    .finish();
+                bytes.as_mut_slice().zeroize();
+                result
             }
         }
     }
@@ -45,7 +65,7 @@ impl Clone for EcdsaPrivateKey {
 
 /// `EcdsaSigner` is an implementation of [`tink::Signer`] for ECDSA.
 /// At the moment, the implementation only accepts DER encoding.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct EcdsaSigner {
     private_key: EcdsaPrivateKey,
     encoding: super::SignatureEncoding,
@@ -108,4 +128,23 @@ impl tink::Signer for EcdsaSigner {
             },
         }
     }
+
+    /// Sign all of the data available from `reader`, hashing it incrementally.  The result is
+    /// the same as [`sign`](tink::Signer::sign) over the complete data.
+    fn sign_reader(&self, reader: &mut dyn std::io::Read) -> Result<Vec<u8>, tink::TinkError> {
+        let mut digest = sha2::Sha256::new();
+        std::io::copy(reader, &mut digest)
+            .map_err(|e| wrap_err("EcdsaSigner: failed to read data", e))?;
+        let mut csprng = rand::rngs::OsRng {};
+        match &self.private_key {
+            EcdsaPrivateKey::NistP256(secret_key) => {
+                let signature: p256::ecdsa::Signature =
+                    secret_key.sign_digest_with_rng(&mut csprng, digest);
+                match self.encoding {
+                    super::SignatureEncoding::Der => Ok(signature.to_asn1().as_bytes().to_vec()),
+                    super::SignatureEncoding::IeeeP1363 => Ok(signature.as_bytes().to_vec()),
+                }
+            }
+        }
+    }
 }
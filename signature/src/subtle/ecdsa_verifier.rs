@@ -16,15 +16,19 @@
 
 use generic_array::typenum::Unsigned;
 use p256::{
-    ecdsa::{signature::Verifier, Signature},
+    ecdsa::{
+        signature::{DigestVerifier, Verifier},
+        Signature,
+    },
     elliptic_curve,
     elliptic_curve::sec1::EncodedPoint,
 };
+use sha2::Digest;
 use signature::Signature as _;
 use tink::{
     proto::{EcdsaSignatureEncoding, EllipticCurveType, HashType},
     utils::wrap_err,
-    TinkError,
+    ErrorKind, TinkError,
 };
 
 /// An ECDSA public key.
@@ -107,18 +111,70 @@ fn element_from_padded_slice<C: elliptic_curve::Curve>(
     }
 }
 
+impl EcdsaVerifier {
+    fn parse_signature(&self, signature: &[u8]) -> Result<Signature, TinkError> {
+        match self.encoding {
+            super::SignatureEncoding::Der => Signature::from_asn1(signature).map_err(|e| {
+                wrap_err("EcdsaVerifier: invalid ASN.1 signature", e)
+                    .with_kind(ErrorKind::Authentication)
+            }),
+            super::SignatureEncoding::IeeeP1363 => Signature::from_bytes(signature).map_err(|e| {
+                wrap_err("EcdsaVerifier: invalid IEEE-P1363 signature", e)
+                    .with_kind(ErrorKind::Authentication)
+            }),
+        }
+    }
+}
+
 impl tink::Verifier for EcdsaVerifier {
     fn verify(&self, signature: &[u8], data: &[u8]) -> Result<(), tink::TinkError> {
-        let signature = match self.encoding {
-            super::SignatureEncoding::Der => Signature::from_asn1(signature)
-                .map_err(|e| wrap_err("EcdsaVerifier: invalid ASN.1 signature", e))?,
-            super::SignatureEncoding::IeeeP1363 => Signature::from_bytes(signature)
-                .map_err(|e| wrap_err("EcdsaVerifier: invalid IEEE-P1363 signature", e))?,
-        };
+        let signature = self.parse_signature(signature)?;
+        match &self.public_key {
+            EcdsaPublicKey::NistP256(verify_key) => {
+                verify_key.verify(&data, &signature).map_err(|e| {
+                    wrap_err("EcdsaVerifier: invalid signature", e)
+                        .with_kind(ErrorKind::Authentication)
+                })
+            }
+        }
+    }
+
+    /// Start an incremental verification, which hashes the data as it is provided.  Signatures
+    /// produced by [`sign`](tink::Signer::sign) and [`sign_reader`](tink::Signer::sign_reader)
+    /// are interchangeable.
+    fn new_verification(
+        &self,
+        signature: &[u8],
+    ) -> Result<Box<dyn tink::SignatureVerification>, tink::TinkError> {
+        Ok(Box::new(EcdsaVerification {
+            public_key: self.public_key.clone(),
+            signature: self.parse_signature(signature)?,
+            digest: sha2::Sha256::new(),
+        }))
+    }
+}
+
+/// Incremental ECDSA verification, as returned by [`EcdsaVerifier`].
+struct EcdsaVerification {
+    public_key: EcdsaPublicKey,
+    signature: Signature,
+    digest: sha2::Sha256,
+}
+
+impl tink::SignatureVerification for EcdsaVerification {
+    fn update(&mut self, data: &[u8]) -> Result<(), tink::TinkError> {
+        self.digest.update(data);
+        Ok(())
+    }
+
+    fn verify(self: Box<Self>) -> Result<(), tink::TinkError> {
         match &self.public_key {
             EcdsaPublicKey::NistP256(verify_key) => verify_key
-                .verify(&data, &signature)
-                .map_err(|e| wrap_err("EcdsaVerifier: invalid signature", e)),
+                .verify_digest(self.digest, &self.signature)
+                .map_err(|e| {
+                    wrap_err("EcdsaVerifier: invalid signature", e)
+                        .with_kind(ErrorKind::Authentication)
+                }),
         }
     }
 }
@@ -14,8 +14,10 @@
 //
 ////////////////////////////////////////////////////////////////////////////////
 
+use sha2::Digest;
 use signature::{Signature, Signer as RustCryptoSigner};
 use tink::{utils::wrap_err, Signer, TinkError};
+use zeroize::Zeroizing;
 
 /// A [`Signer`] implementation for ED25519.
 pub struct Ed25519Signer {
@@ -25,12 +27,26 @@ pub struct Ed25519Signer {
 /// Manual implementation of [`Clone`].
 impl Clone for Ed25519Signer {
     fn clone(&self) -> Self {
+        let bytes = Zeroizing::new(self.keypair.to_bytes());
         Self {
-            keypair: ed25519_dalek::Keypair::from_bytes(&self.keypair.to_bytes()).unwrap(), /* safe: round-trip */
+            keypair: ed25519_dalek::Keypair::from_bytes(&*bytes).unwrap(), /* safe: round-trip */
         }
     }
 }
 
+/// Manual implementation of [`Debug`](std::fmt::Debug) that redacts the private key.
+impl std::fmt::Debug for Ed25519Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Ed25519Signer")
+            .field("public_key", &self.keypair.public.as_bytes())
+            .field(
+                "private_key",
+                &tink::utils::SecretBytes(self.keypair.secret.as_bytes()),
+            )
+            .finish()
+    }
+}
+
 impl Ed25519Signer {
     /// Create an [`Ed25519Signer`] from the provided seed, which must be 32 bytes.
     /// RFC8032's private keys correspond to seeds here.
@@ -54,4 +70,21 @@ impl Signer for Ed25519Signer {
         let r = self.keypair.sign(data);
         Ok(r.as_bytes().to_vec())
     }
+
+    /// Sign all of the data available from `reader` with Ed25519ph (RFC 8032 section 5.1), which
+    /// signs the SHA-512 hash of the data so that the data can be hashed incrementally.
+    ///
+    /// The result is **not** the same as [`sign`](Signer::sign) over the complete data, and can
+    /// only be verified with [`verify_reader`](tink::Verifier::verify_reader) or
+    /// [`new_verification`](tink::Verifier::new_verification).
+    fn sign_reader(&self, reader: &mut dyn std::io::Read) -> Result<Vec<u8>, tink::TinkError> {
+        let mut digest = sha2::Sha512::new();
+        std::io::copy(reader, &mut digest)
+            .map_err(|e| wrap_err("Ed25519Signer: failed to read data", e))?;
+        let r = self
+            .keypair
+            .sign_prehashed(digest, None)
+            .map_err(|e| wrap_err("Ed25519Signer: signing failed", e))?;
+        Ok(r.as_bytes().to_vec())
+    }
 }
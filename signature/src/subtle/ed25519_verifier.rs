@@ -14,8 +14,9 @@
 //
 ////////////////////////////////////////////////////////////////////////////////
 
+use sha2::Digest;
 use signature::{Signature, Verifier as RustCryptoVerifier};
-use tink::{utils::wrap_err, TinkError};
+use tink::{utils::wrap_err, ErrorKind, TinkError};
 
 /// A [`tink::Verifier`] for ED25519.
 #[derive(Clone)]
@@ -41,17 +42,65 @@ impl Ed25519Verifier {
 
 impl tink::Verifier for Ed25519Verifier {
     fn verify(&self, signature: &[u8], data: &[u8]) -> Result<(), tink::TinkError> {
-        if signature.len() != ed25519_dalek::SIGNATURE_LENGTH {
-            return Err(format!(
+        let s = parse_signature(signature)?;
+        self.public_key.verify(data, &s).map_err(|_| {
+            TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "Ed25519Verifier: invalid signature",
+            )
+        })
+    }
+
+    /// Start an incremental verification of an Ed25519ph signature, as produced by
+    /// [`Ed25519Signer::sign_reader`](tink::Signer::sign_reader).  Plain Ed25519 signatures
+    /// (from [`sign`](tink::Signer::sign)) are not accepted.
+    fn new_verification(
+        &self,
+        signature: &[u8],
+    ) -> Result<Box<dyn tink::SignatureVerification>, tink::TinkError> {
+        Ok(Box::new(Ed25519phVerification {
+            public_key: self.public_key,
+            signature: parse_signature(signature)?,
+            digest: sha2::Sha512::new(),
+        }))
+    }
+}
+
+fn parse_signature(signature: &[u8]) -> Result<ed25519_dalek::Signature, TinkError> {
+    if signature.len() != ed25519_dalek::SIGNATURE_LENGTH {
+        return Err(TinkError::new_with_kind(
+            ErrorKind::Authentication,
+            &format!(
                 "the length of the signature is not {}",
                 ed25519_dalek::SIGNATURE_LENGTH
-            )
-            .into());
-        }
-        let s = ed25519_dalek::Signature::from_bytes(signature)
-            .map_err(|e| wrap_err("invalid signature", e))?;
+            ),
+        ));
+    }
+    ed25519_dalek::Signature::from_bytes(signature)
+        .map_err(|e| wrap_err("invalid signature", e).with_kind(ErrorKind::Authentication))
+}
+
+/// Incremental Ed25519ph verification, as returned by [`Ed25519Verifier`].
+struct Ed25519phVerification {
+    public_key: ed25519_dalek::PublicKey,
+    signature: ed25519_dalek::Signature,
+    digest: sha2::Sha512,
+}
+
+impl tink::SignatureVerification for Ed25519phVerification {
+    fn update(&mut self, data: &[u8]) -> Result<(), tink::TinkError> {
+        self.digest.update(data);
+        Ok(())
+    }
+
+    fn verify(self: Box<Self>) -> Result<(), tink::TinkError> {
         self.public_key
-            .verify(data, &s)
-            .map_err(|_| TinkError::new("Ed25519Verifier: invalid signature"))
+            .verify_prehashed(self.digest, None, &self.signature)
+            .map_err(|_| {
+                TinkError::new_with_kind(
+                    ErrorKind::Authentication,
+                    "Ed25519Verifier: invalid signature",
+                )
+            })
     }
 }
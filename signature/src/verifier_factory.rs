@@ -17,7 +17,7 @@
 //! Factory methods for [`tink::Verifier`] instances.
 
 use std::sync::Arc;
-use tink::{utils::wrap_err, TinkError};
+use tink::{monitoring::Logger, utils::wrap_err, ErrorKind, TinkError};
 
 /// Return a [`tink::Verifier`] primitive from the given keyset handle.
 pub fn new_verifier(h: &tink::keyset::Handle) -> Result<Box<dyn tink::Verifier>, TinkError> {
@@ -37,31 +37,78 @@ pub fn new_verifier_with_key_manager(
     Ok(Box::new(ret))
 }
 
+/// Return a [`tink::Verifier`] primitive from the given keyset handle, using the key managers
+/// in the given registry configuration.
+pub fn new_verifier_with_config(
+    h: &tink::keyset::Handle,
+    config: &tink::registry::Registry,
+) -> Result<Box<dyn tink::Verifier>, TinkError> {
+    let ps = h
+        .primitives_with_config(config)
+        .map_err(|e| wrap_err("verifier::factory: cannot obtain primitive set", e))?;
+
+    let ret = WrappedVerifier::new(ps)?;
+    Ok(Box::new(ret))
+}
+
+/// [`PrimitiveWrapper`](tink::registry::PrimitiveWrapper) that combines the primitives for the keys
+/// in a keyset into a single [`tink::Verifier`] primitive. Registered by [`init`](crate::init).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct VerifierWrapper;
+
+impl tink::registry::PrimitiveWrapper for VerifierWrapper {
+    type Primitive = dyn tink::Verifier;
+
+    fn wrap(
+        &self,
+        ps: tink::primitiveset::PrimitiveSet,
+    ) -> Result<Box<dyn tink::Verifier>, TinkError> {
+        Ok(Box::new(WrappedVerifier::new(ps)?))
+    }
+}
+
 /// A [`tink::Verifier`] implementation that uses the underlying primitive set for verifying.
 #[derive(Clone)]
 struct WrappedVerifier {
     ps: tink::primitiveset::PrimitiveSet,
+    logger: Arc<dyn Logger>,
 }
 
 impl WrappedVerifier {
     fn new(ps: tink::primitiveset::PrimitiveSet) -> Result<WrappedVerifier, TinkError> {
         let primary = match &ps.primary {
-            None => return Err("verifier::factory: no primary primitive".into()),
+            None => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "verifier::factory: no primary primitive",
+                ))
+            }
             Some(p) => p,
         };
         match primary.primitive {
             tink::Primitive::Verifier(_) => {}
-            _ => return Err("verifier::factory: not a Verifier primitive".into()),
+            _ => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "verifier::factory: not a Verifier primitive",
+                ))
+            }
         };
-        for (_, primitives) in ps.entries.iter() {
+        for (_, primitives) in ps.entries().iter() {
             for p in primitives {
                 match p.primitive {
                     tink::Primitive::Verifier(_) => {}
-                    _ => return Err("verifier::factory: not a Verifier primitive".into()),
+                    _ => {
+                        return Err(TinkError::new_with_kind(
+                            ErrorKind::InvalidKeyset,
+                            "verifier::factory: not a Verifier primitive",
+                        ))
+                    }
                 };
             }
         }
-        Ok(WrappedVerifier { ps })
+        let logger = tink::monitoring::new_logger(&ps, "public_key_verify", "verify")?;
+        Ok(WrappedVerifier { ps, logger })
     }
 }
 
@@ -69,15 +116,20 @@ impl tink::Verifier for WrappedVerifier {
     fn verify(&self, signature: &[u8], data: &[u8]) -> Result<(), TinkError> {
         let prefix_size = tink::cryptofmt::NON_RAW_PREFIX_SIZE;
         if signature.len() < prefix_size {
-            return Err("verifier::factory: invalid signature".into());
+            self.logger.log_failure();
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "verifier::factory: invalid signature",
+            ));
         }
+        let mut tried = false;
 
         // try non-raw keys
         let prefix = &signature[..prefix_size];
         let signature_no_prefix = &signature[prefix_size..];
-        let entries = self.ps.entries_for_prefix(&prefix);
-        for entry in &entries {
+        for entry in self.ps.entries_for_prefix(prefix) {
             if let tink::Primitive::Verifier(p) = &entry.primitive {
+                tried = true;
                 let result = if entry.prefix_type == tink::proto::OutputPrefixType::Legacy {
                     let mut signed_data_copy = Vec::with_capacity(data.len() + 1);
                     signed_data_copy.extend_from_slice(data);
@@ -87,25 +139,142 @@ impl tink::Verifier for WrappedVerifier {
                     p.verify(signature_no_prefix, data)
                 };
                 if result.is_ok() {
+                    self.logger.log(entry.key_id, data.len());
                     return Ok(());
                 }
             } else {
-                return Err("verifier::factory: not a Verifier primitive".into());
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "verifier::factory: not a Verifier primitive",
+                ));
             }
         }
 
         // try raw keys
-        let entries = self.ps.raw_entries();
-        for entry in &entries {
+        for entry in self.ps.raw_entries() {
             if let tink::Primitive::Verifier(p) = &entry.primitive {
+                tried = true;
                 if p.verify(signature, data).is_ok() {
+                    self.logger.log(entry.key_id, data.len());
                     return Ok(());
                 }
             } else {
-                return Err("verifier::factory: not a Verifier primitive".into());
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "verifier::factory: not a Verifier primitive",
+                ));
+            }
+        }
+
+        self.logger.log_failure();
+        Err(TinkError::new_with_kind(
+            if tried {
+                ErrorKind::Authentication
+            } else {
+                ErrorKind::KeyNotFound
+            },
+            "verifier::factory: invalid signature",
+        ))
+    }
+    fn new_verification(
+        &self,
+        signature: &[u8],
+    ) -> Result<Box<dyn tink::SignatureVerification>, TinkError> {
+        let prefix_size = tink::cryptofmt::NON_RAW_PREFIX_SIZE;
+        if signature.len() < prefix_size {
+            self.logger.log_failure();
+            return Err(TinkError::new_with_kind(
+                ErrorKind::Authentication,
+                "verifier::factory: invalid signature",
+            ));
+        }
+
+        // Verify in parallel against every key that could have produced the signature.
+        let prefix = &signature[..prefix_size];
+        let signature_no_prefix = &signature[prefix_size..];
+        let candidates = self
+            .ps
+            .entries_for_prefix(prefix)
+            .iter()
+            .map(|entry| (entry, signature_no_prefix))
+            .chain(self.ps.raw_entries().iter().map(|entry| (entry, signature)));
+        let mut tried = false;
+        let mut verifications = Vec::new();
+        for (entry, entry_signature) in candidates {
+            let p = match &entry.primitive {
+                tink::Primitive::Verifier(p) => p,
+                _ => {
+                    return Err(TinkError::new_with_kind(
+                        ErrorKind::InvalidKeyset,
+                        "verifier::factory: not a Verifier primitive",
+                    ))
+                }
+            };
+            tried = true;
+            // A signature that cannot be parsed for this key just means that it was not produced
+            // by this key.
+            if let Ok(verification) = p.new_verification(entry_signature) {
+                verifications.push((entry.clone(), verification));
             }
         }
+        if verifications.is_empty() {
+            self.logger.log_failure();
+            return Err(TinkError::new_with_kind(
+                if tried {
+                    ErrorKind::Authentication
+                } else {
+                    ErrorKind::KeyNotFound
+                },
+                "verifier::factory: invalid signature",
+            ));
+        }
+        Ok(Box::new(WrappedSignatureVerification {
+            verifications,
+            logger: self.logger.clone(),
+            data_len: 0,
+        }))
+    }
+}
+
+/// Incremental verification against all of the keys in a keyset that could have produced a
+/// signature.
+struct WrappedSignatureVerification {
+    verifications: Vec<(
+        Arc<tink::primitiveset::Entry>,
+        Box<dyn tink::SignatureVerification>,
+    )>,
+    logger: Arc<dyn Logger>,
+    data_len: usize,
+}
+
+impl tink::SignatureVerification for WrappedSignatureVerification {
+    fn update(&mut self, data: &[u8]) -> Result<(), TinkError> {
+        self.data_len += data.len();
+        for (_, verification) in self.verifications.iter_mut() {
+            verification.update(data)?;
+        }
+        Ok(())
+    }
 
-        Err("verifier::factory: invalid signature".into())
+    fn verify(self: Box<Self>) -> Result<(), TinkError> {
+        for (entry, mut verification) in self.verifications {
+            // As for `verify`, LEGACY keys sign the data followed by a zero byte.
+            if entry.prefix_type == tink::proto::OutputPrefixType::Legacy
+                && verification
+                    .update(&[tink::cryptofmt::LEGACY_START_BYTE])
+                    .is_err()
+            {
+                continue;
+            }
+            if verification.verify().is_ok() {
+                self.logger.log(entry.key_id, self.data_len);
+                return Ok(());
+            }
+        }
+        self.logger.log_failure();
+        Err(TinkError::new_with_kind(
+            ErrorKind::Authentication,
+            "verifier::factory: invalid signature",
+        ))
     }
 }
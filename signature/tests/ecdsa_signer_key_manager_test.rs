@@ -168,7 +168,7 @@ fn test_ecdsa_sign_new_key_multiple_times() {
             keys.insert(serialized_key);
 
             let key_data = km.new_key_data(&serialized_format).unwrap();
-            let serialized_key = key_data.value;
+            let serialized_key = key_data.value.clone();
             keys.insert(serialized_key);
         }
         assert_eq!(
@@ -39,8 +39,8 @@ fn test_ed25519_signer_get_primitive_basic() {
 
     let km_pub = tink::registry::get_key_manager(tink_testutil::ED25519_VERIFIER_TYPE_URL)
         .expect("cannot obtain Ed25519Verifier key manager");
-    let pub_key = pvt_key.public_key.unwrap();
-    let serialized_key = tink_testutil::proto_encode(&pub_key);
+    let pub_key = pvt_key.public_key.as_ref().unwrap();
+    let serialized_key = tink_testutil::proto_encode(pub_key);
     let tmp = km_pub
         .primitive(&serialized_key)
         .expect("unexpected error in test case");
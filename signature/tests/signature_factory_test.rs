@@ -80,6 +80,73 @@ fn test_signer_verify_factory() {
     );
 }
 
+#[test]
+fn test_factory_sign_verify_reader() {
+    tink_signature::init();
+    let prefix_types = vec![
+        tink::proto::OutputPrefixType::Tink,
+        tink::proto::OutputPrefixType::Legacy,
+        tink::proto::OutputPrefixType::Raw,
+        tink::proto::OutputPrefixType::Crunchy,
+    ];
+    let (priv_keys, pub_keys): (Vec<_>, Vec<_>) = prefix_types
+        .iter()
+        .enumerate()
+        .map(|(i, prefix_type)| {
+            new_ecdsa_keyset_keypair(
+                tink::proto::HashType::Sha256,
+                tink::proto::EllipticCurveType::NistP256,
+                *prefix_type,
+                i as tink::KeyId + 1,
+            )
+        })
+        .unzip();
+    let pub_keyset = tink_testutil::new_keyset(pub_keys[0].key_id, pub_keys);
+    let pub_keyset_handle = tink::keyset::insecure::new_handle(pub_keyset).unwrap();
+    let verifier = tink_signature::new_verifier(&pub_keyset_handle).unwrap();
+    let data = get_random_bytes(20000);
+
+    for primary in &priv_keys {
+        let priv_keyset = tink_testutil::new_keyset(primary.key_id, priv_keys.clone());
+        let priv_keyset_handle = tink::keyset::insecure::new_handle(priv_keyset).unwrap();
+        let signer = tink_signature::new_signer(&priv_keyset_handle).unwrap();
+
+        // ECDSA signatures over a reader and over a slice are interchangeable.
+        let sig = signer.sign_reader(&mut &data[..]).unwrap();
+        assert!(verifier.verify(&sig, &data).is_ok());
+        assert!(verifier.verify_reader(&sig, &mut &data[..]).is_ok());
+        let sig = signer.sign(&data).unwrap();
+        assert!(verifier.verify_reader(&sig, &mut &data[..]).is_ok());
+
+        let mut modified = data.clone();
+        modified[0] ^= 0x01;
+        assert!(verifier.verify_reader(&sig, &mut &modified[..]).is_err());
+    }
+
+    // Ed25519 keys sign the data read from a reader with Ed25519ph.
+    for template in vec![
+        tink_signature::ed25519_key_template(),
+        tink_signature::ed25519_key_without_prefix_template(),
+    ] {
+        let kh = tink::keyset::Handle::new(&template).unwrap();
+        let signer = tink_signature::new_signer(&kh).unwrap();
+        let verifier = tink_signature::new_verifier(&kh.public().unwrap()).unwrap();
+        let sig = signer.sign_reader(&mut &data[..]).unwrap();
+        assert!(verifier.verify_reader(&sig, &mut &data[..]).is_ok());
+        assert!(verifier.verify(&sig, &data).is_err());
+        let sig = signer.sign(&data).unwrap();
+        assert!(verifier.verify_reader(&sig, &mut &data[..]).is_err());
+    }
+
+    // Signatures from keys outside the keyset are rejected.
+    let other_kh = tink::keyset::Handle::new(&tink_signature::ecdsa_p256_key_template()).unwrap();
+    let sig = tink_signature::new_signer(&other_kh)
+        .unwrap()
+        .sign_reader(&mut &data[..])
+        .unwrap();
+    assert!(verifier.verify_reader(&sig, &mut &data[..]).is_err());
+}
+
 fn new_ecdsa_keyset_keypair(
     hash_type: tink::proto::HashType,
     curve: tink::proto::EllipticCurveType,
@@ -100,7 +167,7 @@ fn new_ecdsa_keyset_keypair(
         output_prefix_type,
     );
 
-    let serialized_key = tink_testutil::proto_encode(&key.public_key.unwrap());
+    let serialized_key = tink_testutil::proto_encode(key.public_key.as_ref().unwrap());
     let key_data = tink_testutil::new_key_data(
         tink_testutil::ECDSA_VERIFIER_TYPE_URL,
         &serialized_key,
@@ -100,6 +100,58 @@ fn test_sign_verify() {
     }
 }
 
+#[test]
+fn test_sign_verify_reader() {
+    let mut csprng = rand::thread_rng();
+    let data = get_random_bytes(20000);
+    let hash = HashType::Sha256;
+    let curve = EllipticCurveType::NistP256;
+    for encoding in vec![
+        EcdsaSignatureEncoding::Der,
+        EcdsaSignatureEncoding::IeeeP1363,
+    ] {
+        let secret_key = p256::ecdsa::SigningKey::random(&mut csprng);
+        let public_key = p256::ecdsa::VerifyingKey::from(&secret_key);
+        let signer = subtle::EcdsaSigner::new_from_private_key(
+            hash,
+            curve,
+            encoding,
+            EcdsaPrivateKey::NistP256(secret_key),
+        )
+        .unwrap();
+        let verifier = subtle::EcdsaVerifier::new_from_public_key(
+            hash,
+            curve,
+            encoding,
+            EcdsaPublicKey::NistP256(public_key),
+        )
+        .unwrap();
+
+        // Signatures over a reader and over a slice are interchangeable.
+        let signature = signer.sign_reader(&mut &data[..]).unwrap();
+        assert!(verifier.verify(&signature, &data).is_ok());
+        assert!(verifier.verify_reader(&signature, &mut &data[..]).is_ok());
+        let signature = signer.sign(&data).unwrap();
+        assert!(verifier.verify_reader(&signature, &mut &data[..]).is_ok());
+
+        let mut verification = verifier.new_verification(&signature).unwrap();
+        for chunk in data.chunks(999) {
+            verification.update(chunk).unwrap();
+        }
+        assert!(verification.verify().is_ok());
+
+        let mut modified = data.clone();
+        modified[12345] ^= 0x01;
+        assert!(verifier
+            .verify_reader(&signature, &mut &modified[..])
+            .is_err());
+        assert!(verifier
+            .verify_reader(&signature, &mut &data[..data.len() - 1])
+            .is_err());
+        assert!(verifier.new_verification(&signature[1..]).is_err());
+    }
+}
+
 #[test]
 fn test_ecdsa_invalid_params() {
     let mut csprng = rand::thread_rng();
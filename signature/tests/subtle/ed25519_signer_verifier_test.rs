@@ -43,6 +43,21 @@ fn test_ed25519_deterministic() {
     assert_eq!(sign1, sign2, "deterministic signature check failure");
 }
 
+#[test]
+fn test_ed25519_signer_debug_redacted() {
+    let mut csprng = rand::thread_rng();
+    let keypair = Keypair::generate(&mut csprng);
+    let secret = format!("{:?}", keypair.secret.as_bytes());
+    let signer = Ed25519Signer::new(keypair.secret.as_bytes()).unwrap();
+
+    let debug = format!("{:?}", signer);
+    assert!(debug.contains("<redacted 32 bytes>"), "{}", debug);
+    assert!(!debug.contains(&secret), "{}", debug);
+
+    let debug = format!("{:?}", tink::keyset::insecure::Unredacted(&signer));
+    assert!(debug.contains(&secret), "{}", debug);
+}
+
 #[test]
 fn test_ed25519_verify_modified_signature() {
     let data = get_random_bytes(20);
@@ -179,6 +194,59 @@ struct TestCaseEd25519 {
     pub sig: Vec<u8>,
 }
 
+#[test]
+fn test_ed25519ph_sign_verify_reader() {
+    let data = get_random_bytes(20000);
+    let mut csprng = rand::thread_rng();
+    let keypair = Keypair::generate(&mut csprng);
+    let (signer, verifier) = new_signer_verifier(keypair).unwrap();
+
+    let signature = signer.sign_reader(&mut &data[..]).unwrap();
+    assert!(verifier.verify_reader(&signature, &mut &data[..]).is_ok());
+    let mut verification = verifier.new_verification(&signature).unwrap();
+    for chunk in data.chunks(999) {
+        verification.update(chunk).unwrap();
+    }
+    assert!(verification.verify().is_ok());
+
+    // Ed25519ph and plain Ed25519 signatures are not interchangeable.
+    assert!(verifier.verify(&signature, &data).is_err());
+    let plain_signature = signer.sign(&data).unwrap();
+    assert!(verifier
+        .verify_reader(&plain_signature, &mut &data[..])
+        .is_err());
+
+    let mut modified = data.clone();
+    modified[12345] ^= 0x01;
+    assert!(verifier
+        .verify_reader(&signature, &mut &modified[..])
+        .is_err());
+    assert!(verifier
+        .verify_reader(&signature[..signature.len() - 1], &mut &data[..])
+        .is_err());
+}
+
+#[test]
+fn test_ed25519ph_rfc8032_vector() {
+    // Test vector from RFC 8032 section 7.3.
+    let seed =
+        hex::decode("833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42").unwrap();
+    let public_key =
+        hex::decode("ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf").unwrap();
+    let message = hex::decode("616263").unwrap();
+    let want = hex::decode(
+        "98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae41\
+         31f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406",
+    )
+    .unwrap();
+
+    let signer = Ed25519Signer::new(&seed).unwrap();
+    let verifier = Ed25519Verifier::new(&public_key).unwrap();
+    let signature = signer.sign_reader(&mut &message[..]).unwrap();
+    assert_eq!(hex::encode(&signature), hex::encode(&want));
+    assert!(verifier.verify_reader(&want, &mut &message[..]).is_ok());
+}
+
 #[test]
 fn test_vectors_ed25519() {
     // signing tests are same between ecdsa and ed25519
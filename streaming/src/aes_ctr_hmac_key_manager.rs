@@ -41,11 +41,13 @@ impl tink::registry::KeyManager for AesCtrHmacKeyManager {
 
         let key_params = key
             .params
+            .as_ref()
             .ok_or_else(|| TinkError::new("AesCtrHmacKeyManager: no params"))?;
         let hkdf_hash = HashType::from_i32(key_params.hkdf_hash_type)
             .ok_or_else(|| TinkError::new("AesCtrHmacKeyManager: unknown hash"))?;
         let hmac_params = key_params
             .hmac_params
+            .as_ref()
             .ok_or_else(|| TinkError::new("AesCtrHmacKeyManager: no params"))?;
         let hmac_hash = HashType::from_i32(hmac_params.hash)
             .ok_or_else(|| TinkError::new("AesCtrHmacKeyManager: unknown hash"))?;
@@ -99,6 +101,14 @@ impl tink::registry::KeyManager for AesCtrHmacKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
+
+    fn version(&self) -> u32 {
+        AES_CTR_HMAC_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "StreamingAead"
+    }
 }
 
 /// Validate the given [`tink::proto::AesCtrHmacStreamingKey`].
@@ -41,6 +41,7 @@ impl tink::registry::KeyManager for AesGcmHkdfKeyManager {
 
         let key_params = key
             .params
+            .as_ref()
             .ok_or_else(|| TinkError::new("AesGcmHkdfKeyManager: no params"))?;
         let hkdf_hash = HashType::from_i32(key_params.hkdf_hash_type)
             .ok_or_else(|| TinkError::new("AesGcmHkdfKeyManager: unknown hash"))?;
@@ -90,6 +91,14 @@ impl tink::registry::KeyManager for AesGcmHkdfKeyManager {
     fn key_material_type(&self) -> tink::proto::key_data::KeyMaterialType {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
+
+    fn version(&self) -> u32 {
+        AES_GCM_HKDF_KEY_VERSION
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        "StreamingAead"
+    }
 }
 
 /// Validate the given [`tink::proto::AesGcmHkdfStreamingKey`].
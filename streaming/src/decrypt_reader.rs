@@ -23,18 +23,26 @@ use std::{
 enum State {
     // Matching primitive not yet determined, raw ciphertext reader available.
     Pending(Box<dyn io::Read>),
-    // Matching primitive that correctly decrypts has been found.
-    Found(Box<dyn io::Read>),
+    // Matching primitive that correctly decrypts has been found, for the given key.
+    Found(Box<dyn io::Read>, tink::KeyId),
+    // Decryption has finished (successfully or not), and the monitoring event for the stream has
+    // been logged.
+    Done(Box<dyn io::Read>),
     // No matching primitive available.
     Failed,
 }
 
 /// `DecryptReader` is a reader that tries to find the right key to decrypt ciphertext from the
 /// given primitive set.
+///
+/// A single monitoring event is logged for the stream: a success (with the total number of bytes
+/// of plaintext) when the end of the stream is reached, or a failure on the first error.
 pub(crate) struct DecryptReader {
     wrapped: crate::WrappedStreamingAead,
     aad: Vec<u8>,
     state: State,
+    /// Number of plaintext bytes read so far.
+    num_bytes: usize,
 }
 
 impl DecryptReader {
@@ -47,6 +55,40 @@ impl DecryptReader {
             wrapped,
             aad: aad.to_vec(),
             state: State::Pending(reader),
+            num_bytes: 0,
+        }
+    }
+
+    /// Record the result of a read from the decrypting reader for `key_id`, logging the event
+    /// for the stream at the end of the stream or on failure.
+    fn record(
+        &mut self,
+        key_id: tink::KeyId,
+        buf_len: usize,
+        result: io::Result<usize>,
+    ) -> io::Result<usize> {
+        match result {
+            Ok(n) => {
+                self.num_bytes += n;
+                if n == 0 && buf_len > 0 {
+                    self.wrapped.decrypt_logger.log(key_id, self.num_bytes);
+                    self.finish();
+                }
+                Ok(n)
+            }
+            Err(e) => {
+                self.wrapped.decrypt_logger.log_failure();
+                self.finish();
+                Err(e)
+            }
+        }
+    }
+
+    /// Move to the [`State::Done`] state, as the event for the stream has been logged.
+    fn finish(&mut self) {
+        let state = std::mem::replace(&mut self.state, State::Failed);
+        if let State::Found(reader, _) = state {
+            self.state = State::Done(reader);
         }
     }
 }
@@ -54,7 +96,12 @@ impl DecryptReader {
 impl io::Read for DecryptReader {
     fn read(&mut self, p: &mut [u8]) -> io::Result<usize> {
         match &mut self.state {
-            State::Found(reader) => return reader.read(p),
+            State::Found(reader, key_id) => {
+                let key_id = *key_id;
+                let result = reader.read(p);
+                return self.record(key_id, p.len(), result);
+            }
+            State::Done(reader) => return reader.read(p),
             State::Failed => {
                 return Err(io::Error::new(
                     io::ErrorKind::InvalidInput,
@@ -72,8 +119,7 @@ impl io::Read for DecryptReader {
         let mut copy_reader = SharedCopyReader::new(raw_reader);
 
         // find proper key to decrypt ciphertext
-        let entries = self.wrapped.ps.raw_entries();
-        for e in &entries {
+        for e in self.wrapped.ps.raw_entries() {
             let sa = match &e.primitive {
                 tink::Primitive::StreamingAead(p) => p,
                 _ => continue,
@@ -103,9 +149,11 @@ impl io::Read for DecryptReader {
             // Reading has succeeded, so use this particular key from now on and no longer need
             // to store copies of read data.
             copy_reader.stop_copying();
-            self.state = State::Found(r);
-            return Ok(n);
+            let key_id = e.key_id;
+            self.state = State::Found(r, key_id);
+            return self.record(key_id, p.len(), Ok(n));
         }
+        self.wrapped.decrypt_logger.log_failure();
         Err(io::Error::new(
             io::ErrorKind::InvalidInput,
             "no matching key found for the ciphertext in the stream",
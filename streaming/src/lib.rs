@@ -45,6 +45,9 @@ static INIT: Once = Once::new();
 
 pub fn init() {
     INIT.call_once(|| {
+        tink::registry::register_primitive_wrapper(StreamingAeadWrapper)
+            .expect("tink_streaming_aead::init() failed"); // safe: init
+
         register_key_manager(std::sync::Arc::new(AesCtrHmacKeyManager::default()))
             .expect("tink_streaming_aead::init() failed"); // safe: init
         register_key_manager(std::sync::Arc::new(AesGcmHkdfKeyManager::default()))
@@ -14,7 +14,8 @@
 //
 ////////////////////////////////////////////////////////////////////////////////
 
-use tink::{utils::wrap_err, TinkError};
+use std::sync::Arc;
+use tink::{monitoring::Logger, utils::wrap_err, ErrorKind, TinkError};
 
 /// Return a [`tink::StreamingAead`] primitive from the given keyset handle.
 pub fn new(h: &tink::keyset::Handle) -> Result<Box<dyn tink::StreamingAead>, TinkError> {
@@ -34,32 +35,86 @@ pub fn new_with_key_manager(
     Ok(Box::new(ret))
 }
 
+/// Return a [`tink::StreamingAead`] primitive from the given keyset handle, using the key
+/// managers in the given registry configuration.
+pub fn new_with_config(
+    h: &tink::keyset::Handle,
+    config: &tink::registry::Registry,
+) -> Result<Box<dyn tink::StreamingAead>, TinkError> {
+    let ps = h
+        .primitives_with_config(config)
+        .map_err(|e| wrap_err("streaming_aead::factory: cannot obtain primitive set", e))?;
+
+    let ret = WrappedStreamingAead::new(ps)?;
+    Ok(Box::new(ret))
+}
+
+/// [`PrimitiveWrapper`](tink::registry::PrimitiveWrapper) that combines the primitives for the keys
+/// in a keyset into a single [`tink::StreamingAead`] primitive. Registered by
+/// [`init`](crate::init).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StreamingAeadWrapper;
+
+impl tink::registry::PrimitiveWrapper for StreamingAeadWrapper {
+    type Primitive = dyn tink::StreamingAead;
+
+    fn wrap(
+        &self,
+        ps: tink::primitiveset::PrimitiveSet,
+    ) -> Result<Box<dyn tink::StreamingAead>, TinkError> {
+        Ok(Box::new(WrappedStreamingAead::new(ps)?))
+    }
+}
+
 // `WrappedStreamingAead` is a  [`tink::StreamingAead`] implementation that uses the underlying
 // primitive set for deterministic encryption and decryption.
 #[derive(Clone)]
 pub(crate) struct WrappedStreamingAead {
     pub(crate) ps: tink::primitiveset::PrimitiveSet,
+    encrypt_logger: Arc<dyn Logger>,
+    pub(crate) decrypt_logger: Arc<dyn Logger>,
 }
 
 impl WrappedStreamingAead {
     fn new(ps: tink::primitiveset::PrimitiveSet) -> Result<WrappedStreamingAead, TinkError> {
         let entry = match &ps.primary {
-            None => return Err("streaming_aead::factory: no primary primitive".into()),
+            None => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "streaming_aead::factory: no primary primitive",
+                ))
+            }
             Some(p) => p,
         };
         match entry.primitive {
             tink::Primitive::StreamingAead(_) => {}
-            _ => return Err("streaming_aead::factory: not a StreamingAead primitive".into()),
+            _ => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "streaming_aead::factory: not a StreamingAead primitive",
+                ))
+            }
         };
-        for (_, primitives) in ps.entries.iter() {
+        for (_, primitives) in ps.entries().iter() {
             for p in primitives {
                 match p.primitive {
                     tink::Primitive::StreamingAead(_) => {}
-                    _ => return Err("aead::factory: not a StreamingAead primitive".into()),
+                    _ => {
+                        return Err(TinkError::new_with_kind(
+                            ErrorKind::InvalidKeyset,
+                            "aead::factory: not a StreamingAead primitive",
+                        ))
+                    }
                 };
             }
         }
-        Ok(WrappedStreamingAead { ps })
+        let encrypt_logger = tink::monitoring::new_logger(&ps, "streamingaead", "encrypt")?;
+        let decrypt_logger = tink::monitoring::new_logger(&ps, "streamingaead", "decrypt")?;
+        Ok(WrappedStreamingAead {
+            ps,
+            encrypt_logger,
+            decrypt_logger,
+        })
     }
 }
 
@@ -70,14 +125,37 @@ impl tink::StreamingAead for WrappedStreamingAead {
         aad: &[u8],
     ) -> Result<Box<dyn tink::EncryptingWrite>, TinkError> {
         let entry = match &self.ps.primary {
-            None => return Err("streaming_aead::factory: no primary primitive".into()),
+            None => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "streaming_aead::factory: no primary primitive",
+                ))
+            }
             Some(p) => p,
         };
         let p = match &entry.primitive {
             tink::Primitive::StreamingAead(p) => p,
-            _ => return Err("streaming_aead::factory: not a StreamingAead primitive".into()),
+            _ => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "streaming_aead::factory: not a StreamingAead primitive",
+                ))
+            }
+        };
+        let writer = match p.new_encrypting_writer(w, aad) {
+            Ok(writer) => writer,
+            Err(e) => {
+                self.encrypt_logger.log_failure();
+                return Err(e);
+            }
         };
-        p.new_encrypting_writer(w, aad)
+        Ok(Box::new(MonitoredWriter {
+            writer,
+            key_id: entry.key_id,
+            logger: self.encrypt_logger.clone(),
+            num_bytes: 0,
+            logged: false,
+        }))
     }
 
     /// Return a wrapper around an underlying `std::io::Read`, such that any read-operation
@@ -91,3 +169,59 @@ impl tink::StreamingAead for WrappedStreamingAead {
         Ok(Box::new(crate::DecryptReader::new(self.clone(), r, aad)))
     }
 }
+
+/// Wrapper around an [`tink::EncryptingWrite`] that reports the plaintext written through it to a
+/// monitoring [`Logger`], as a single event for the whole stream when it is closed (or a single
+/// failure event).
+struct MonitoredWriter {
+    writer: Box<dyn tink::EncryptingWrite>,
+    key_id: tink::KeyId,
+    logger: Arc<dyn Logger>,
+    /// Number of plaintext bytes written so far.
+    num_bytes: usize,
+    /// Whether the event for the stream has been logged.
+    logged: bool,
+}
+
+impl MonitoredWriter {
+    fn log_failure(&mut self) {
+        if !self.logged {
+            self.logged = true;
+            self.logger.log_failure();
+        }
+    }
+}
+
+impl std::io::Write for MonitoredWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self.writer.write(buf) {
+            Ok(n) => {
+                self.num_bytes += n;
+                Ok(n)
+            }
+            Err(e) => {
+                self.log_failure();
+                Err(e)
+            }
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl tink::EncryptingWrite for MonitoredWriter {
+    fn close(&mut self) -> Result<(), TinkError> {
+        let result = self.writer.close();
+        match result {
+            Ok(()) if !self.logged => {
+                self.logged = true;
+                self.logger.log(self.key_id, self.num_bytes);
+            }
+            Ok(()) => {}
+            Err(_) => self.log_failure(),
+        }
+        result
+    }
+}
@@ -20,6 +20,7 @@ use super::noncebased;
 use aes_ctr::cipher::stream::{Key, NewStreamCipher, SyncStreamCipher};
 use std::convert::TryInto;
 use tink::{proto::HashType, subtle::random::get_random_bytes, utils::wrap_err, Mac, TinkError};
+use zeroize::{Zeroize, Zeroizing};
 
 /// The size of the nonces used as IVs for CTR.
 pub const AES_CTR_HMAC_NONCE_SIZE_IN_BYTES: usize = 16;
@@ -36,6 +37,15 @@ enum AesCtrKeyVariant {
     Aes256(Key<aes_ctr::Aes256Ctr>),
 }
 
+impl Drop for AesCtrKeyVariant {
+    fn drop(&mut self) {
+        match self {
+            AesCtrKeyVariant::Aes128(key) => key.as_mut_slice().zeroize(),
+            AesCtrKeyVariant::Aes256(key) => key.as_mut_slice().zeroize(),
+        }
+    }
+}
+
 /// `AesCtrHmac` implements streaming AEAD encryption using AES-CTR and HMAC.
 ///
 /// Each ciphertext uses new AES-CTR and HMAC keys. These keys are derived using
@@ -53,6 +63,12 @@ pub struct AesCtrHmac {
     first_ciphertext_segment_offset: usize,
 }
 
+impl Drop for AesCtrHmac {
+    fn drop(&mut self) {
+        self.main_key.zeroize();
+    }
+}
+
 /// Calculate the header length for a given key size.  The header includes
 /// space for:
 /// - a single byte indicating header length
@@ -116,9 +132,14 @@ impl AesCtrHmac {
     }
 
     /// Return a key derived from the main key using` salt` and `aad` as parameters.
-    fn derive_key_material(&self, salt: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+    fn derive_key_material(
+        &self,
+        salt: &[u8],
+        aad: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, TinkError> {
         let key_material_size = self.key_size_in_bytes + AES_CTR_HMAC_KEY_SIZE_IN_BYTES;
         tink::subtle::compute_hkdf(self.hkdf_alg, &self.main_key, salt, aad, key_material_size)
+            .map(Zeroizing::new)
     }
 }
 
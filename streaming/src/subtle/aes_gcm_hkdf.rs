@@ -20,6 +20,7 @@ use super::noncebased;
 use aes_gcm::aead::{generic_array::GenericArray, Aead, NewAead};
 use std::convert::TryInto;
 use tink::{proto::HashType, subtle::random::get_random_bytes, utils::wrap_err, TinkError};
+use zeroize::{Zeroize, Zeroizing};
 
 /// The size of the nonces used for GCM.
 pub const AES_GCM_HKDF_NONCE_SIZE_IN_BYTES: usize = 12;
@@ -45,6 +46,12 @@ pub struct AesGcmHkdf {
     plaintext_segment_size: usize,
 }
 
+impl Drop for AesGcmHkdf {
+    fn drop(&mut self) {
+        self.main_key.zeroize();
+    }
+}
+
 #[derive(Clone)]
 enum AesGcmKeyVariant {
     Aes128(Box<aes_gcm::Aes128Gcm>),
@@ -104,7 +111,7 @@ impl AesGcmHkdf {
     }
 
     /// Return a key derived from the given main key using `salt` and `aad` parameters.
-    fn derive_key(&self, salt: &[u8], aad: &[u8]) -> Result<Vec<u8>, TinkError> {
+    fn derive_key(&self, salt: &[u8], aad: &[u8]) -> Result<Zeroizing<Vec<u8>>, TinkError> {
         tink::subtle::compute_hkdf(
             self.hkdf_alg,
             &self.main_key,
@@ -112,6 +119,7 @@ impl AesGcmHkdf {
             aad,
             self.key_size_in_bytes,
         )
+        .map(Zeroizing::new)
     }
 }
 
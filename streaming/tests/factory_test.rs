@@ -14,6 +14,7 @@
 //
 ////////////////////////////////////////////////////////////////////////////////
 
+use std::io::{Read, Write};
 use tink::{utils::wrap_err, TinkError};
 
 mod common;
@@ -147,3 +148,56 @@ fn test_key_rotation() {
     assert!(validate_factory_cipher(a3.box_clone(), a3.box_clone()).is_ok());
     assert!(validate_factory_cipher(a3.box_clone(), a4.box_clone()).is_ok());
 }
+
+#[test]
+fn test_factory_monitoring() {
+    tink_streaming_aead::init();
+    let client = tink::monitoring::InMemoryClient::new();
+    tink::monitoring::register_client(std::sync::Arc::new(client.clone()));
+
+    let keyset = tink_testutil::new_test_aes_gcm_hkdf_keyset();
+    let primary_id = keyset.primary_key_id;
+    let mut annotations = std::collections::HashMap::new();
+    annotations.insert("service".to_string(), "streaming".to_string());
+    let keyset_handle = tink::keyset::insecure::new_handle(keyset)
+        .unwrap()
+        .with_annotations(annotations);
+    let a = tink_streaming_aead::new(&keyset_handle).unwrap();
+
+    let pt = vec![0x42u8; 5000];
+    let buf = tink_testutil::SharedBuf::new();
+    let mut w = a
+        .new_encrypting_writer(Box::new(buf.clone()), b"aad")
+        .unwrap();
+    w.write_all(&pt[..1000]).unwrap();
+    w.write_all(&pt[1000..]).unwrap();
+    w.close().unwrap();
+    let ct = buf.contents();
+    // A single event is logged for the stream, however many writes it took.
+    assert_eq!(client.key_count(primary_id), 1);
+    assert_eq!(client.key_bytes(primary_id), pt.len());
+    assert!(client
+        .events()
+        .iter()
+        .all(|e| e.primitive == "streamingaead" && e.api_function == "encrypt"));
+
+    client.clear();
+    let mut r = a
+        .new_decrypting_reader(Box::new(std::io::Cursor::new(ct.clone())), b"aad")
+        .unwrap();
+    let mut decrypted = vec![];
+    r.read_to_end(&mut decrypted).unwrap();
+    assert_eq!(decrypted, pt);
+    assert_eq!(client.key_count(primary_id), 1);
+    assert_eq!(client.key_bytes(primary_id), pt.len());
+    assert_eq!(client.failure_count(), 0);
+    // Reading again after the end of the stream logs nothing further.
+    let _ = r.read(&mut [0; 10]);
+    assert_eq!(client.key_count(primary_id), 1);
+
+    let mut r = a
+        .new_decrypting_reader(Box::new(std::io::Cursor::new(ct)), b"wrong aad")
+        .unwrap();
+    assert!(r.read_to_end(&mut vec![]).is_err());
+    assert_eq!(client.failure_count(), 1);
+}
@@ -33,14 +33,10 @@ impl proto::prf_set_server::PrfSet for PrfSetServerImpl {
             let mut reader = tink::keyset::BinaryReader::new(cursor);
             let handle = tink::keyset::insecure::read(&mut reader)?;
             let primitive = tink_prf::Set::new(&handle)?;
-            let mut output = proto::prf_set_key_ids_response::Output {
+            Ok(proto::prf_set_key_ids_response::Output {
                 primary_key_id: primitive.primary_id,
-                key_id: Vec::new(),
-            };
-            for key_id in primitive.prfs.keys() {
-                output.key_id.push(*key_id);
-            }
-            Ok(output)
+                key_id: primitive.key_ids(),
+            })
         };
         Ok(tonic::Response::new(proto::PrfSetKeyIdsResponse {
             result: Some(match closure() {
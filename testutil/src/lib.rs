@@ -70,6 +70,10 @@ impl tink::registry::KeyManager for DummyAeadKeyManager {
         tink::proto::key_data::KeyMaterialType::Symmetric
     }
 
+    fn primitive_name(&self) -> &'static str {
+        "Aead"
+    }
+
     fn new_key_data(&self, _serialized_key_format: &[u8]) -> Result<KeyData, TinkError> {
         Err("not implemented".into())
     }
@@ -314,6 +318,7 @@ pub fn new_random_ecdsa_public_key(
 ) -> tink::proto::EcdsaPublicKey {
     new_random_ecdsa_private_key(hash_type, curve)
         .public_key
+        .take()
         .unwrap()
 }
 
@@ -361,7 +366,7 @@ pub fn new_ed25519_private_key_data() -> KeyData {
 /// Create an [`Ed25519PublicKey`](tink::proto::Ed25519PublicKey) with randomly generated key
 /// material.
 pub fn new_ed25519_public_key() -> tink::proto::Ed25519PublicKey {
-    new_ed25519_private_key().public_key.unwrap()
+    new_ed25519_private_key().public_key.take().unwrap()
 }
 
 /// Create a [`KeyData`] containing a randomly generated [`AesSivKey`](tink::proto::AesSivKey).
@@ -15,8 +15,143 @@
 
 use std::path::{Path, PathBuf};
 
+/// Messages that hold secret key material, and so are zeroized on drop and have their `bytes`
+/// fields redacted in `Debug` output.  Each of these needs a `Debug` implementation in
+/// `src/proto/debug.rs`.
+const SECRET_MESSAGES: &[&str] = &[
+    "AeadWithNonceKey",
+    "AesCmacKey",
+    "AesCmacPrfKey",
+    "AesCtrHmacAeadKey",
+    "AesCtrHmacStreamingKey",
+    "AesCtrKey",
+    "AesEaxKey",
+    "AesGcmCommittingKey",
+    "AesGcmHkdfStreamingKey",
+    "AesGcmKey",
+    "AesGcmSivKey",
+    "AesSivKey",
+    "ChaCha20Poly1305Key",
+    "EciesAeadHkdfPrivateKey",
+    "EcdsaPrivateKey",
+    "Ed25519PrivateKey",
+    "HkdfPrfKey",
+    "HmacKey",
+    "HmacPrfKey",
+    "JwtHmacKey",
+    "KeyData",
+    "Keyset",
+    "PrfBasedDeriverKey",
+    "RsaSsaPkcs1PrivateKey",
+    "RsaSsaPssPrivateKey",
+    "XAesGcmKey",
+    "XChaCha20Poly1305Key",
+];
+
+/// Derive `Zeroize` for all generated types (so that messages holding secrets can zeroize their
+/// nested messages), and zeroize secret-bearing messages on drop.
+fn secret_message_config(config: &mut prost_build::Config) -> &mut prost_build::Config {
+    config.type_attribute(".", "#[derive(zeroize::Zeroize)]");
+    for msg in SECRET_MESSAGES {
+        config.type_attribute(msg, "#[zeroize(drop)]");
+    }
+    config
+}
+
+/// Rewrite a generated file so that secret-bearing messages do not derive `prost::Message`,
+/// which (in `prost-derive` 0.6) always includes a `Debug` implementation that shows the key
+/// material.  The derive and its `#[prost(..)]` field attributes are removed, and replaced by a
+/// `secret_message!` invocation (from `src/proto/secret.rs`) that implements `Message` without
+/// `Debug`, leaving the redacting `Debug` implementations in `src/proto/debug.rs` to fill the gap.
+fn replace_secret_message_derives(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    const DERIVE: &str = "#[derive(Clone, PartialEq, ::prost::Message)]";
+    let code = std::fs::read_to_string(path)?;
+    let mut out: Vec<String> = Vec::new();
+    let mut derive_line = None;
+    // Name, indentation and `(tag, field, kind)` list for the secret message being processed.
+    let mut current: Option<(String, String, Vec<SecretField>)> = None;
+    let mut field_attr: Option<String> = None;
+    for line in code.lines() {
+        let trimmed = line.trim_start();
+        let indent = &line[..line.len() - trimmed.len()];
+        if let Some((name, struct_indent, fields)) = &mut current {
+            if let Some(attr) = trimmed
+                .strip_prefix("#[prost(")
+                .and_then(|attr| attr.strip_suffix(")]"))
+            {
+                field_attr = Some(attr.to_string());
+                continue;
+            }
+            if let (Some(field), Some(attr)) = (trimmed.strip_prefix("pub "), &field_attr) {
+                let field = field.split(':').next().unwrap_or_default().to_string();
+                let (tag, kind) = secret_field_spec(&field, attr)?;
+                fields.push((tag, field, kind));
+                field_attr = None;
+            } else if trimmed == "}" && indent == struct_indent {
+                fields.sort();
+                out.push(line.to_string());
+                out.push(format!("{}secret_message! {{", indent));
+                out.push(format!("{}    {} {{", indent, name));
+                for (tag, field, kind) in fields.iter() {
+                    out.push(format!("{}        {}: {} = {},", indent, field, kind, tag));
+                }
+                out.push(format!("{}    }}", indent));
+                out.push(format!("{}}}", indent));
+                current = None;
+                continue;
+            }
+        } else if trimmed == DERIVE {
+            derive_line = Some(out.len());
+        } else if let Some(name) = trimmed
+            .strip_prefix("pub struct ")
+            .and_then(|rest| rest.strip_suffix(" {"))
+        {
+            if SECRET_MESSAGES.contains(&name) {
+                let idx = derive_line.ok_or_else(|| format!("no derive found for {}", name))?;
+                out[idx] = format!("{}#[derive(Clone, PartialEq)]", indent);
+                current = Some((name.to_string(), indent.to_string(), Vec::new()));
+            }
+            derive_line = None;
+        }
+        out.push(line.to_string());
+    }
+    out.push(String::new());
+    std::fs::write(path, out.join("\n"))?;
+    Ok(())
+}
+
+/// Tag, name and `secret_message!` kind of a field in a secret-bearing message.
+type SecretField = (u32, String, String);
+
+/// Convert the contents of a `#[prost(..)]` field attribute into the tag and field kind
+/// understood by `secret_message!`.
+fn secret_field_spec(field: &str, attr: &str) -> Result<(u32, String), Box<dyn std::error::Error>> {
+    let parts: Vec<&str> = attr.split(", ").collect();
+    let tag = parts
+        .iter()
+        .find_map(|part| part.strip_prefix("tag=\""))
+        .and_then(|tag| tag.strip_suffix('"'))
+        .ok_or_else(|| format!("no tag for field {}", field))?
+        .parse()?;
+    let kind = match &parts[..parts.len() - 1] {
+        ["bytes"] | ["string"] | ["uint32"] => parts[0].to_string(),
+        ["message", "optional"] => "message".to_string(),
+        ["message", "repeated"] => "repeated_message".to_string(),
+        [enumeration] if enumeration.starts_with("enumeration=") => format!(
+            "enumeration({}, set_{})",
+            enumeration
+                .trim_start_matches("enumeration=")
+                .trim_matches('"'),
+            field
+        ),
+        _ => return Err(format!("unsupported field {}: {}", field, attr).into()),
+    };
+    Ok((tag, kind))
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let source_files = [
+        "aead_with_nonce.proto",
         "aes_cmac.proto",
         "aes_cmac_prf.proto",
         "aes_ctr.proto",
@@ -24,6 +159,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "aes_ctr_hmac_streaming.proto",
         "aes_eax.proto",
         "aes_gcm.proto",
+        "aes_gcm_committing.proto",
         "aes_gcm_hkdf_streaming.proto",
         "aes_gcm_siv.proto",
         "aes_siv.proto",
@@ -44,6 +180,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "rsa_ssa_pkcs1.proto",
         "rsa_ssa_pss.proto",
         "tink.proto",
+        "x_aes_gcm.proto",
         "xchacha20_poly1305.proto",
     ];
     let proto_path = Path::new("../proto").to_path_buf();
@@ -55,13 +192,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("cargo:rerun-if-changed={}", proto_file.display());
     }
 
-    prost_build::Config::new()
+    secret_message_config(&mut prost_build::Config::new())
         // Emit generated code into the source directory, so it can be checked in.
         .out_dir("src/codegen")
         .compile_protos(&proto_files, &[PathBuf::from("..")])?;
 
     // Separate variant with serde-related annotations
-    prost_build::Config::new()
+    secret_message_config(&mut prost_build::Config::new())
         // Emit generated code into the source directory, so it can be checked in.
         .out_dir("src/codegen/serde")
         // Set up serde-json options for Keyset-related messages
@@ -83,6 +220,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .type_attribute("KeyInfo", "#[serde(rename_all = \"camelCase\")]")
         .type_attribute("KeyData", "#[derive(serde::Deserialize, serde::Serialize)]")
         .type_attribute("KeyData", "#[serde(rename_all = \"camelCase\")]")
+        .type_attribute(
+            "RegistryConfig",
+            "#[derive(serde::Deserialize, serde::Serialize)]",
+        )
+        .type_attribute(
+            "RegistryConfig",
+            "#[serde(rename_all = \"camelCase\", default)]",
+        )
+        .type_attribute(
+            "KeyTypeEntry",
+            "#[derive(serde::Deserialize, serde::Serialize)]",
+        )
+        .type_attribute(
+            "KeyTypeEntry",
+            "#[serde(rename_all = \"camelCase\", default)]",
+        )
         // Set up serde-json options for fields that need special parsing
         .field_attribute(
             "Key.status",
@@ -111,5 +264,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         )
         .compile_protos(&proto_files, &[PathBuf::from("..")])?;
 
+    for out_dir in &["src/codegen", "src/codegen/serde"] {
+        for file in &["google.crypto.tink.rs", "tink_rust.rs"] {
+            replace_secret_message_derives(&Path::new(out_dir).join(file))?;
+        }
+    }
+
     Ok(())
 }
@@ -21,7 +21,7 @@
 /// Implementations of this trait are secure against adaptive chosen ciphertext attacks.
 /// Encryption with additional data ensures authenticity and integrity of that data, but not
 /// its secrecy (see [RFC 5116](https://tools.ietf.org/html/rfc5116)).
-pub trait Aead: AeadBoxClone {
+pub trait Aead: AeadBoxClone + Send + Sync {
     // Encrypt plaintext with `additional_data` as additional
     // authenticated data. The resulting ciphertext allows for checking
     // authenticity and integrity of additional data `additional_data`,
@@ -40,6 +40,83 @@ pub trait Aead: AeadBoxClone {
         ciphertext: &[u8],
         additional_data: &[u8],
     ) -> Result<Vec<u8>, crate::TinkError>;
+
+    /// Encrypt the plaintext held in `buffer` with `additional_data` as additional authenticated
+    /// data, replacing it with the ciphertext.  Implementations that support this natively only
+    /// allocate if `buffer` lacks the capacity for the ciphertext overhead.  If encryption fails,
+    /// `buffer` is left unchanged.
+    ///
+    /// The default implementation uses
+    /// [`encrypt_in_place_with_prefix`](Aead::encrypt_in_place_with_prefix).
+    fn encrypt_in_place(
+        &self,
+        buffer: &mut Vec<u8>,
+        additional_data: &[u8],
+    ) -> Result<(), crate::TinkError> {
+        self.encrypt_in_place_with_prefix(&[], buffer, additional_data)
+    }
+
+    /// Encrypt the plaintext held in `buffer` as for [`encrypt_in_place`](Aead::encrypt_in_place),
+    /// replacing it with `prefix || ciphertext`.  This allows a caller that adds its own header
+    /// to the ciphertext to do so without moving the ciphertext a second time.  If encryption
+    /// fails, `buffer` is left unchanged.
+    ///
+    /// The default implementation uses [`encrypt`](Aead::encrypt).
+    fn encrypt_in_place_with_prefix(
+        &self,
+        prefix: &[u8],
+        buffer: &mut Vec<u8>,
+        additional_data: &[u8],
+    ) -> Result<(), crate::TinkError> {
+        let ct = self.encrypt(buffer, additional_data)?;
+        buffer.clear();
+        buffer.reserve(prefix.len() + ct.len());
+        buffer.extend_from_slice(prefix);
+        buffer.extend_from_slice(&ct);
+        Ok(())
+    }
+
+    /// Decrypt the ciphertext held in `buffer` with `additional_data` as additional authenticated
+    /// data, replacing it with the plaintext.  If decryption fails, `buffer` is left unchanged.
+    ///
+    /// The default implementation uses [`decrypt`](Aead::decrypt).
+    fn decrypt_in_place(
+        &self,
+        buffer: &mut Vec<u8>,
+        additional_data: &[u8],
+    ) -> Result<(), crate::TinkError> {
+        *buffer = self.decrypt(buffer, additional_data)?;
+        Ok(())
+    }
+
+    /// Encrypt `plaintext` with `additional_data` as additional authenticated data, appending the
+    /// ciphertext to `out` (so that a caller-provided buffer can be reused across messages).  If
+    /// encryption fails, `out` is left unchanged.
+    ///
+    /// The default implementation uses [`encrypt`](Aead::encrypt).
+    fn encrypt_to(
+        &self,
+        plaintext: &[u8],
+        additional_data: &[u8],
+        out: &mut Vec<u8>,
+    ) -> Result<(), crate::TinkError> {
+        out.extend_from_slice(&self.encrypt(plaintext, additional_data)?);
+        Ok(())
+    }
+
+    /// Decrypt `ciphertext` with `additional_data` as additional authenticated data, appending the
+    /// plaintext to `out`.  If decryption fails, `out` is left unchanged.
+    ///
+    /// The default implementation uses [`decrypt`](Aead::decrypt).
+    fn decrypt_to(
+        &self,
+        ciphertext: &[u8],
+        additional_data: &[u8],
+        out: &mut Vec<u8>,
+    ) -> Result<(), crate::TinkError> {
+        out.extend_from_slice(&self.decrypt(ciphertext, additional_data)?);
+        Ok(())
+    }
 }
 
 /// Trait bound to indicate that primitive trait objects should support cloning
@@ -1,19 +1,27 @@
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesCmacParams {
     #[prost(uint32, tag="1")]
     pub tag_size: u32,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.AesCmacKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct AesCmacKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(bytes, tag="2")]
     pub key_value: std::vec::Vec<u8>,
-    #[prost(message, optional, tag="3")]
     pub params: ::std::option::Option<AesCmacParams>,
 }
+secret_message! {
+    AesCmacKey {
+        version: uint32 = 1,
+        key_value: bytes = 2,
+        params: message = 3,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesCmacKeyFormat {
     #[prost(uint32, tag="1")]
     pub key_size: u32,
@@ -21,14 +29,21 @@ pub struct AesCmacKeyFormat {
     pub params: ::std::option::Option<AesCmacParams>,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.AesCmacPrfKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct AesCmacPrfKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(bytes, tag="2")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    AesCmacPrfKey {
+        version: uint32 = 1,
+        key_value: bytes = 2,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesCmacPrfKeyFormat {
     #[prost(uint32, tag="2")]
     pub version: u32,
@@ -36,11 +51,13 @@ pub struct AesCmacPrfKeyFormat {
     pub key_size: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesCtrParams {
     #[prost(uint32, tag="1")]
     pub iv_size: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesCtrKeyFormat {
     #[prost(message, optional, tag="1")]
     pub params: ::std::option::Option<AesCtrParams>,
@@ -48,17 +65,24 @@ pub struct AesCtrKeyFormat {
     pub key_size: u32,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.AesCtrKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct AesCtrKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(message, optional, tag="2")]
     pub params: ::std::option::Option<AesCtrParams>,
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    AesCtrKey {
+        version: uint32 = 1,
+        params: message = 2,
+        key_value: bytes = 3,
+    }
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+#[derive(zeroize::Zeroize)]
 pub enum EllipticCurveType {
     UnknownCurve = 0,
     NistP256 = 2,
@@ -68,6 +92,7 @@ pub enum EllipticCurveType {
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+#[derive(zeroize::Zeroize)]
 pub enum EcPointFormat {
     UnknownFormat = 0,
     Uncompressed = 1,
@@ -78,6 +103,7 @@ pub enum EcPointFormat {
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+#[derive(zeroize::Zeroize)]
 pub enum HashType {
     UnknownHash = 0,
     /// Using SHA1 for digital signature is deprecated but HMAC-SHA1 is
@@ -88,6 +114,7 @@ pub enum HashType {
     Sha512 = 4,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct HmacParams {
     /// HashType is an enum.
     #[prost(enumeration="HashType", tag="1")]
@@ -96,16 +123,23 @@ pub struct HmacParams {
     pub tag_size: u32,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.HmacKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct HmacKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(message, optional, tag="2")]
     pub params: ::std::option::Option<HmacParams>,
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    HmacKey {
+        version: uint32 = 1,
+        params: message = 2,
+        key_value: bytes = 3,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct HmacKeyFormat {
     #[prost(message, optional, tag="1")]
     pub params: ::std::option::Option<HmacParams>,
@@ -115,6 +149,7 @@ pub struct HmacKeyFormat {
     pub version: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesCtrHmacAeadKeyFormat {
     #[prost(message, optional, tag="1")]
     pub aes_ctr_key_format: ::std::option::Option<AesCtrKeyFormat>,
@@ -122,16 +157,23 @@ pub struct AesCtrHmacAeadKeyFormat {
     pub hmac_key_format: ::std::option::Option<HmacKeyFormat>,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.AesCtrHmacAeadKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct AesCtrHmacAeadKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(message, optional, tag="2")]
     pub aes_ctr_key: ::std::option::Option<AesCtrKey>,
-    #[prost(message, optional, tag="3")]
     pub hmac_key: ::std::option::Option<HmacKey>,
 }
+secret_message! {
+    AesCtrHmacAeadKey {
+        version: uint32 = 1,
+        aes_ctr_key: message = 2,
+        hmac_key: message = 3,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesCtrHmacStreamingParams {
     #[prost(uint32, tag="1")]
     pub ciphertext_segment_size: u32,
@@ -146,6 +188,7 @@ pub struct AesCtrHmacStreamingParams {
     pub hmac_params: ::std::option::Option<HmacParams>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesCtrHmacStreamingKeyFormat {
     #[prost(uint32, tag="3")]
     pub version: u32,
@@ -156,24 +199,32 @@ pub struct AesCtrHmacStreamingKeyFormat {
     pub key_size: u32,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.AesCtrHmacStreamingKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct AesCtrHmacStreamingKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(message, optional, tag="2")]
     pub params: ::std::option::Option<AesCtrHmacStreamingParams>,
     /// the main key, aka. "ikm", input key material
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    AesCtrHmacStreamingKey {
+        version: uint32 = 1,
+        params: message = 2,
+        key_value: bytes = 3,
+    }
+}
 /// only allowing tag size in bytes = 16
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesEaxParams {
     /// possible value is 12 or 16 bytes.
     #[prost(uint32, tag="1")]
     pub iv_size: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesEaxKeyFormat {
     #[prost(message, optional, tag="1")]
     pub params: ::std::option::Option<AesEaxParams>,
@@ -181,18 +232,25 @@ pub struct AesEaxKeyFormat {
     pub key_size: u32,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.AesEaxKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct AesEaxKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(message, optional, tag="2")]
     pub params: ::std::option::Option<AesEaxParams>,
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    AesEaxKey {
+        version: uint32 = 1,
+        params: message = 2,
+        key_value: bytes = 3,
+    }
+}
 /// only allowing IV size in bytes = 12 and tag size in bytes = 16
 /// Thus, accept no params.
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesGcmKeyFormat {
     #[prost(uint32, tag="2")]
     pub key_size: u32,
@@ -200,14 +258,21 @@ pub struct AesGcmKeyFormat {
     pub version: u32,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.AesGcmKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct AesGcmKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    AesGcmKey {
+        version: uint32 = 1,
+        key_value: bytes = 3,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesGcmHkdfStreamingParams {
     #[prost(uint32, tag="1")]
     pub ciphertext_segment_size: u32,
@@ -218,6 +283,7 @@ pub struct AesGcmHkdfStreamingParams {
     pub hkdf_hash_type: i32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesGcmHkdfStreamingKeyFormat {
     #[prost(uint32, tag="3")]
     pub version: u32,
@@ -228,18 +294,25 @@ pub struct AesGcmHkdfStreamingKeyFormat {
     pub key_size: u32,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.AesGcmHkdfStreamingKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct AesGcmHkdfStreamingKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(message, optional, tag="2")]
     pub params: ::std::option::Option<AesGcmHkdfStreamingParams>,
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    AesGcmHkdfStreamingKey {
+        version: uint32 = 1,
+        params: message = 2,
+        key_value: bytes = 3,
+    }
+}
 /// The only allowed IV size is 12 bytes and tag size is 16 bytes.
 /// Thus, accept no params.
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesGcmSivKeyFormat {
     #[prost(uint32, tag="2")]
     pub key_size: u32,
@@ -247,45 +320,66 @@ pub struct AesGcmSivKeyFormat {
     pub version: u32,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.AesGcmSivKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct AesGcmSivKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    AesGcmSivKey {
+        version: uint32 = 1,
+        key_value: bytes = 3,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct AesSivKeyFormat {
     /// Only valid value is: 64.
     #[prost(uint32, tag="1")]
     pub key_size: u32,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.AesSivKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct AesSivKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
     /// First half is AES-CTR key, second is AES-SIV.
-    #[prost(bytes, tag="2")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    AesSivKey {
+        version: uint32 = 1,
+        key_value: bytes = 2,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct ChaCha20Poly1305KeyFormat {
 }
 /// key_type: type.googleapis.com/google.crypto.tink.ChaCha20Poly1305.
 /// This key type actually implements ChaCha20Poly1305 as described
 /// at https://tools.ietf.org/html/rfc7539#section-2.8.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct ChaCha20Poly1305Key {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(bytes, tag="2")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    ChaCha20Poly1305Key {
+        version: uint32 = 1,
+        key_value: bytes = 2,
+    }
+}
 /// An entry that describes a key type to be used with Tink library,
 /// specifying the corresponding primitive, key manager, and deprecation status.
 /// All fields are required.
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct KeyTypeEntry {
     /// E.g. “Aead”, “Mac”, ... (case-insensitive)
     #[prost(string, tag="1")]
@@ -307,6 +401,7 @@ pub struct KeyTypeEntry {
 /// to be available via the Registry after initialization.
 /// All fields are required.
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct RegistryConfig {
     #[prost(string, tag="1")]
     pub config_name: std::string::String,
@@ -315,6 +410,7 @@ pub struct RegistryConfig {
 }
 /// Protos for Ecdsa.
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct EcdsaParams {
     /// Required.
     #[prost(enumeration="HashType", tag="1")]
@@ -328,6 +424,7 @@ pub struct EcdsaParams {
 }
 /// key_type: type.googleapis.com/google.crypto.tink.EcdsaPublicKey
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct EcdsaPublicKey {
     /// Required.
     #[prost(uint32, tag="1")]
@@ -347,20 +444,27 @@ pub struct EcdsaPublicKey {
     pub y: std::vec::Vec<u8>,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.EcdsaPrivateKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct EcdsaPrivateKey {
     /// Required.
-    #[prost(uint32, tag="1")]
     pub version: u32,
     /// Required.
-    #[prost(message, optional, tag="2")]
     pub public_key: ::std::option::Option<EcdsaPublicKey>,
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    EcdsaPrivateKey {
+        version: uint32 = 1,
+        public_key: message = 2,
+        key_value: bytes = 3,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct EcdsaKeyFormat {
     /// Required.
     #[prost(message, optional, tag="2")]
@@ -368,6 +472,7 @@ pub struct EcdsaKeyFormat {
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+#[derive(zeroize::Zeroize)]
 pub enum EcdsaSignatureEncoding {
     UnknownEncoding = 0,
     /// The signature's format is r || s, where r and s are zero-padded and have
@@ -408,6 +513,7 @@ pub enum EcdsaSignatureEncoding {
 // to the name of the *Key-proto from type_url-field suffixed with "Format".
 
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct KeyTemplate {
     /// Required.
     ///
@@ -444,25 +550,32 @@ pub struct KeyTemplate {
 /// to this serialized proto contains also type_url identifying the
 /// definition of *Key-proto (as in KeyFormat-message), and some extra metadata
 /// about the type key material.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct KeyData {
     /// Required.
     ///
     /// In format type.googleapis.com/packagename.messagename
-    #[prost(string, tag="1")]
     pub type_url: std::string::String,
     /// Required.
     ///
     /// contains specific serialized *Key proto
-    #[prost(bytes, tag="2")]
     pub value: std::vec::Vec<u8>,
     /// Required.
-    #[prost(enumeration="key_data::KeyMaterialType", tag="3")]
     pub key_material_type: i32,
 }
+secret_message! {
+    KeyData {
+        type_url: string = 1,
+        value: bytes = 2,
+        key_material_type: enumeration(key_data::KeyMaterialType, set_key_material_type) = 3,
+    }
+}
 pub mod key_data {
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
     #[repr(i32)]
+    #[derive(zeroize::Zeroize)]
     pub enum KeyMaterialType {
         UnknownKeymaterial = 0,
         Symmetric = 1,
@@ -476,19 +589,26 @@ pub mod key_data {
 /// to enable key rotation.  The keys in a keyset can belong to different
 /// implementations/key types, but must all implement the same primitive.
 /// Any given keyset (and any given key) can be used for one primitive only.
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct Keyset {
     /// Identifies key used to generate new crypto data (encrypt, sign).
     /// Required.
-    #[prost(uint32, tag="1")]
     pub primary_key_id: u32,
     /// Actual keys in the Keyset.
     /// Required.
-    #[prost(message, repeated, tag="2")]
     pub key: ::std::vec::Vec<keyset::Key>,
 }
+secret_message! {
+    Keyset {
+        primary_key_id: uint32 = 1,
+        key: repeated_message = 2,
+    }
+}
 pub mod keyset {
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[derive(zeroize::Zeroize)]
     pub struct Key {
         /// Contains the actual, instantiation specific key proto.
         /// By convention, each key proto contains a version field.
@@ -510,6 +630,7 @@ pub mod keyset {
 /// thus can be used for logging or monitoring. Most fields are copied from
 /// Keyset.
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct KeysetInfo {
     /// See Keyset.primary_key_id.
     #[prost(uint32, tag="1")]
@@ -521,6 +642,7 @@ pub struct KeysetInfo {
 }
 pub mod keyset_info {
     #[derive(Clone, PartialEq, ::prost::Message)]
+    #[derive(zeroize::Zeroize)]
     pub struct KeyInfo {
         /// the type url of this key,
         /// e.g., type.googleapis.com/google.crypto.tink.HmacKey.
@@ -539,6 +661,7 @@ pub mod keyset_info {
 }
 /// Represents a keyset that is encrypted with a master key.
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct EncryptedKeyset {
     /// Required.
     #[prost(bytes, tag="2")]
@@ -549,6 +672,7 @@ pub struct EncryptedKeyset {
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+#[derive(zeroize::Zeroize)]
 pub enum KeyStatusType {
     UnknownStatus = 0,
     /// Can be used for crypto operations.
@@ -571,6 +695,7 @@ pub enum KeyStatusType {
 ///   - Raw   : prefix is 0 byte, i.e., empty.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+#[derive(zeroize::Zeroize)]
 pub enum OutputPrefixType {
     UnknownPrefix = 0,
     Tink = 1,
@@ -601,6 +726,7 @@ pub enum OutputPrefixType {
 
 /// Parameters of KEM (Key Encapsulation Mechanism)
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct EciesHkdfKemParams {
     /// Required.
     #[prost(enumeration="EllipticCurveType", tag="1")]
@@ -614,6 +740,7 @@ pub struct EciesHkdfKemParams {
 }
 /// Parameters of AEAD DEM (Data Encapsulation Mechanism).
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct EciesAeadDemParams {
     /// Required.
     ///
@@ -622,6 +749,7 @@ pub struct EciesAeadDemParams {
     pub aead_dem: ::std::option::Option<KeyTemplate>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct EciesAeadHkdfParams {
     /// Key Encapsulation Mechanism.
     /// Required.
@@ -639,6 +767,7 @@ pub struct EciesAeadHkdfParams {
 /// EciesAeadHkdfPublicKey represents HybridEncryption primitive.
 /// key_type: type.googleapis.com/google.crypto.tink.EciesAeadHkdfPublicKey
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct EciesAeadHkdfPublicKey {
     /// Required.
     #[prost(uint32, tag="1")]
@@ -657,31 +786,40 @@ pub struct EciesAeadHkdfPublicKey {
 }
 /// EciesKdfAeadPrivateKey represents HybridDecryption primitive.
 /// key_type: type.googleapis.com/google.crypto.tink.EciesAeadHkdfPrivateKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct EciesAeadHkdfPrivateKey {
     /// Required.
-    #[prost(uint32, tag="1")]
     pub version: u32,
     /// Required.
-    #[prost(message, optional, tag="2")]
     pub public_key: ::std::option::Option<EciesAeadHkdfPublicKey>,
     /// Required.
     ///
     /// Big integer in bigendian representation.
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    EciesAeadHkdfPrivateKey {
+        version: uint32 = 1,
+        public_key: message = 2,
+        key_value: bytes = 3,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct EciesAeadHkdfKeyFormat {
     /// Required.
     #[prost(message, optional, tag="1")]
     pub params: ::std::option::Option<EciesAeadHkdfParams>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct Ed25519KeyFormat {
 }
 /// key_type: type.googleapis.com/google.crypto.tink.Ed25519PublicKey
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct Ed25519PublicKey {
     /// Required.
     #[prost(uint32, tag="1")]
@@ -693,24 +831,32 @@ pub struct Ed25519PublicKey {
     pub key_value: std::vec::Vec<u8>,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.Ed25519PrivateKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct Ed25519PrivateKey {
     /// Required.
-    #[prost(uint32, tag="1")]
     pub version: u32,
     /// The private key is 32 bytes of cryptographically secure random data.
     /// See https://tools.ietf.org/html/rfc8032#section-5.1.5.
     /// Required.
-    #[prost(bytes, tag="2")]
     pub key_value: std::vec::Vec<u8>,
     /// The corresponding public key.
-    #[prost(message, optional, tag="3")]
     pub public_key: ::std::option::Option<Ed25519PublicKey>,
 }
+secret_message! {
+    Ed25519PrivateKey {
+        version: uint32 = 1,
+        key_value: bytes = 2,
+        public_key: message = 3,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct Empty {
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct HkdfPrfParams {
     #[prost(enumeration="HashType", tag="1")]
     pub hash: i32,
@@ -719,16 +865,23 @@ pub struct HkdfPrfParams {
     #[prost(bytes, tag="2")]
     pub salt: std::vec::Vec<u8>,
 }
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct HkdfPrfKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(message, optional, tag="2")]
     pub params: ::std::option::Option<HkdfPrfParams>,
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    HkdfPrfKey {
+        version: uint32 = 1,
+        params: message = 2,
+        key_value: bytes = 3,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct HkdfPrfKeyFormat {
     #[prost(message, optional, tag="1")]
     pub params: ::std::option::Option<HkdfPrfParams>,
@@ -738,22 +891,30 @@ pub struct HkdfPrfKeyFormat {
     pub version: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct HmacPrfParams {
     /// HashType is an enum.
     #[prost(enumeration="HashType", tag="1")]
     pub hash: i32,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.HmacPrfKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct HmacPrfKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(message, optional, tag="2")]
     pub params: ::std::option::Option<HmacPrfParams>,
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    HmacPrfKey {
+        version: uint32 = 1,
+        params: message = 2,
+        key_value: bytes = 3,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct HmacPrfKeyFormat {
     #[prost(message, optional, tag="1")]
     pub params: ::std::option::Option<HmacPrfParams>,
@@ -763,16 +924,23 @@ pub struct HmacPrfKeyFormat {
     pub version: u32,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.JwtHmacKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct JwtHmacKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(enumeration="HashType", tag="2")]
     pub hash_type: i32,
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    JwtHmacKey {
+        version: uint32 = 1,
+        hash_type: enumeration(HashType, set_hash_type) = 2,
+        key_value: bytes = 3,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct JwtHmacKeyFormat {
     #[prost(uint32, tag="1")]
     pub version: u32,
@@ -782,6 +950,7 @@ pub struct JwtHmacKeyFormat {
     pub key_size: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct KmsAeadKeyFormat {
     /// Required.
     /// The location of a KMS key.
@@ -794,6 +963,7 @@ pub struct KmsAeadKeyFormat {
 }
 /// There is no actual key material in the key.
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct KmsAeadKey {
     #[prost(uint32, tag="1")]
     pub version: u32,
@@ -802,6 +972,7 @@ pub struct KmsAeadKey {
     pub params: ::std::option::Option<KmsAeadKeyFormat>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct KmsEnvelopeAeadKeyFormat {
     /// Required.
     /// The location of the KEK in a remote KMS.
@@ -818,6 +989,7 @@ pub struct KmsEnvelopeAeadKeyFormat {
 }
 /// There is no actual key material in the key.
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct KmsEnvelopeAeadKey {
     #[prost(uint32, tag="1")]
     pub version: u32,
@@ -826,11 +998,13 @@ pub struct KmsEnvelopeAeadKey {
     pub params: ::std::option::Option<KmsEnvelopeAeadKeyFormat>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct PrfBasedDeriverParams {
     #[prost(message, optional, tag="1")]
     pub derived_key_template: ::std::option::Option<KeyTemplate>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct PrfBasedDeriverKeyFormat {
     #[prost(message, optional, tag="1")]
     pub prf_key_template: ::std::option::Option<KeyTemplate>,
@@ -838,16 +1012,23 @@ pub struct PrfBasedDeriverKeyFormat {
     pub params: ::std::option::Option<PrfBasedDeriverParams>,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.PrfBasedDeriverKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct PrfBasedDeriverKey {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(message, optional, tag="2")]
     pub prf_key: ::std::option::Option<KeyData>,
-    #[prost(message, optional, tag="3")]
     pub params: ::std::option::Option<PrfBasedDeriverParams>,
 }
+secret_message! {
+    PrfBasedDeriverKey {
+        version: uint32 = 1,
+        prf_key: message = 2,
+        params: message = 3,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct RsaSsaPkcs1Params {
     /// Hash function used in computing hash of the signing message
     /// (see https://tools.ietf.org/html/rfc8017#section-9.2).
@@ -857,6 +1038,7 @@ pub struct RsaSsaPkcs1Params {
 }
 /// key_type: type.googleapis.com/google.crypto.tink.RsaSsaPkcs1PublicKey
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct RsaSsaPkcs1PublicKey {
     /// Required.
     #[prost(uint32, tag="1")]
@@ -874,47 +1056,54 @@ pub struct RsaSsaPkcs1PublicKey {
     pub e: std::vec::Vec<u8>,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.RsaSsaPkcs1PrivateKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct RsaSsaPkcs1PrivateKey {
     /// Required.
-    #[prost(uint32, tag="1")]
     pub version: u32,
     /// Required.
-    #[prost(message, optional, tag="2")]
     pub public_key: ::std::option::Option<RsaSsaPkcs1PublicKey>,
     /// Private exponent.
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="3")]
     pub d: std::vec::Vec<u8>,
     /// The following parameters are used to optimize RSA signature computation.
     /// The prime factor p of n.
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="4")]
     pub p: std::vec::Vec<u8>,
     /// The prime factor q of n.
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="5")]
     pub q: std::vec::Vec<u8>,
     /// d mod (p - 1).
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="6")]
     pub dp: std::vec::Vec<u8>,
     /// d mod (q - 1).
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="7")]
     pub dq: std::vec::Vec<u8>,
     /// Chinese Remainder Theorem coefficient q^(-1) mod p.
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="8")]
     pub crt: std::vec::Vec<u8>,
 }
+secret_message! {
+    RsaSsaPkcs1PrivateKey {
+        version: uint32 = 1,
+        public_key: message = 2,
+        d: bytes = 3,
+        p: bytes = 4,
+        q: bytes = 5,
+        dp: bytes = 6,
+        dq: bytes = 7,
+        crt: bytes = 8,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct RsaSsaPkcs1KeyFormat {
     /// Required.
     #[prost(message, optional, tag="1")]
@@ -927,6 +1116,7 @@ pub struct RsaSsaPkcs1KeyFormat {
     pub public_exponent: std::vec::Vec<u8>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct RsaSsaPssParams {
     /// Hash function used in computing hash of the signing message
     /// (see https://tools.ietf.org/html/rfc8017#section-9.1.1).
@@ -945,6 +1135,7 @@ pub struct RsaSsaPssParams {
 }
 /// key_type: type.googleapis.com/google.crypto.tink.RsaSsaPssPublicKey
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct RsaSsaPssPublicKey {
     /// Required.
     #[prost(uint32, tag="1")]
@@ -962,47 +1153,54 @@ pub struct RsaSsaPssPublicKey {
     pub e: std::vec::Vec<u8>,
 }
 /// key_type: type.googleapis.com/google.crypto.tink.RsaSsaPssPrivateKey
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct RsaSsaPssPrivateKey {
     /// Required.
-    #[prost(uint32, tag="1")]
     pub version: u32,
     /// Required.
-    #[prost(message, optional, tag="2")]
     pub public_key: ::std::option::Option<RsaSsaPssPublicKey>,
     /// Private exponent.
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="3")]
     pub d: std::vec::Vec<u8>,
     /// The following parameters are used to optimize RSA signature computation.
     /// The prime factor p of n.
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="4")]
     pub p: std::vec::Vec<u8>,
     /// The prime factor q of n.
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="5")]
     pub q: std::vec::Vec<u8>,
     /// d mod (p - 1).
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="6")]
     pub dp: std::vec::Vec<u8>,
     /// d mod (q - 1).
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="7")]
     pub dq: std::vec::Vec<u8>,
     /// Chinese Remainder Theorem coefficient q^(-1) mod p.
     /// Unsigned big integer in bigendian representation.
     /// Required.
-    #[prost(bytes, tag="8")]
     pub crt: std::vec::Vec<u8>,
 }
+secret_message! {
+    RsaSsaPssPrivateKey {
+        version: uint32 = 1,
+        public_key: message = 2,
+        d: bytes = 3,
+        p: bytes = 4,
+        q: bytes = 5,
+        dp: bytes = 6,
+        dq: bytes = 7,
+        crt: bytes = 8,
+    }
+}
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct RsaSsaPssKeyFormat {
     /// Required.
     #[prost(message, optional, tag="1")]
@@ -1014,14 +1212,57 @@ pub struct RsaSsaPssKeyFormat {
     #[prost(bytes, tag="3")]
     pub public_exponent: std::vec::Vec<u8>,
 }
+/// XAES-256-GCM (https://c2sp.org/XAES-256-GCM): the first salt_size bytes of
+/// each nonce are used to derive a per-message AES-256-GCM key from the
+/// 32-byte key value, and the remaining 12 bytes are used as the AES-GCM
+/// nonce.
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
+pub struct XAesGcmParams {
+    /// Size of the salt in bytes. Only 12 (for a 192-bit nonce) is currently
+    /// supported.
+    #[prost(uint32, tag="1")]
+    pub salt_size: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
+pub struct XAesGcmKeyFormat {
+    #[prost(uint32, tag="1")]
+    pub version: u32,
+    #[prost(message, optional, tag="3")]
+    pub params: ::std::option::Option<XAesGcmParams>,
+}
+/// key_type: type.googleapis.com/google.crypto.tink.XAesGcmKey
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
+pub struct XAesGcmKey {
+    pub version: u32,
+    pub params: ::std::option::Option<XAesGcmParams>,
+    pub key_value: std::vec::Vec<u8>,
+}
+secret_message! {
+    XAesGcmKey {
+        version: uint32 = 1,
+        params: message = 2,
+        key_value: bytes = 3,
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
 pub struct XChaCha20Poly1305KeyFormat {
 }
 /// key_type: type.googleapis.com/google.crypto.tink.XChaCha20Poly1305Key
-#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
 pub struct XChaCha20Poly1305Key {
-    #[prost(uint32, tag="1")]
     pub version: u32,
-    #[prost(bytes, tag="3")]
     pub key_value: std::vec::Vec<u8>,
 }
+secret_message! {
+    XChaCha20Poly1305Key {
+        version: uint32 = 1,
+        key_value: bytes = 3,
+    }
+}
@@ -0,0 +1,61 @@
+/// Key for an AEAD that is used with caller-supplied nonces. The key wraps a
+/// key of one of the AEAD key types that support this (AES-GCM, AES-GCM-SIV,
+/// ChaCha20-Poly1305, XChaCha20-Poly1305 and XAES-256-GCM), so that keysets
+/// for use with caller-supplied nonces cannot be confused with keysets for the
+/// corresponding AEAD, which always uses random nonces.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
+pub struct AeadWithNonceKeyFormat {
+    /// Type URL of the wrapped key type, e.g.
+    /// type.googleapis.com/google.crypto.tink.AesGcmKey.
+    #[prost(string, tag="1")]
+    pub aead_type_url: std::string::String,
+    /// Serialized key format of the wrapped key type.
+    #[prost(bytes, tag="2")]
+    pub aead_key_format: std::vec::Vec<u8>,
+}
+/// key_type: type.googleapis.com/tink_rust.AeadWithNonceKey
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
+pub struct AeadWithNonceKey {
+    pub version: u32,
+    /// Type URL of the wrapped key type.
+    pub aead_type_url: std::string::String,
+    /// Serialized key of the wrapped key type.
+    pub aead_key: std::vec::Vec<u8>,
+}
+secret_message! {
+    AeadWithNonceKey {
+        version: uint32 = 1,
+        aead_type_url: string = 2,
+        aead_key: bytes = 3,
+    }
+}
+/// Key-committing AEAD built from AES-GCM: for each message, an AES-GCM key
+/// and a 32-byte commitment tag are derived from the key value and a random
+/// nonce with HKDF-SHA256, and the commitment tag is included in the
+/// ciphertext. The only allowed nonce size is 12 bytes and tag size is 16
+/// bytes, so accept no params.
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[derive(zeroize::Zeroize)]
+pub struct AesGcmCommittingKeyFormat {
+    #[prost(uint32, tag="1")]
+    pub key_size: u32,
+    #[prost(uint32, tag="2")]
+    pub version: u32,
+}
+/// key_type: type.googleapis.com/tink_rust.AesGcmCommittingKey
+#[derive(Clone, PartialEq)]
+#[derive(zeroize::Zeroize)]
+#[zeroize(drop)]
+pub struct AesGcmCommittingKey {
+    pub version: u32,
+    pub key_value: std::vec::Vec<u8>,
+}
+secret_message! {
+    AesGcmCommittingKey {
+        version: uint32 = 1,
+        key_value: bytes = 2,
+    }
+}
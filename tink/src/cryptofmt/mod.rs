@@ -16,7 +16,7 @@
 
 //! Provides constants and convenience methods that define the format of ciphertexts and signatures.
 
-use crate::{proto::OutputPrefixType, TinkError};
+use crate::{proto::OutputPrefixType, ErrorKind, TinkError};
 
 #[cfg(test)]
 mod tests;
@@ -56,9 +56,10 @@ pub fn output_prefix(key: &crate::proto::keyset::Key) -> Result<Vec<u8>, TinkErr
             key.key_id,
         )),
         Some(OutputPrefixType::Raw) => Ok(RAW_PREFIX),
-        Some(OutputPrefixType::UnknownPrefix) | None => {
-            Err("cryptofmt: unknown output prefix type".into())
-        }
+        Some(OutputPrefixType::UnknownPrefix) | None => Err(TinkError::new_with_kind(
+            ErrorKind::InvalidKeyset,
+            "cryptofmt: unknown output prefix type",
+        )),
     }
 }
 
@@ -37,7 +37,7 @@
 ///
 /// - [RFC 5116](https://tools.ietf.org/html/rfc5116_
 /// - [RFC 5297 s1.3](https://tools.ietf.org/html/rfc5297#section-1.3)
-pub trait DeterministicAead: DeterministicAeadBoxClone {
+pub trait DeterministicAead: DeterministicAeadBoxClone + Send + Sync {
     // Deterministical encrypt plaintext with `additional_data` as additional authenticated data.
     // The resulting ciphertext allows for checking authenticity and integrity of additional
     // data `additional_data`, but there are no guarantees wrt. secrecy of that data.
@@ -55,6 +55,48 @@ pub trait DeterministicAead: DeterministicAeadBoxClone {
         ciphertext: &[u8],
         additional_data: &[u8],
     ) -> Result<Vec<u8>, crate::TinkError>;
+
+    /// Deterministically encrypt `plaintext`, authenticating each of the `additional_data`
+    /// components as a separate string (as for the vector of associated data in
+    /// [RFC 5297](https://tools.ietf.org/html/rfc5297)), which avoids the need to concatenate
+    /// independent fields unambiguously.
+    ///
+    /// Encrypting with a single component is the same as
+    /// [`encrypt_deterministically`](DeterministicAead::encrypt_deterministically) with that
+    /// component.  The default implementation only supports a single component.
+    fn encrypt_deterministically_with_ad_vector(
+        &self,
+        plaintext: &[u8],
+        additional_data: &[&[u8]],
+    ) -> Result<Vec<u8>, crate::TinkError> {
+        match additional_data {
+            [aad] => self.encrypt_deterministically(plaintext, aad),
+            _ => Err(ad_vector_unsupported()),
+        }
+    }
+
+    /// Deterministically decrypt `ciphertext` produced by
+    /// [`encrypt_deterministically_with_ad_vector`](DeterministicAead::encrypt_deterministically_with_ad_vector)
+    /// with the same `additional_data` components.
+    ///
+    /// The default implementation only supports a single component.
+    fn decrypt_deterministically_with_ad_vector(
+        &self,
+        ciphertext: &[u8],
+        additional_data: &[&[u8]],
+    ) -> Result<Vec<u8>, crate::TinkError> {
+        match additional_data {
+            [aad] => self.decrypt_deterministically(ciphertext, aad),
+            _ => Err(ad_vector_unsupported()),
+        }
+    }
+}
+
+fn ad_vector_unsupported() -> crate::TinkError {
+    crate::TinkError::new_with_kind(
+        crate::ErrorKind::Unsupported,
+        "DeterministicAead: only a single associated data component is supported",
+    )
 }
 
 /// Trait bound to indicate that primitive trait objects should support cloning
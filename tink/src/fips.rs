@@ -0,0 +1,59 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Support for restricting Tink to FIPS 140-approved algorithms.
+//!
+//! When FIPS mode is enabled (at runtime via [`enable`], or at build time via the `fips` cargo
+//! feature), primitives and new keys can only be created for keys whose algorithms are
+//! FIPS 140-approved, namely:
+//!
+//! - AES-GCM and AES-CTR-HMAC-SHA2 AEAD
+//! - HMAC-SHA2 MAC and PRF
+//! - HKDF PRF
+//! - ECDSA over NIST curves.
+//!
+//! Other keys (such as ChaCha20-Poly1305, XChaCha20-Poly1305, AES-GCM-SIV, Ed25519 or HMAC-SHA1
+//! keys) are rejected with an [`ErrorKind::Unsupported`](crate::ErrorKind::Unsupported) error.
+//!
+//! FIPS mode can also be selected for an individual [`Registry`](crate::registry::Registry)
+//! configuration, using [`Registry::fips_only`](crate::registry::Registry::fips_only).
+//!
+//! Note that this restricts the *algorithms* in use; the underlying implementations have not been
+//! validated under FIPS 140.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enable FIPS mode for the rest of the lifetime of the process.
+pub fn enable() {
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// Indicate whether FIPS mode is enabled.
+pub fn is_enabled() -> bool {
+    cfg!(feature = "fips") || ENABLED.load(Ordering::SeqCst)
+}
+
+/// Indicate whether the hash function identified by `hash` (a [`HashType`](crate::proto::HashType)
+/// value) is FIPS 140-approved for use in HMAC and HKDF.
+pub fn is_approved_hash(hash: i32) -> bool {
+    use crate::proto::HashType;
+    matches!(
+        HashType::from_i32(hash),
+        Some(HashType::Sha256) | Some(HashType::Sha384) | Some(HashType::Sha512)
+    )
+}
@@ -53,7 +53,7 @@
 ///   [RFC 5116](ttps://tools.ietf.org/html/rfc5116)).
 /// - use `context_info` as "CtxInfo"-input for HKDF (if the implementation uses HKDF as key
 ///   derivation function, cf. [RFC 5869](https://tools.ietf.org/html/rfc5869)).
-pub trait HybridEncrypt: HybridEncryptBoxClone {
+pub trait HybridEncrypt: HybridEncryptBoxClone + Send + Sync {
     /// Encrypt plaintext binding `context_info` to the resulting
     /// ciphertext. Returns resulting ciphertext.
     fn encrypt(&self, plaintext: &[u8], context_info: &[u8]) -> Result<Vec<u8>, crate::TinkError>;
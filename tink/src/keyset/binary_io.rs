@@ -16,8 +16,9 @@
 
 //! Binary I/O for keysets.
 
-use crate::{utils::wrap_err, TinkError};
+use crate::{utils::wrap_err, ErrorKind, TinkError};
 use std::io::{Read, Write};
+use zeroize::Zeroizing;
 
 /// `BinaryReader` deserializes a keyset from binary proto format.
 pub struct BinaryReader<T: Read> {
@@ -49,12 +50,13 @@ fn read<T>(r: &mut dyn Read) -> Result<T, TinkError>
 where
     T: prost::Message + std::default::Default,
 {
-    let mut data = vec![];
+    // The serialized data may include secret key material, so wipe it after use.
+    let mut data = Zeroizing::new(vec![]);
     r.read_to_end(&mut data)
-        .map_err(|e| wrap_err("read failed", e))?;
+        .map_err(|e| wrap_err("read failed", e).with_kind(ErrorKind::Io))?;
     match T::decode(data.as_ref()) {
         Ok(msg) => Ok(msg),
-        Err(e) => Err(wrap_err("decode failed", e)),
+        Err(e) => Err(wrap_err("decode failed", e).with_kind(ErrorKind::InvalidKeyset)),
     }
 }
 
@@ -86,13 +88,15 @@ fn write<T>(w: &mut dyn Write, msg: &T) -> Result<(), TinkError>
 where
     T: prost::Message,
 {
-    let mut data = vec![];
-    match msg.encode(&mut data) {
+    // Allocate the exact size up front so that no partial copies of the (possibly secret)
+    // serialized data are left behind by reallocation.
+    let mut data = Zeroizing::new(Vec::with_capacity(msg.encoded_len()));
+    match msg.encode(&mut *data) {
         Ok(()) => Ok(()),
         Err(e) => Err(wrap_err("encode failed", e)),
     }?;
     match w.write(&data) {
         Ok(_size) => Ok(()),
-        Err(e) => Err(wrap_err("write failed", e)),
+        Err(e) => Err(wrap_err("write failed", e).with_kind(ErrorKind::Io)),
     }
 }
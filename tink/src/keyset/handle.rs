@@ -19,15 +19,17 @@
 use crate::{
     proto::{key_data::KeyMaterialType, Keyset, KeysetInfo},
     utils::wrap_err,
-    TinkError,
+    ErrorKind, TinkError,
 };
 use prost::Message;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
+use zeroize::Zeroizing;
 
 /// `Handle` provides access to a [`Keyset`] protobuf, to limit the exposure
 /// of actual protocol buffers that hold sensitive key material.
 pub struct Handle {
     ks: Keyset,
+    annotations: HashMap<String, String>,
 }
 
 impl Handle {
@@ -46,10 +48,14 @@ impl Handle {
     pub fn new_with_no_secrets(ks: Keyset) -> Result<Self, TinkError> {
         let h = Handle {
             ks: validate_keyset(ks)?,
+            annotations: HashMap::new(),
         };
         if h.has_secrets()? {
             // If you need to do this, you have to use `tink::keyset::insecure::read()` instead.
-            return Err("importing unencrypted secret key material is forbidden".into());
+            return Err(TinkError::new_with_kind(
+                ErrorKind::InvalidArgument,
+                "importing unencrypted secret key material is forbidden",
+            ));
         }
         Ok(h)
     }
@@ -57,13 +63,28 @@ impl Handle {
     /// Attempt to create a [`Handle`] from an encrypted keyset obtained via a
     /// [`Reader`](crate::keyset::Reader).
     pub fn read<T>(reader: &mut T, master_key: Box<dyn crate::Aead>) -> Result<Self, TinkError>
+    where
+        T: crate::keyset::Reader,
+    {
+        Self::read_with_associated_data(reader, master_key, &[])
+    }
+
+    /// Attempt to create a [`Handle`] from an encrypted keyset obtained via a
+    /// [`Reader`](crate::keyset::Reader), which was encrypted with the given associated data
+    /// (e.g. by [`write_with_associated_data`](Handle::write_with_associated_data)).
+    pub fn read_with_associated_data<T>(
+        reader: &mut T,
+        master_key: Box<dyn crate::Aead>,
+        associated_data: &[u8],
+    ) -> Result<Self, TinkError>
     where
         T: crate::keyset::Reader,
     {
         let encrypted_keyset = reader.read_encrypted()?;
-        let ks = decrypt(&encrypted_keyset, master_key)?;
+        let ks = decrypt(&encrypted_keyset, master_key, associated_data)?;
         Ok(Handle {
             ks: validate_keyset(ks)?,
+            annotations: HashMap::new(),
         })
     }
 
@@ -82,10 +103,9 @@ impl Handle {
         let priv_keys = &self.ks.key;
         let mut pub_keys = Vec::with_capacity(priv_keys.len());
         for priv_key in priv_keys {
-            let priv_key_data = priv_key
-                .key_data
-                .as_ref()
-                .ok_or_else(|| TinkError::new("keyset::Handle: invalid keyset"))?;
+            let priv_key_data = priv_key.key_data.as_ref().ok_or_else(|| {
+                TinkError::new_with_kind(ErrorKind::InvalidKeyset, "keyset::Handle: invalid keyset")
+            })?;
             let pub_key_data =
                 public_key_data(priv_key_data).map_err(|e| wrap_err("keyset::Handle", e))?;
             pub_keys.push(crate::proto::keyset::Key {
@@ -99,7 +119,23 @@ impl Handle {
             primary_key_id: self.ks.primary_key_id,
             key: pub_keys,
         };
-        Ok(Handle { ks })
+        Ok(Handle {
+            ks,
+            annotations: self.annotations.clone(),
+        })
+    }
+
+    /// Attach the given annotations to the keyset handle, replacing any existing annotations.
+    /// Primitives created from an annotated handle report their usage to the registered
+    /// [monitoring client](crate::monitoring), identified by the annotations.
+    pub fn with_annotations(mut self, annotations: HashMap<String, String>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Return the annotations attached to the keyset handle.
+    pub fn annotations(&self) -> &HashMap<String, String> {
+        &self.annotations
     }
 
     /// Encrypts and writes the enclosed [`Keyset`].
@@ -111,7 +147,23 @@ impl Handle {
     where
         T: super::Writer,
     {
-        let encrypted = encrypt(&self.ks, master_key)?;
+        self.write_with_associated_data(writer, master_key, &[])
+    }
+
+    /// Encrypts and writes the enclosed [`Keyset`], binding the encrypted keyset to
+    /// `associated_data` (e.g. the name of the service or environment that uses the keyset).  The
+    /// same associated data must be provided to
+    /// [`read_with_associated_data`](Handle::read_with_associated_data) to read the keyset back.
+    pub fn write_with_associated_data<T>(
+        &self,
+        writer: &mut T,
+        master_key: Box<dyn crate::Aead>,
+        associated_data: &[u8],
+    ) -> Result<(), TinkError>
+    where
+        T: super::Writer,
+    {
+        let encrypted = encrypt(&self.ks, master_key, associated_data)?;
         writer.write_encrypted(&encrypted)
     }
 
@@ -122,12 +174,45 @@ impl Handle {
         T: super::Writer,
     {
         if self.has_secrets()? {
-            Err("exporting unencrypted secret key material is forbidden".into())
+            Err(TinkError::new_with_kind(
+                ErrorKind::InvalidArgument,
+                "exporting unencrypted secret key material is forbidden",
+            ))
         } else {
             w.write(&self.ks)
         }
     }
 
+    /// Create a primitive of type `P` (e.g. `dyn tink::Aead`) for the keys in the keyset, using
+    /// the [`PrimitiveWrapper`](crate::registry::PrimitiveWrapper) registered for `P` in the
+    /// global registry.  Fails with [`ErrorKind::Unsupported`] if there is no wrapper for `P`
+    /// (e.g. because the relevant primitive crate has not been initialized), and with
+    /// [`ErrorKind::InvalidKeyset`] if the keys in the keyset are not for `P`.
+    pub fn primitive<P>(&self) -> Result<Box<P>, TinkError>
+    where
+        P: ?Sized + 'static,
+    {
+        let wrapper = crate::registry::get_primitive_wrapper::<P>()
+            .map_err(|e| wrap_err("keyset::Handle: cannot create primitive", e))?;
+        wrapper.wrap(self.primitives()?)
+    }
+
+    /// Create a primitive of type `P` for the keys in the keyset, using the key managers and the
+    /// [`PrimitiveWrapper`](crate::registry::PrimitiveWrapper) in the given
+    /// [`Registry`](crate::registry::Registry) rather than the global registry.
+    pub fn primitive_with_config<P>(
+        &self,
+        config: &crate::registry::Registry,
+    ) -> Result<Box<P>, TinkError>
+    where
+        P: ?Sized + 'static,
+    {
+        let wrapper = config
+            .get_primitive_wrapper::<P>()
+            .map_err(|e| wrap_err("keyset::Handle: cannot create primitive", e))?;
+        wrapper.wrap(self.primitives_with_config(config)?)
+    }
+
     /// Create a set of primitives corresponding to the keys with status=ENABLED in the keyset of
     /// the given keyset [`Handle`], assuming all the corresponding key managers are present (keys
     /// with status!=ENABLED are skipped).
@@ -154,31 +239,63 @@ impl Handle {
         &self,
         km: Option<Arc<dyn crate::registry::KeyManager>>,
     ) -> Result<crate::primitiveset::PrimitiveSet, TinkError> {
-        super::validate(&self.ks)
-            .map_err(|e| wrap_err("primitives_with_key_manager: invalid keyset", e))?;
+        self.primitives_from("primitives_with_key_manager", |key_data| match &km {
+            Some(km) if km.does_support(&key_data.type_url) => {
+                crate::registry::check_fips(
+                    km.as_ref(),
+                    &key_data.value,
+                    crate::fips::is_enabled(),
+                )?;
+                km.primitive(&key_data.value)
+            }
+            Some(_) | None => crate::registry::primitive_from_key_data(key_data),
+        })
+    }
+
+    /// Create a set of primitives corresponding to the keys with status=ENABLED in the keyset of
+    /// the given keyset [`Handle`], using the key managers in the given
+    /// [`Registry`](crate::registry::Registry) rather than the global registry.  Keys with
+    /// status!=ENABLED are skipped.
+    ///
+    /// The returned set is usually later "wrapped" into a class that implements the corresponding
+    /// [`Primitive`](crate::Primitive)-interface.
+    pub fn primitives_with_config(
+        &self,
+        config: &crate::registry::Registry,
+    ) -> Result<crate::primitiveset::PrimitiveSet, TinkError> {
+        self.primitives_from("primitives_with_config", |key_data| {
+            config.primitive_from_key_data(key_data)
+        })
+    }
+
+    /// Create a set of primitives corresponding to the keys with status=ENABLED in the keyset,
+    /// using `primitive_fn` to create each primitive.
+    fn primitives_from<F>(
+        &self,
+        ctx: &str,
+        primitive_fn: F,
+    ) -> Result<crate::primitiveset::PrimitiveSet, TinkError>
+    where
+        F: Fn(&crate::proto::KeyData) -> Result<crate::Primitive, TinkError>,
+    {
+        super::validate(&self.ks).map_err(|e| wrap_err(&format!("{}: invalid keyset", ctx), e))?;
         let mut primitive_set = crate::primitiveset::PrimitiveSet::new();
+        primitive_set.annotations = self.annotations.clone();
         for key in &self.ks.key {
             if key.status != crate::proto::KeyStatusType::Enabled as i32 {
                 continue;
             }
-            let key_data = key
-                .key_data
-                .as_ref()
-                .ok_or_else(|| TinkError::new("primitives_with_key_manager: no key_data"))?;
-            let primitive = match &km {
-                Some(km) if km.does_support(&key_data.type_url) => km.primitive(&key_data.value),
-                Some(_) | None => crate::registry::primitive_from_key_data(&key_data),
-            }
-            .map_err(|e| {
-                wrap_err(
-                    "primitives_with_key_manager: cannot get primitive from key",
-                    e,
-                )
+            let key_data = key.key_data.as_ref().ok_or_else(|| {
+                TinkError::new_with_kind(ErrorKind::InvalidKeyset, &format!("{}: no key_data", ctx))
+            })?;
+            let primitive = primitive_fn(key_data).map_err(|e| {
+                wrap_err(&format!("{}: cannot get primitive from key", ctx), e)
+                    .or_kind(ErrorKind::InvalidKey)
             })?;
 
             let entry = primitive_set
                 .add(primitive, key)
-                .map_err(|e| wrap_err("primitives_with_key_manager: cannot add primitive", e))?;
+                .map_err(|e| wrap_err(&format!("{}: cannot add primitive", ctx), e))?;
             if key.key_id == self.ks.primary_key_id {
                 primitive_set.primary = Some(entry.clone());
             }
@@ -193,14 +310,24 @@ impl Handle {
         let mut result = false;
         for k in &self.ks.key {
             match &k.key_data {
-                None => return Err("invalid keyset".into()),
+                None => {
+                    return Err(TinkError::new_with_kind(
+                        ErrorKind::InvalidKeyset,
+                        "invalid keyset",
+                    ))
+                }
                 Some(kd) => match KeyMaterialType::from_i32(kd.key_material_type) {
                     Some(KeyMaterialType::UnknownKeymaterial) => result = true,
                     Some(KeyMaterialType::Symmetric) => result = true,
                     Some(KeyMaterialType::AsymmetricPrivate) => result = true,
                     Some(KeyMaterialType::AsymmetricPublic) => {}
                     Some(KeyMaterialType::Remote) => {}
-                    None => return Err("invalid key material type".into()),
+                    None => {
+                        return Err(TinkError::new_with_kind(
+                            ErrorKind::InvalidKeyset,
+                            "invalid key material type",
+                        ))
+                    }
                 },
             }
         }
@@ -230,6 +357,7 @@ impl Handle {
     pub(crate) fn from_keyset(ks: Keyset) -> Result<Self, TinkError> {
         Ok(Handle {
             ks: validate_keyset(ks)?,
+            annotations: HashMap::new(),
         })
     }
 }
@@ -239,10 +367,20 @@ fn validate_keyset(ks: Keyset) -> Result<Keyset, TinkError> {
     for k in &ks.key {
         match &k.key_data {
             None if k.status == crate::proto::KeyStatusType::Destroyed as i32 => {}
-            None => return Err("invalid keyset".into()),
+            None => {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidKeyset,
+                    "invalid keyset",
+                ))
+            }
             Some(kd) => match KeyMaterialType::from_i32(kd.key_material_type) {
                 Some(_) => {}
-                None => return Err("invalid key material type".into()),
+                None => {
+                    return Err(TinkError::new_with_kind(
+                        ErrorKind::InvalidKeyset,
+                        "invalid key material type",
+                    ))
+                }
             },
         }
     }
@@ -256,42 +394,56 @@ fn public_key_data(
     if priv_key_data.key_material_type
         != crate::proto::key_data::KeyMaterialType::AsymmetricPrivate as i32
     {
-        return Err("keyset::Handle: keyset contains a non-private key".into());
+        return Err(TinkError::new_with_kind(
+            ErrorKind::InvalidArgument,
+            "keyset::Handle: keyset contains a non-private key",
+        ));
     }
     let km = crate::registry::get_key_manager(&priv_key_data.type_url)?;
 
     if !km.supports_private_keys() {
-        return Err(format!(
-            "keyset::Handle: {} does not belong to a KeyManager that handles private keys",
-            priv_key_data.type_url
-        )
-        .into());
+        return Err(TinkError::new_with_kind(
+            ErrorKind::InvalidArgument,
+            &format!(
+                "keyset::Handle: {} does not belong to a KeyManager that handles private keys",
+                priv_key_data.type_url
+            ),
+        ));
     }
     km.public_key_data(&priv_key_data.value)
 }
 
-/// Decrypt a keyset with a master key.
+/// Decrypt a keyset with a master key and associated data.
 fn decrypt(
     encrypted_keyset: &crate::proto::EncryptedKeyset,
     master_key: Box<dyn crate::Aead>,
+    associated_data: &[u8],
 ) -> Result<Keyset, TinkError> {
     let decrypted = master_key
-        .decrypt(&encrypted_keyset.encrypted_keyset, &[])
-        .map_err(|e| wrap_err("keyset::Handle: decryption failed", e))?;
-    Keyset::decode(&decrypted[..]).map_err(|_| TinkError::new("keyset::Handle:: invalid keyset"))
+        .decrypt(&encrypted_keyset.encrypted_keyset, associated_data)
+        .map(Zeroizing::new)
+        .map_err(|e| {
+            wrap_err("keyset::Handle: decryption failed", e).or_kind(ErrorKind::Authentication)
+        })?;
+    Keyset::decode(&decrypted[..]).map_err(|_| {
+        TinkError::new_with_kind(ErrorKind::InvalidKeyset, "keyset::Handle:: invalid keyset")
+    })
 }
 
-/// Encrypt a keyset with a master key.
+/// Encrypt a keyset with a master key and associated data.
 fn encrypt(
     keyset: &Keyset,
     master_key: Box<dyn crate::Aead>,
+    associated_data: &[u8],
 ) -> Result<crate::proto::EncryptedKeyset, TinkError> {
-    let mut serialized_keyset = vec![];
+    // Allocate the exact size up front so that reallocation does not leave unwiped copies of
+    // the serialized keyset behind.
+    let mut serialized_keyset = Zeroizing::new(Vec::with_capacity(keyset.encoded_len()));
     keyset
-        .encode(&mut serialized_keyset)
+        .encode(&mut *serialized_keyset)
         .map_err(|e| wrap_err("keyset::Handle: invalid keyset", e))?;
     let encrypted = master_key
-        .encrypt(&serialized_keyset, &[])
+        .encrypt(&serialized_keyset, associated_data)
         .map_err(|e| wrap_err("keyset::Handle: encrypted failed", e))?;
     Ok(crate::proto::EncryptedKeyset {
         encrypted_keyset: encrypted,
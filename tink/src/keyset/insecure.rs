@@ -16,7 +16,7 @@
 
 //! Module for test code methods to read or write cleartext keyset material.
 
-use crate::TinkError;
+use crate::{ErrorKind, TinkError};
 
 /// Create a [`Handle`](super::Handle) from cleartext key material.
 fn keyset_handle(ks: crate::proto::Keyset) -> Result<super::Handle, TinkError> {
@@ -32,7 +32,10 @@ pub fn keyset_material(h: &super::Handle) -> crate::proto::Keyset {
 /// [`Keyset`](crate::proto::Keyset).
 pub fn new_handle(ks: crate::proto::Keyset) -> Result<super::Handle, TinkError> {
     if ks.key.is_empty() {
-        Err("insecure: invalid keyset".into())
+        Err(TinkError::new_with_kind(
+            ErrorKind::InvalidKeyset,
+            "insecure: invalid keyset",
+        ))
     } else {
         keyset_handle(ks)
     }
@@ -45,7 +48,10 @@ where
 {
     let ks = r.read()?;
     if ks.key.is_empty() {
-        Err("insecure: invalid keyset".into())
+        Err(TinkError::new_with_kind(
+            ErrorKind::InvalidKeyset,
+            "insecure: invalid keyset",
+        ))
     } else {
         keyset_handle(ks)
     }
@@ -60,3 +66,18 @@ where
 {
     w.write(&keyset_material(h))
 }
+
+/// Wrapper whose [`Debug`](std::fmt::Debug) output includes the secret key material held in the
+/// wrapped value, which is otherwise redacted.  Leaking this output (e.g. into logs) is dangerous.
+///
+/// ```ignore
+/// println!("{:?}", tink::keyset::insecure::Unredacted(&keyset));
+/// ```
+pub struct Unredacted<'a, T: ?Sized>(pub &'a T);
+
+impl<T: std::fmt::Debug + ?Sized> std::fmt::Debug for Unredacted<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let _show = crate::utils::ShowSecrets::new();
+        self.0.fmt(f)
+    }
+}
@@ -16,7 +16,7 @@
 
 //! JSON I/O for keysets (requires activation of the `json` feature).
 
-use crate::{utils::wrap_err, TinkError};
+use crate::{utils::wrap_err, ErrorKind, TinkError};
 use serde::Deserialize;
 use std::io::{Read, Write};
 
@@ -37,7 +37,7 @@ impl<T: Read> super::Reader for JsonReader<T> {
     /// [`std::io::Read`].
     fn read(&mut self) -> Result<crate::proto::Keyset, TinkError> {
         let mut de = serde_json::Deserializer::from_reader(&mut self.r);
-        crate::proto::Keyset::deserialize(&mut de).map_err(|e| wrap_err("failed to parse", e))
+        crate::proto::Keyset::deserialize(&mut de).map_err(|e| json_err("failed to parse", e))
     }
 
     /// Return an [`EncryptedKeyset`](crate::proto::EncryptedKeyset) object from the underlying
@@ -45,7 +45,7 @@ impl<T: Read> super::Reader for JsonReader<T> {
     fn read_encrypted(&mut self) -> Result<crate::proto::EncryptedKeyset, TinkError> {
         let mut de = serde_json::Deserializer::from_reader(&mut self.r);
         crate::proto::EncryptedKeyset::deserialize(&mut de)
-            .map_err(|e| wrap_err("failed to parse", e))
+            .map_err(|e| json_err("failed to parse", e))
     }
 }
 
@@ -65,16 +65,26 @@ impl<T: Write> super::Writer for JsonWriter<T> {
     /// Write the keyset to the underlying [`std::io::Write`].
     fn write(&mut self, keyset: &crate::proto::Keyset) -> Result<(), TinkError> {
         serde_json::to_writer_pretty(&mut self.w, keyset)
-            .map_err(|e| wrap_err("failed to encode", e))
+            .map_err(|e| json_err("failed to encode", e))
     }
 
     /// Write the encrypted keyset to the underlying [`std::io::Write`].
     fn write_encrypted(&mut self, keyset: &crate::proto::EncryptedKeyset) -> Result<(), TinkError> {
         serde_json::to_writer_pretty(&mut self.w, keyset)
-            .map_err(|e| wrap_err("failed to encode", e))
+            .map_err(|e| json_err("failed to encode", e))
     }
 }
 
+/// Wrap a JSON error, categorizing it as an I/O error or an invalid keyset.
+fn json_err(msg: &str, e: serde_json::Error) -> TinkError {
+    let kind = if e.is_io() {
+        ErrorKind::Io
+    } else {
+        ErrorKind::InvalidKeyset
+    };
+    wrap_err(msg, e).with_kind(kind)
+}
+
 // Manual serialization implementations that map enums onto strings rather than
 // the `i32` values used by prost.
 pub mod key_status_type {
@@ -155,15 +165,18 @@ pub mod key_material_type {
 }
 pub mod b64 {
     //! Manual serialization implementations for base64-encoded binary data.
+    //!
+    //! The encoded data may hold secret key material, so intermediate strings are wiped after use.
     use serde::Deserialize;
+    use zeroize::Zeroizing;
     pub fn serialize<S: serde::Serializer>(val: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
-        serializer.serialize_str(&base64::encode(val))
+        serializer.serialize_str(&Zeroizing::new(base64::encode(val)))
     }
     pub fn deserialize<'de, D: serde::Deserializer<'de>>(
         deserializer: D,
     ) -> Result<Vec<u8>, D::Error> {
-        let s = String::deserialize(deserializer)?;
-        base64::decode(&s).map_err(|_e| {
+        let s = Zeroizing::new(String::deserialize(deserializer)?);
+        base64::decode(s.as_bytes()).map_err(|_e| {
             serde::de::Error::invalid_value(serde::de::Unexpected::Str(&s), &"base64 data expected")
         })
     }
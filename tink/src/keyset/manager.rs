@@ -19,7 +19,7 @@
 use crate::{
     proto::{KeyStatusType, OutputPrefixType},
     utils::wrap_err,
-    KeyId, TinkError,
+    ErrorKind, KeyId, TinkError,
 };
 use rand::Rng;
 
@@ -96,15 +96,17 @@ impl Manager {
                         key.status = KeyStatusType::Enabled as i32;
                         Ok(())
                     }
-                    _ => Err(format!(
-                        "Cannot enable key with key_id {} and status {}",
-                        key_id, key.status
-                    )
-                    .into()),
+                    _ => Err(TinkError::new_with_kind(
+                        ErrorKind::InvalidArgument,
+                        &format!(
+                            "Cannot enable key with key_id {} and status {}",
+                            key_id, key.status
+                        ),
+                    )),
                 };
             }
         }
-        Err(format!("Key {} not found", key_id).into())
+        Err(key_not_found(key_id))
     }
 
     /// Sets the status of the specified key to [`KeyStatusType::Disabled`].
@@ -112,7 +114,10 @@ impl Manager {
     /// is not primary and has status [`KeyStatusType::Disabled`] or [`KeyStatusType::Enabled`].
     pub fn disable(&mut self, key_id: KeyId) -> Result<(), TinkError> {
         if self.ks.primary_key_id == key_id {
-            return Err(format!("Cannot disable primary key (key_id {})", key_id).into());
+            return Err(TinkError::new_with_kind(
+                ErrorKind::InvalidArgument,
+                &format!("Cannot disable primary key (key_id {})", key_id),
+            ));
         }
         for key in &mut self.ks.key {
             if key.key_id == key_id {
@@ -121,15 +126,17 @@ impl Manager {
                         key.status = KeyStatusType::Disabled as i32;
                         Ok(())
                     }
-                    _ => Err(format!(
-                        "Cannot disable key with key_id {} and status {}",
-                        key_id, key.status
-                    )
-                    .into()),
+                    _ => Err(TinkError::new_with_kind(
+                        ErrorKind::InvalidArgument,
+                        &format!(
+                            "Cannot disable key with key_id {} and status {}",
+                            key_id, key.status
+                        ),
+                    )),
                 };
             }
         }
-        Err(format!("Key {} not found", key_id).into())
+        Err(key_not_found(key_id))
     }
 
     /// Sets the status of the specified key to [`KeyStatusType::Destroyed`], and removes the
@@ -138,7 +145,10 @@ impl Manager {
     /// [`KeyStatusType::Enabled`], or [`KeyStatusType::Destroyed`].
     pub fn destroy(&mut self, key_id: KeyId) -> Result<(), TinkError> {
         if self.ks.primary_key_id == key_id {
-            return Err(format!("Cannot destroy primary key (key_id {})", key_id).into());
+            return Err(TinkError::new_with_kind(
+                ErrorKind::InvalidArgument,
+                &format!("Cannot destroy primary key (key_id {})", key_id),
+            ));
         }
         for key in &mut self.ks.key {
             if key.key_id == key_id {
@@ -150,22 +160,27 @@ impl Manager {
                         key.status = KeyStatusType::Destroyed as i32;
                         Ok(())
                     }
-                    _ => Err(format!(
-                        "Cannot destroy key with key_id {} and status {}",
-                        key_id, key.status
-                    )
-                    .into()),
+                    _ => Err(TinkError::new_with_kind(
+                        ErrorKind::InvalidArgument,
+                        &format!(
+                            "Cannot destroy key with key_id {} and status {}",
+                            key_id, key.status
+                        ),
+                    )),
                 };
             }
         }
-        Err(format!("Key {} not found", key_id).into())
+        Err(key_not_found(key_id))
     }
 
     /// Removes the specifed key from the managed keyset.  Succeeds only if the specified key is not
     /// primary.  After deletion the keyset contains one key fewer.
     pub fn delete(&mut self, key_id: KeyId) -> Result<(), TinkError> {
         if self.ks.primary_key_id == key_id {
-            return Err(format!("Cannot delete primary key (key_id {})", key_id).into());
+            return Err(TinkError::new_with_kind(
+                ErrorKind::InvalidArgument,
+                &format!("Cannot delete primary key (key_id {})", key_id),
+            ));
         }
         let mut idx: Option<usize> = None;
         for (i, key) in self.ks.key.iter().enumerate() {
@@ -179,7 +194,7 @@ impl Manager {
                 self.ks.key.remove(i);
                 Ok(())
             }
-            None => Err(format!("Key {} not found", key_id).into()),
+            None => Err(key_not_found(key_id)),
         }
     }
 
@@ -192,15 +207,18 @@ impl Manager {
                         self.ks.primary_key_id = key_id;
                         Ok(())
                     }
-                    _ => Err(format!(
-                        "The candidate (key_id {}) for the primary key must be Enabled (status {})",
-                        key_id, key.status
-                    )
-                    .into()),
+                    _ => Err(TinkError::new_with_kind(
+                        ErrorKind::InvalidArgument,
+                        &format!(
+                            "The candidate (key_id {}) for the primary key must be Enabled \
+                             (status {})",
+                            key_id, key.status
+                        ),
+                    )),
                 };
             }
         }
-        Err(format!("Key {} not found", key_id).into())
+        Err(key_not_found(key_id))
     }
 
     /// Return the count of all keys in the keyset.
@@ -221,3 +239,8 @@ impl Manager {
         }
     }
 }
+
+/// Return an error indicating that the key with the given ID is not in the keyset.
+fn key_not_found(key_id: KeyId) -> TinkError {
+    TinkError::new_with_kind(ErrorKind::KeyNotFound, &format!("Key {} not found", key_id))
+}
@@ -16,7 +16,7 @@
 
 //! In-memory I/O for keysets (typically for testing).
 
-use crate::TinkError;
+use crate::{ErrorKind, TinkError};
 
 /// `MemReaderWriter` implements [`keyset::Reader`](super::Reader) and
 /// [`keyset.Writer`](super::Writer) with in-memory storage.
@@ -31,7 +31,10 @@ impl super::Reader for MemReaderWriter {
     fn read(&mut self) -> Result<crate::proto::Keyset, TinkError> {
         match &self.keyset {
             Some(keyset) => Ok(keyset.clone()),
-            None => Err("no keyset available".into()),
+            None => Err(TinkError::new_with_kind(
+                ErrorKind::InvalidKeyset,
+                "no keyset available",
+            )),
         }
     }
 
@@ -39,7 +42,10 @@ impl super::Reader for MemReaderWriter {
     fn read_encrypted(&mut self) -> Result<crate::proto::EncryptedKeyset, TinkError> {
         match &self.encrypted_keyset {
             Some(keyset) => Ok(keyset.clone()),
-            None => Err("no keyset available".into()),
+            None => Err(TinkError::new_with_kind(
+                ErrorKind::InvalidKeyset,
+                "no keyset available",
+            )),
         }
     }
 }
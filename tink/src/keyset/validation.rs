@@ -16,24 +16,30 @@
 
 //! Keyset validation functions.
 
-use crate::TinkError;
+use crate::{ErrorKind, TinkError};
 
 /// Check whether the given version is valid. The version is valid
 /// only if it is the range [0..max_expected].
 pub fn validate_key_version(version: u32, max_expected: u32) -> Result<(), TinkError> {
     if version > max_expected {
-        Err(format!(
-            "key has version {}; only keys with version in range [0..{}] are supported",
-            version, max_expected
-        )
-        .into())
+        Err(TinkError::new_with_kind(
+            ErrorKind::InvalidKey,
+            &format!(
+                "key has version {}; only keys with version in range [0..{}] are supported",
+                version, max_expected
+            ),
+        ))
     } else {
         Ok(())
     }
 }
 
-/// Validate the given key set.
+/// Validate the given key set.  Errors have [`ErrorKind::InvalidKeyset`].
 pub fn validate(keyset: &crate::proto::Keyset) -> Result<(), TinkError> {
+    validate_keyset(keyset).map_err(|e| e.with_kind(ErrorKind::InvalidKeyset))
+}
+
+fn validate_keyset(keyset: &crate::proto::Keyset) -> Result<(), TinkError> {
     if keyset.key.is_empty() {
         return Err("empty keyset".into());
     }
@@ -70,8 +76,12 @@ pub fn validate(keyset: &crate::proto::Keyset) -> Result<(), TinkError> {
     }
 }
 
-/// Validate the given key.
+/// Validate the given key.  Errors have [`ErrorKind::InvalidKeyset`].
 pub fn validate_key(key: &crate::proto::keyset::Key) -> Result<(), TinkError> {
+    validate_keyset_key(key).map_err(|e| e.with_kind(ErrorKind::InvalidKeyset))
+}
+
+fn validate_keyset_key(key: &crate::proto::keyset::Key) -> Result<(), TinkError> {
     if key.key_id == 0 {
         Err(format!("key has zero key id: {}", key.key_id).into())
     } else if key.key_data.is_none() {
@@ -19,19 +19,35 @@
 #![deny(broken_intra_doc_links)]
 
 pub mod cryptofmt;
+pub mod fips;
 pub mod keyset;
+pub mod monitoring;
 pub mod primitiveset;
 pub mod proto {
     //! Auto-generated code from protocol buffer message definitions.
+    //!
+    //! Messages that hold secret key material (such as [`KeyData`] and the various key types)
+    //! zeroize their contents when dropped, and redact their key material in `Debug` output.
+    // Derived `Drop` implementations for secret-bearing messages need the trait in scope.
+    use zeroize::Zeroize;
+    #[macro_use]
+    mod secret;
     #[cfg(not(feature = "json"))]
     include!("codegen/google.crypto.tink.rs");
     #[cfg(feature = "json")]
     include!("codegen/serde/google.crypto.tink.rs");
+    // Messages that are specific to this project, in the `tink_rust` package.
+    #[cfg(not(feature = "json"))]
+    include!("codegen/tink_rust.rs");
+    #[cfg(feature = "json")]
+    include!("codegen/serde/tink_rust.rs");
+    mod debug;
 }
 pub mod registry;
 pub mod subtle;
 pub mod utils;
-pub use utils::TinkError;
+use std::{any::Any, sync::Arc};
+pub use utils::{ErrorKind, TinkError};
 
 /// The [upstream Tink](https://github.com/google/tink) version that this Rust
 /// port is based on.
@@ -61,6 +77,62 @@ mod verifier;
 pub use verifier::*;
 
 /// The primitives available in Tink.
+///
+/// All primitive traits require `Send + Sync`, so primitives can be shared between threads
+/// (e.g. by converting a `Box<dyn Aead>` into an `Arc<dyn Aead>`).
+///
+/// # Custom primitives
+///
+/// Primitives that are not defined by Tink itself are held in the [`Primitive::Custom`]
+/// variant, which allows a third-party crate to use keysets, key rotation and
+/// [`PrimitiveSet`](primitiveset::PrimitiveSet)s for its own primitive type.  Such a crate:
+///
+/// - implements a [`KeyManager`](registry::KeyManager) whose `primitive()` method returns
+///   [`Primitive::custom`] holding the primitive for a key (typically as a boxed trait object);
+/// - implements a [`PrimitiveWrapper`](registry::PrimitiveWrapper) that extracts the per-key
+///   primitives from the [`PrimitiveSet`](primitiveset::PrimitiveSet) with
+///   [`Primitive::downcast_ref`], and combines them into a primitive for the whole keyset;
+/// - registers both in its `init()` function, after which
+///   [`Handle::primitive`](keyset::Handle::primitive) builds the keyset primitive.
+///
+/// ```
+/// use std::sync::Arc;
+/// use tink::{primitiveset::PrimitiveSet, registry::PrimitiveWrapper, TinkError};
+///
+/// /// Primitive defined outside Tink.
+/// pub trait BlindIndex: Send + Sync {
+///     fn index(&self, data: &[u8]) -> Result<Vec<u8>, TinkError>;
+/// }
+///
+/// /// Keyset primitive that indexes with the primary key.
+/// struct WrappedBlindIndex {
+///     primary: Arc<dyn BlindIndex>,
+/// }
+///
+/// impl BlindIndex for WrappedBlindIndex {
+///     fn index(&self, data: &[u8]) -> Result<Vec<u8>, TinkError> {
+///         self.primary.index(data)
+///     }
+/// }
+///
+/// pub struct BlindIndexWrapper;
+///
+/// impl PrimitiveWrapper for BlindIndexWrapper {
+///     type Primitive = dyn BlindIndex;
+///
+///     fn wrap(&self, ps: PrimitiveSet) -> Result<Box<dyn BlindIndex>, TinkError> {
+///         let primary = ps.primary.as_ref().ok_or("blind_index: no primary")?;
+///         // The key manager returns `Primitive::custom::<Arc<dyn BlindIndex>>(..)`.
+///         let primary = primary
+///             .primitive
+///             .downcast_ref::<Arc<dyn BlindIndex>>()
+///             .ok_or("blind_index: not a BlindIndex primitive")?;
+///         Ok(Box::new(WrappedBlindIndex {
+///             primary: primary.clone(),
+///         }))
+///     }
+/// }
+/// ```
 pub enum Primitive {
     Aead(Box<dyn Aead>),
     DeterministicAead(Box<dyn DeterministicAead>),
@@ -71,6 +143,30 @@ pub enum Primitive {
     Signer(Box<dyn Signer>),
     StreamingAead(Box<dyn StreamingAead>),
     Verifier(Box<dyn Verifier>),
+    /// A primitive of a type defined outside Tink.  The primitive is shared (rather than boxed)
+    /// so that it can be cloned without knowledge of its type.
+    Custom(Arc<dyn Any + Send + Sync>),
+}
+
+impl Primitive {
+    /// Create a [`Primitive::Custom`] holding `p`.
+    pub fn custom<T: Any + Send + Sync>(p: T) -> Self {
+        Primitive::Custom(Arc::new(p))
+    }
+
+    /// Return a reference to the contained custom primitive if it has type `T`, or `None` if
+    /// it has a different type or is not a custom primitive.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        match self {
+            Primitive::Custom(p) => p.downcast_ref::<T>(),
+            _ => None,
+        }
+    }
+
+    /// Indicate whether this is a custom primitive of type `T`.
+    pub fn is_custom<T: Any>(&self) -> bool {
+        self.downcast_ref::<T>().is_some()
+    }
 }
 
 /// Manual implementation of the [`Clone`] trait, which makes use of the trait bounds
@@ -88,6 +184,7 @@ impl Clone for Primitive {
             Primitive::Signer(p) => Primitive::Signer(p.box_clone()),
             Primitive::StreamingAead(p) => Primitive::StreamingAead(p.box_clone()),
             Primitive::Verifier(p) => Primitive::Verifier(p.box_clone()),
+            Primitive::Custom(p) => Primitive::Custom(p.clone()),
         }
     }
 }
@@ -19,7 +19,7 @@
 /// `Mac` is the interface for MACs (Message Authentication Codes).
 /// This interface should be used for authentication only, and not for other purposes
 /// (for example, it should not be used to generate pseudorandom bytes).
-pub trait Mac: MacBoxClone {
+pub trait Mac: MacBoxClone + Send + Sync {
     // Compute message authentication code (MAC) for code data.
     fn compute_mac(&self, data: &[u8]) -> Result<Vec<u8>, crate::TinkError>;
 
@@ -30,7 +30,96 @@ pub trait Mac: MacBoxClone {
         if crate::subtle::constant_time_compare(mac, &computed) {
             Ok(())
         } else {
-            Err("Invalid MAC".into())
+            Err(crate::TinkError::new_with_kind(
+                crate::ErrorKind::Authentication,
+                "Invalid MAC",
+            ))
+        }
+    }
+
+    /// Start an incremental computation of a MAC, for data that is provided in chunks.  The
+    /// result is the same as [`compute_mac`](Mac::compute_mac) over the concatenated chunks.
+    ///
+    /// The default implementation buffers all of the data and uses `compute_mac`.
+    fn new_computation(&self) -> Result<Box<dyn MacComputation>, crate::TinkError> {
+        Ok(Box::new(BufferedMacComputation {
+            mac: self.box_clone(),
+            data: Vec::new(),
+        }))
+    }
+
+    /// Start an incremental verification that `mac` is a correct authentication code for data
+    /// that is provided in chunks.
+    ///
+    /// The default implementation compares `mac` with the result of a
+    /// [`new_computation`](Mac::new_computation).
+    fn new_verification(&self, mac: &[u8]) -> Result<Box<dyn MacVerification>, crate::TinkError> {
+        Ok(Box::new(ComputedMacVerification {
+            computation: self.new_computation()?,
+            mac: mac.to_vec(),
+        }))
+    }
+}
+
+/// `MacComputation` holds the state of an incremental MAC computation, as returned by
+/// [`Mac::new_computation`].
+pub trait MacComputation: Send {
+    /// Process the next chunk of data.
+    fn update(&mut self, data: &[u8]) -> Result<(), crate::TinkError>;
+
+    /// Return the MAC of all the data processed so far.
+    fn compute_mac(self: Box<Self>) -> Result<Vec<u8>, crate::TinkError>;
+}
+
+/// `MacVerification` holds the state of an incremental MAC verification, as returned by
+/// [`Mac::new_verification`].
+pub trait MacVerification: Send {
+    /// Process the next chunk of data.
+    fn update(&mut self, data: &[u8]) -> Result<(), crate::TinkError>;
+
+    /// Return `()` if the MAC is a correct authentication code for all of the data processed so
+    /// far, otherwise return an error.
+    fn verify_mac(self: Box<Self>) -> Result<(), crate::TinkError>;
+}
+
+/// [`MacComputation`] that accumulates all of the data for a single call to
+/// [`Mac::compute_mac`].
+struct BufferedMacComputation {
+    mac: Box<dyn Mac>,
+    data: Vec<u8>,
+}
+
+impl MacComputation for BufferedMacComputation {
+    fn update(&mut self, data: &[u8]) -> Result<(), crate::TinkError> {
+        self.data.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn compute_mac(self: Box<Self>) -> Result<Vec<u8>, crate::TinkError> {
+        self.mac.compute_mac(&self.data)
+    }
+}
+
+/// [`MacVerification`] that compares the expected MAC against an incremental computation.
+struct ComputedMacVerification {
+    computation: Box<dyn MacComputation>,
+    mac: Vec<u8>,
+}
+
+impl MacVerification for ComputedMacVerification {
+    fn update(&mut self, data: &[u8]) -> Result<(), crate::TinkError> {
+        self.computation.update(data)
+    }
+
+    fn verify_mac(self: Box<Self>) -> Result<(), crate::TinkError> {
+        let computed = self.computation.compute_mac()?;
+        if crate::subtle::constant_time_compare(&self.mac, &computed) {
+            Ok(())
+        } else {
+            Err(crate::TinkError::new_with_kind(
+                crate::ErrorKind::Authentication,
+                "Invalid MAC",
+            ))
         }
     }
 }
@@ -0,0 +1,262 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Monitoring of primitive usage.
+//!
+//! The keyset wrappers for each primitive (as returned by the primitive factories) report every
+//! operation that they perform to a [`Logger`], which is obtained from the registered
+//! [`MonitoringClient`].  This makes it possible to find out which keys in a keyset are actually
+//! in use, for example before retiring an old key.  Streaming primitives report a single event
+//! for each stream, with the total number of bytes processed.
+//!
+//! Monitoring is only performed for keysets that have been annotated (with
+//! [`Handle::with_annotations`](crate::keyset::Handle::with_annotations)), and only once a
+//! client has been registered with [`register_client`].  An [`InMemoryClient`] that counts the
+//! events it receives is provided.
+
+use crate::{primitiveset::PrimitiveSet, KeyId, TinkError};
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, RwLock},
+};
+
+/// Information about a key in a monitored keyset.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyInfo {
+    pub key_id: KeyId,
+    pub status: crate::proto::KeyStatusType,
+    /// Type URL of the key.
+    pub key_type: String,
+    pub prefix_type: crate::proto::OutputPrefixType,
+}
+
+/// Information about a monitored keyset, which holds no key material.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeysetInfo {
+    /// Annotations attached to the keyset handle.
+    pub annotations: HashMap<String, String>,
+    pub primary_key_id: KeyId,
+    /// Information about the (enabled) keys in the keyset, in key ID order.
+    pub entries: Vec<KeyInfo>,
+}
+
+impl KeysetInfo {
+    /// Build the monitoring information for the keys in a [`PrimitiveSet`].
+    pub fn from_primitive_set(ps: &PrimitiveSet) -> Self {
+        let mut entries: Vec<KeyInfo> = ps
+            .entries()
+            .values()
+            .flatten()
+            .map(|entry| KeyInfo {
+                key_id: entry.key_id,
+                status: entry.status,
+                key_type: entry.type_url.clone(),
+                prefix_type: entry.prefix_type,
+            })
+            .collect();
+        entries.sort_by_key(|info| info.key_id);
+        Self {
+            annotations: ps.annotations.clone(),
+            primary_key_id: ps.primary.as_ref().map_or(0, |entry| entry.key_id),
+            entries,
+        }
+    }
+}
+
+/// Context in which a [`Logger`] is used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Context {
+    /// Name of the primitive, e.g. "aead" or "mac".
+    pub primitive: &'static str,
+    /// Name of the operation, e.g. "encrypt" or "verify".
+    pub api_function: &'static str,
+    pub keyset_info: KeysetInfo,
+}
+
+/// A `Logger` receives the events for a particular operation of a monitored primitive.
+pub trait Logger: Send + Sync {
+    /// Record a successful operation using the key with ID `key_id`, processing `num_bytes`
+    /// bytes of input.
+    fn log(&self, key_id: KeyId, num_bytes: usize);
+
+    /// Record a failed operation.
+    fn log_failure(&self);
+}
+
+/// A `MonitoringClient` creates a [`Logger`] for each [`Context`] that is monitored.
+pub trait MonitoringClient: Send + Sync {
+    /// Create a logger for the given `context`.
+    fn new_logger(&self, context: &Context) -> Result<Arc<dyn Logger>, TinkError>;
+}
+
+/// [`Logger`] that discards all events.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoOpLogger;
+
+impl Logger for NoOpLogger {
+    fn log(&self, _key_id: KeyId, _num_bytes: usize) {}
+    fn log_failure(&self) {}
+}
+
+lazy_static! {
+    /// Global monitoring client.
+    static ref CLIENT: RwLock<Option<Arc<dyn MonitoringClient>>> = RwLock::new(None);
+}
+
+/// Error message for global monitoring client lock.
+const CERR: &str = "global monitoring client lock poisoned";
+
+/// Error message for in-memory events lock.
+const EERR: &str = "monitoring events lock poisoned";
+
+/// Register the monitoring client to use for primitives created subsequently, replacing any
+/// previously registered client.
+pub fn register_client(client: Arc<dyn MonitoringClient>) {
+    *CLIENT.write().expect(CERR) = Some(client); // safe: lock
+}
+
+/// Return a [`Logger`] for the `api_function` operation of the `primitive` built from the given
+/// primitive set.  This is a [`NoOpLogger`] if the keyset is not annotated or no client is
+/// registered.
+pub fn new_logger(
+    ps: &PrimitiveSet,
+    primitive: &'static str,
+    api_function: &'static str,
+) -> Result<Arc<dyn Logger>, TinkError> {
+    if ps.annotations.is_empty() {
+        return Ok(Arc::new(NoOpLogger));
+    }
+    let client = CLIENT.read().expect(CERR).clone(); // safe: lock
+    let client = match client {
+        Some(client) => client,
+        None => return Ok(Arc::new(NoOpLogger)),
+    };
+    client.new_logger(&Context {
+        primitive,
+        api_function,
+        keyset_info: KeysetInfo::from_primitive_set(ps),
+    })
+}
+
+/// An event recorded by an [`InMemoryClient`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub primitive: &'static str,
+    pub api_function: &'static str,
+    pub annotations: HashMap<String, String>,
+    /// Key used for a successful operation, or `None` for a failure.
+    pub key_id: Option<KeyId>,
+    pub num_bytes: usize,
+}
+
+/// [`MonitoringClient`] that keeps all events in memory, and which provides per-key usage counts.
+///
+/// Clones of an `InMemoryClient` share the same underlying events.
+#[derive(Clone, Default)]
+pub struct InMemoryClient {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+impl InMemoryClient {
+    /// Create a new client with no events.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return all events recorded so far.
+    pub fn events(&self) -> Vec<Event> {
+        self.events.lock().expect(EERR).clone() // safe: lock
+    }
+
+    /// Return the number of successful operations that used the key with ID `key_id`.
+    pub fn key_count(&self, key_id: KeyId) -> usize {
+        self.events
+            .lock()
+            .expect(EERR) // safe: lock
+            .iter()
+            .filter(|e| e.key_id == Some(key_id))
+            .count()
+    }
+
+    /// Return the total number of bytes processed by successful operations that used the key
+    /// with ID `key_id`.
+    pub fn key_bytes(&self, key_id: KeyId) -> usize {
+        self.events
+            .lock()
+            .expect(EERR) // safe: lock
+            .iter()
+            .filter(|e| e.key_id == Some(key_id))
+            .map(|e| e.num_bytes)
+            .sum()
+    }
+
+    /// Return the number of failed operations.
+    pub fn failure_count(&self) -> usize {
+        self.events
+            .lock()
+            .expect(EERR) // safe: lock
+            .iter()
+            .filter(|e| e.key_id.is_none())
+            .count()
+    }
+
+    /// Discard all events recorded so far.
+    pub fn clear(&self) {
+        self.events.lock().expect(EERR).clear() // safe: lock
+    }
+}
+
+impl MonitoringClient for InMemoryClient {
+    fn new_logger(&self, context: &Context) -> Result<Arc<dyn Logger>, TinkError> {
+        Ok(Arc::new(InMemoryLogger {
+            primitive: context.primitive,
+            api_function: context.api_function,
+            annotations: context.keyset_info.annotations.clone(),
+            events: self.events.clone(),
+        }))
+    }
+}
+
+/// [`Logger`] created by an [`InMemoryClient`].
+struct InMemoryLogger {
+    primitive: &'static str,
+    api_function: &'static str,
+    annotations: HashMap<String, String>,
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+impl InMemoryLogger {
+    fn record(&self, key_id: Option<KeyId>, num_bytes: usize) {
+        self.events.lock().expect(EERR).push(Event {
+            primitive: self.primitive,
+            api_function: self.api_function,
+            annotations: self.annotations.clone(),
+            key_id,
+            num_bytes,
+        }); // safe: lock
+    }
+}
+
+impl Logger for InMemoryLogger {
+    fn log(&self, key_id: KeyId, num_bytes: usize) {
+        self.record(Some(key_id), num_bytes);
+    }
+
+    fn log_failure(&self) {
+        self.record(None, 0);
+    }
+}
@@ -32,7 +32,7 @@
 /// verification, avoiding the security problems that often happen during
 /// verification, and having automatic support for key rotation. It also allows
 /// for non-deterministic MAC algorithms.
-pub trait Prf: PrfBoxClone {
+pub trait Prf: PrfBoxClone + Send + Sync {
     /// Compute the PRF selected by the underlying key on input and
     /// returns the first `output_length` bytes.
     /// When choosing this parameter keep the birthday paradox in mind.
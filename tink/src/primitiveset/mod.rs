@@ -20,11 +20,14 @@
 //! particular, one of the primitives in the set can be distinguished as "the
 //! primary" one.
 
-use crate::utils::{wrap_err, TinkError};
-use std::collections::{hash_map, HashMap};
+use crate::{
+    utils::{wrap_err, TinkError},
+    ErrorKind,
+};
+use std::{collections::HashMap, sync::Arc};
 
 /// `Entry` represents a single entry in the keyset. In addition to the actual
-/// primitive, it holds the identifier and status of the primitive.
+/// primitive, it holds the identifier, status and type of the primitive.
 #[derive(Clone)]
 pub struct Entry {
     pub key_id: crate::KeyId,
@@ -32,6 +35,7 @@ pub struct Entry {
     pub prefix: Vec<u8>,
     pub prefix_type: crate::proto::OutputPrefixType,
     pub status: crate::proto::KeyStatusType,
+    pub type_url: String,
 }
 
 impl Entry {
@@ -41,6 +45,7 @@ impl Entry {
         prefix: &[u8],
         prefix_type: crate::proto::OutputPrefixType,
         status: crate::proto::KeyStatusType,
+        type_url: &str,
     ) -> Self {
         Entry {
             key_id,
@@ -48,6 +53,7 @@ impl Entry {
             prefix: prefix.to_vec(),
             prefix_type,
             status,
+            type_url: type_url.to_string(),
         }
     }
 }
@@ -63,15 +69,28 @@ impl Entry {
 ///
 /// `PrimitiveSet` is public to allow its use in implementations of custom
 /// primitives.
+///
+/// Entries are held behind an [`Arc`], so lookups hand out shared references
+/// to the stored entries rather than copies of them; this keeps per-message
+/// operations in the wrapped primitives free of allocation.
 #[derive(Clone, Default)]
 pub struct PrimitiveSet {
-    // Copy of the primary entry in `entries`.
-    pub primary: Option<Entry>,
+    /// Shared reference to the primary entry, which should be one of the entries returned by
+    /// [`add`](PrimitiveSet::add).
+    pub primary: Option<Arc<Entry>>,
 
     // The primitives are stored in a map of (ciphertext prefix, list of
     // primitives sharing the prefix). This allows quickly retrieving the
-    // primitives sharing some particular prefix.
-    pub entries: HashMap<Vec<u8>, Vec<Entry>>,
+    // primitives sharing some particular prefix.  Only modified by `add`, so
+    // that it stays consistent with `by_key_id`.
+    entries: HashMap<Vec<u8>, Vec<Arc<Entry>>>,
+
+    // Index of the same entries by key ID, maintained by `add`.
+    by_key_id: HashMap<crate::KeyId, Vec<Arc<Entry>>>,
+
+    // Annotations of the keyset that the primitives were created from, used for
+    // [monitoring](crate::monitoring).
+    pub annotations: HashMap<String, String>,
 }
 
 impl PrimitiveSet {
@@ -80,49 +99,71 @@ impl PrimitiveSet {
         PrimitiveSet {
             primary: None,
             entries: HashMap::new(),
+            by_key_id: HashMap::new(),
+            annotations: HashMap::new(),
         }
     }
 
+    /// Return all primitives in the set, grouped by their prefix.
+    pub fn entries(&self) -> &HashMap<Vec<u8>, Vec<Arc<Entry>>> {
+        &self.entries
+    }
+
     /// Return all primitives in the set that have RAW prefix.
-    pub fn raw_entries(&self) -> Vec<Entry> {
+    pub fn raw_entries(&self) -> &[Arc<Entry>] {
         self.entries_for_prefix(&crate::cryptofmt::RAW_PREFIX)
     }
 
     /// Return all primitives in the set that have the given prefix.
-    pub fn entries_for_prefix(&self, prefix: &[u8]) -> Vec<Entry> {
+    pub fn entries_for_prefix(&self, prefix: &[u8]) -> &[Arc<Entry>] {
         match self.entries.get(prefix) {
-            Some(v) => v.clone(),
-            None => Vec::new(),
+            Some(v) => v,
+            None => &[],
+        }
+    }
+
+    /// Return all primitives in the set that were created from a key with the given key ID.
+    pub fn entries_for_key_id(&self, key_id: crate::KeyId) -> &[Arc<Entry>] {
+        match self.by_key_id.get(&key_id) {
+            Some(v) => v,
+            None => &[],
         }
     }
 
-    /// Create a new entry in the primitive set and returns a copy of the added entry.
+    /// Create a new entry in the primitive set and returns a shared reference to the added
+    /// entry.
     pub fn add(
         &mut self,
         p: crate::Primitive,
         key: &crate::proto::keyset::Key,
-    ) -> Result<Entry, TinkError> {
+    ) -> Result<Arc<Entry>, TinkError> {
         if key.status != crate::proto::KeyStatusType::Enabled as i32 {
-            return Err("The key must be ENABLED".into());
+            return Err(TinkError::new_with_kind(
+                ErrorKind::InvalidArgument,
+                "The key must be ENABLED",
+            ));
         }
         let prefix =
             crate::cryptofmt::output_prefix(key).map_err(|e| wrap_err("primitiveset", e))?;
-        let entry = Entry::new(
+        let entry = Arc::new(Entry::new(
             key.key_id,
             p,
             &prefix,
-            crate::proto::OutputPrefixType::from_i32(key.output_prefix_type)
-                .ok_or_else(|| TinkError::new("invalid key prefix type"))?,
-            crate::proto::KeyStatusType::from_i32(key.status)
-                .ok_or_else(|| TinkError::new("invalid key status"))?,
-        );
-        let retval = entry.clone();
-        match self.entries.entry(prefix) {
-            hash_map::Entry::Occupied(mut oe) => oe.get_mut().push(entry),
-            hash_map::Entry::Vacant(ve) => {
-                ve.insert(vec![entry]);
-            }
-        };
-        Ok(retval)
+            crate::proto::OutputPrefixType::from_i32(key.output_prefix_type).ok_or_else(|| {
+                TinkError::new_with_kind(ErrorKind::InvalidKeyset, "invalid key prefix type")
+            })?,
+            crate::proto::KeyStatusType::from_i32(key.status).ok_or_else(|| {
+                TinkError::new_with_kind(ErrorKind::InvalidKeyset, "invalid key status")
+            })?,
+            key.key_data
+                .as_ref()
+                .map_or("", |key_data| key_data.type_url.as_str()),
+        ));
+        self.by_key_id
+            .entry(entry.key_id)
+            .or_default()
+            .push(entry.clone());
+        self.entries.entry(prefix).or_default().push(entry.clone());
+        Ok(entry)
     }
 }
@@ -0,0 +1,105 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! `Debug` implementations for generated messages that hold secret key material, which are
+//! generated without `#[derive(prost::Message)]` (see `SECRET_MESSAGES` in `build.rs`, and
+//! `secret.rs`).
+
+use super::*;
+use crate::utils::{DebugEnum, SecretBytes};
+
+/// Format a single field of a message, according to how it is marked.
+macro_rules! debug_field {
+    ($value:expr) => {
+        $value
+    };
+    ($value:expr, secret) => {
+        SecretBytes($value)
+    };
+    ($value:expr, enumeration<$ty:ty>) => {
+        DebugEnum(*$value, <$ty>::from_i32)
+    };
+}
+
+/// Implement `Debug` for messages, listing every field (so that adding a field to one of the
+/// messages without updating its entry here fails to compile).  Fields marked `secret` are
+/// redacted, and fields marked `enumeration<T>` are shown as the corresponding enum value.
+macro_rules! secret_debug {
+    ($($name:ident { $($field:ident $(: $mark:ident $(<$ty:ty>)?)?),* $(,)? })*) => {
+        $(
+            impl std::fmt::Debug for $name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    let $name { $($field),* } = self;
+                    f.debug_struct(stringify!($name))
+                        $(.field(stringify!($field), &debug_field!($field $(, $mark $(<$ty>)?)?)))*
+                        .finish()
+                }
+            }
+        )*
+    };
+}
+
+secret_debug! {
+    AeadWithNonceKey { version, aead_type_url, aead_key: secret }
+    AesCmacKey { version, key_value: secret, params }
+    AesCmacPrfKey { version, key_value: secret }
+    AesCtrHmacAeadKey { version, aes_ctr_key, hmac_key }
+    AesCtrHmacStreamingKey { version, params, key_value: secret }
+    AesCtrKey { version, params, key_value: secret }
+    AesEaxKey { version, params, key_value: secret }
+    AesGcmCommittingKey { version, key_value: secret }
+    AesGcmHkdfStreamingKey { version, params, key_value: secret }
+    AesGcmKey { version, key_value: secret }
+    AesGcmSivKey { version, key_value: secret }
+    AesSivKey { version, key_value: secret }
+    ChaCha20Poly1305Key { version, key_value: secret }
+    EciesAeadHkdfPrivateKey { version, public_key, key_value: secret }
+    EcdsaPrivateKey { version, public_key, key_value: secret }
+    Ed25519PrivateKey { version, key_value: secret, public_key }
+    HkdfPrfKey { version, params, key_value: secret }
+    HmacKey { version, params, key_value: secret }
+    HmacPrfKey { version, params, key_value: secret }
+    JwtHmacKey { version, hash_type: enumeration<HashType>, key_value: secret }
+    KeyData {
+        type_url,
+        value: secret,
+        key_material_type: enumeration<key_data::KeyMaterialType>,
+    }
+    Keyset { primary_key_id, key }
+    PrfBasedDeriverKey { version, prf_key, params }
+    RsaSsaPkcs1PrivateKey {
+        version,
+        public_key,
+        d: secret,
+        p: secret,
+        q: secret,
+        dp: secret,
+        dq: secret,
+        crt: secret,
+    }
+    RsaSsaPssPrivateKey {
+        version,
+        public_key,
+        d: secret,
+        p: secret,
+        q: secret,
+        dp: secret,
+        dq: secret,
+        crt: secret,
+    }
+    XAesGcmKey { version, params, key_value: secret }
+    XChaCha20Poly1305Key { version, key_value: secret }
+}
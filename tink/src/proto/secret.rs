@@ -0,0 +1,169 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! `prost::Message` implementations for generated messages that hold secret key material.
+//!
+//! `#[derive(prost::Message)]` (in `prost-derive` 0.6) always emits a `Debug` implementation that
+//! shows every field, so `build.rs` removes the derive (and its `#[prost(..)]` field attributes)
+//! from the messages listed in `SECRET_MESSAGES`, and emits a `secret_message!` invocation for
+//! each of them instead.  Their redacting `Debug` implementations are in `debug.rs`.
+
+/// Implement `prost::Message` and `Default` for a message, in the same way as
+/// `#[derive(prost::Message)]` but without implementing `Debug`.  Each field is listed with its
+/// protobuf kind and tag, in tag order:
+///
+/// - `bytes`, `string` or `uint32` for scalar fields.
+/// - `enumeration(Type, set_field)` for enum fields, which also get the same accessor methods
+///   as derived messages.
+/// - `message` for (optional) message fields, and `repeated_message` for repeated ones.
+macro_rules! secret_message {
+    ($name:ident { $($field:ident: $kind:ident $(($($arg:tt)*))? = $tag:literal),* $(,)? }) => {
+        impl ::prost::Message for $name {
+            #[allow(unused_variables)]
+            fn encode_raw<B>(&self, buf: &mut B)
+            where
+                B: ::prost::bytes::BufMut,
+            {
+                $(secret_field!(encode $kind, self.$field, $tag, buf);)*
+            }
+
+            #[allow(unused_variables)]
+            fn merge_field<B>(
+                &mut self,
+                tag: u32,
+                wire_type: ::prost::encoding::WireType,
+                buf: &mut B,
+                ctx: ::prost::encoding::DecodeContext,
+            ) -> ::std::result::Result<(), ::prost::DecodeError>
+            where
+                B: ::prost::bytes::Buf,
+            {
+                match tag {
+                    $(
+                        $tag => secret_field!(merge $kind, self.$field, wire_type, buf, ctx)
+                            .map_err(|mut error| {
+                                error.push(stringify!($name), stringify!($field));
+                                error
+                            }),
+                    )*
+                    _ => ::prost::encoding::skip_field(wire_type, tag, buf, ctx),
+                }
+            }
+
+            #[inline]
+            fn encoded_len(&self) -> usize {
+                0 $(+ secret_field!(encoded_len $kind, self.$field, $tag))*
+            }
+
+            fn clear(&mut self) {
+                *self = Self::default();
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> $name {
+                $name {
+                    $($field: Default::default(),)*
+                }
+            }
+        }
+
+        $(secret_field!(methods $name, $field, $kind $(($($arg)*))?);)*
+    };
+}
+
+/// Generate the code for a single field of a `secret_message!`.
+macro_rules! secret_field {
+    (encode message, $value:expr, $tag:literal, $buf:ident) => {
+        if let Some(ref msg) = $value {
+            ::prost::encoding::message::encode($tag, msg, $buf);
+        }
+    };
+    (encode repeated_message, $value:expr, $tag:literal, $buf:ident) => {
+        ::prost::encoding::message::encode_repeated($tag, &$value, $buf)
+    };
+    (encode enumeration, $value:expr, $tag:literal, $buf:ident) => {
+        secret_field!(encode int32, $value, $tag, $buf)
+    };
+    (encode $kind:ident, $value:expr, $tag:literal, $buf:ident) => {
+        if !secret_field!(is_default $kind, $value) {
+            ::prost::encoding::$kind::encode($tag, &$value, $buf);
+        }
+    };
+
+    (merge message, $value:expr, $wire_type:ident, $buf:ident, $ctx:ident) => {
+        ::prost::encoding::message::merge(
+            $wire_type,
+            $value.get_or_insert_with(Default::default),
+            $buf,
+            $ctx,
+        )
+    };
+    (merge repeated_message, $value:expr, $wire_type:ident, $buf:ident, $ctx:ident) => {
+        ::prost::encoding::message::merge_repeated($wire_type, &mut $value, $buf, $ctx)
+    };
+    (merge enumeration, $value:expr, $wire_type:ident, $buf:ident, $ctx:ident) => {
+        secret_field!(merge int32, $value, $wire_type, $buf, $ctx)
+    };
+    (merge $kind:ident, $value:expr, $wire_type:ident, $buf:ident, $ctx:ident) => {
+        ::prost::encoding::$kind::merge($wire_type, &mut $value, $buf, $ctx)
+    };
+
+    (encoded_len message, $value:expr, $tag:literal) => {
+        $value
+            .as_ref()
+            .map_or(0, |msg| ::prost::encoding::message::encoded_len($tag, msg))
+    };
+    (encoded_len repeated_message, $value:expr, $tag:literal) => {
+        ::prost::encoding::message::encoded_len_repeated($tag, &$value)
+    };
+    (encoded_len enumeration, $value:expr, $tag:literal) => {
+        secret_field!(encoded_len int32, $value, $tag)
+    };
+    (encoded_len $kind:ident, $value:expr, $tag:literal) => {
+        if secret_field!(is_default $kind, $value) {
+            0
+        } else {
+            ::prost::encoding::$kind::encoded_len($tag, &$value)
+        }
+    };
+
+    (is_default bytes, $value:expr) => {
+        $value.is_empty()
+    };
+    (is_default string, $value:expr) => {
+        $value.is_empty()
+    };
+    (is_default $kind:ident, $value:expr) => {
+        $value == 0
+    };
+
+    (methods $name:ident, $field:ident, enumeration($ty:path, $set:ident)) => {
+        impl $name {
+            /// Returns the enum value of the field, or the default if the field is set to an
+            /// invalid enum value.
+            pub fn $field(&self) -> $ty {
+                <$ty>::from_i32(self.$field).unwrap_or_default()
+            }
+
+            /// Sets the field to the provided enum value.
+            pub fn $set(&mut self, value: $ty) {
+                self.$field = value as i32;
+            }
+        }
+    };
+    (methods $name:ident, $field:ident, $kind:ident) => {};
+}
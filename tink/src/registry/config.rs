@@ -0,0 +1,197 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Registry configuration from [`RegistryConfig`] protocol buffers.
+
+use super::{KeyManager, Registry};
+use crate::{proto::RegistryConfig, utils::wrap_err, ErrorKind, TinkError};
+use std::sync::Arc;
+
+/// Parse a [`RegistryConfig`] from its binary protobuf encoding.
+pub fn config_from_binary(data: &[u8]) -> Result<RegistryConfig, TinkError> {
+    prost::Message::decode(data)
+        .map_err(|e| wrap_err("registry: failed to decode RegistryConfig", e))
+}
+
+/// Parse a [`RegistryConfig`] from its JSON encoding (requires activation of the `json`
+/// feature), for example:
+///
+/// ```json
+/// {
+///   "configName": "production",
+///   "entry": [
+///     {
+///       "primitiveName": "Aead",
+///       "typeUrl": "type.googleapis.com/google.crypto.tink.AesGcmKey",
+///       "keyManagerVersion": 0,
+///       "newKeyAllowed": true
+///     }
+///   ]
+/// }
+/// ```
+#[cfg(feature = "json")]
+pub fn config_from_json(data: &[u8]) -> Result<RegistryConfig, TinkError> {
+    serde_json::from_slice(data)
+        .map_err(|e| wrap_err("registry: failed to parse RegistryConfig", e))
+}
+
+impl Registry {
+    /// Return a copy of this registry that only contains the key managers listed in `config`.
+    ///
+    /// Each [`KeyTypeEntry`](crate::proto::KeyTypeEntry) in the configuration must refer to a
+    /// registered key manager for its `type_url`, whose
+    /// [`primitive_name`](KeyManager::primitive_name) matches the entry's `primitive_name`
+    /// (case-insensitively) and whose [`version`](KeyManager::version) is at least the entry's
+    /// `key_manager_version`.  If
+    /// `new_key_allowed` is false, the key manager can only be used to create primitives for
+    /// existing keys.  The `catalogue_name` must be empty or refer to Tink's own key managers
+    /// (e.g. "Tink" or "TinkAead").
+    ///
+    /// Errors have [`ErrorKind::Unsupported`] if the configuration refers to a key type that is
+    /// unavailable, and [`ErrorKind::InvalidArgument`] if it is malformed.
+    pub fn with_config(&self, config: &RegistryConfig) -> Result<Self, TinkError> {
+        let mut registry = Registry {
+            kms_clients: self.kms_clients.clone(),
+            wrappers: self.wrappers.clone(),
+            fips: self.fips,
+            ..Registry::default()
+        };
+        for entry in &config.entry {
+            if entry.type_url.is_empty() {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidArgument,
+                    "registry: config entry without type URL",
+                ));
+            }
+            let catalogue = entry.catalogue_name.to_lowercase();
+            if !catalogue.is_empty() && !catalogue.starts_with("tink") {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::Unsupported,
+                    &format!(
+                        "registry: unsupported catalogue {} for key type {}",
+                        entry.catalogue_name, entry.type_url
+                    ),
+                ));
+            }
+            let km = self.get_key_manager(&entry.type_url)?;
+            if !entry
+                .primitive_name
+                .eq_ignore_ascii_case(km.primitive_name())
+            {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidArgument,
+                    &format!(
+                        "registry: key type {} is for primitive '{}', not '{}'",
+                        entry.type_url,
+                        km.primitive_name(),
+                        entry.primitive_name
+                    ),
+                ));
+            }
+            if km.version() < entry.key_manager_version {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::Unsupported,
+                    &format!(
+                        "registry: key manager for {} has version {}, config requires {}",
+                        entry.type_url,
+                        km.version(),
+                        entry.key_manager_version
+                    ),
+                ));
+            }
+            let km: Arc<dyn KeyManager> = if entry.new_key_allowed {
+                km
+            } else {
+                Arc::new(NoNewKeyManager(km))
+            };
+            if registry.key_managers.insert(km.type_url(), km).is_some() {
+                return Err(TinkError::new_with_kind(
+                    ErrorKind::InvalidArgument,
+                    &format!("registry: duplicate config entry for {}", entry.type_url),
+                ));
+            }
+        }
+        Ok(registry)
+    }
+}
+
+/// Wrapper for a [`KeyManager`] that refuses to generate new keys.
+struct NoNewKeyManager(Arc<dyn KeyManager>);
+
+impl NoNewKeyManager {
+    fn err(&self) -> TinkError {
+        TinkError::new_with_kind(
+            ErrorKind::Unsupported,
+            &format!(
+                "registry: creation of new keys of type {} is not allowed",
+                self.0.type_url()
+            ),
+        )
+    }
+}
+
+impl KeyManager for NoNewKeyManager {
+    fn primitive(&self, serialized_key: &[u8]) -> Result<crate::Primitive, TinkError> {
+        self.0.primitive(serialized_key)
+    }
+
+    fn new_key(&self, _serialized_key_format: &[u8]) -> Result<Vec<u8>, TinkError> {
+        Err(self.err())
+    }
+
+    fn does_support(&self, type_url: &str) -> bool {
+        self.0.does_support(type_url)
+    }
+
+    fn type_url(&self) -> &'static str {
+        self.0.type_url()
+    }
+
+    fn key_material_type(&self) -> crate::proto::key_data::KeyMaterialType {
+        self.0.key_material_type()
+    }
+
+    fn version(&self) -> u32 {
+        self.0.version()
+    }
+
+    fn primitive_name(&self) -> &'static str {
+        self.0.primitive_name()
+    }
+
+    fn new_key_data(
+        &self,
+        _serialized_key_format: &[u8],
+    ) -> Result<crate::proto::KeyData, TinkError> {
+        Err(self.err())
+    }
+
+    fn fips_compatible(&self) -> bool {
+        self.0.fips_compatible()
+    }
+
+    fn fips_compatible_key(&self, serialized_key: &[u8]) -> bool {
+        self.0.fips_compatible_key(serialized_key)
+    }
+
+    fn supports_private_keys(&self) -> bool {
+        self.0.supports_private_keys()
+    }
+
+    fn public_key_data(&self, serialized_key: &[u8]) -> Result<crate::proto::KeyData, TinkError> {
+        self.0.public_key_data(serialized_key)
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Instance-scoped registry of key managers, primitive wrappers and KMS clients.
+
+use super::{KeyManager, KmsClient, PrimitiveWrapper};
+use crate::{ErrorKind, TinkError};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::Arc,
+};
+
+/// A registry of [`KeyManager`]s (indexed by type URL), [`PrimitiveWrapper`]s (indexed by the
+/// type of primitive they produce) and [`KmsClient`]s.
+///
+/// The functions at the top level of the [`registry`](crate::registry) module operate on a
+/// process-wide global `Registry`, which is used by default.  Separate `Registry` instances can
+/// be built and populated independently, and passed to
+/// [`Handle::primitives_with_config`](crate::keyset::Handle::primitives_with_config) (or the
+/// `new_with_config` functions of the primitive factories), so that different parts of a program
+/// (or different tests) can use different key managers for the same type URL.
+#[derive(Clone, Default)]
+pub struct Registry {
+    pub(super) key_managers: HashMap<&'static str, Arc<dyn KeyManager>>,
+    pub(super) kms_clients: Vec<Arc<dyn KmsClient>>,
+    // Each value is an `Arc<dyn PrimitiveWrapper<Primitive = P>>`, keyed by `TypeId::of::<P>()`.
+    pub(super) wrappers: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    pub(super) fips: bool,
+}
+
+/// Check that `serialized_key` is FIPS 140-approved, if `fips` is set.
+pub(crate) fn check_fips(
+    km: &dyn KeyManager,
+    serialized_key: &[u8],
+    fips: bool,
+) -> Result<(), TinkError> {
+    if fips && !(km.fips_compatible() && km.fips_compatible_key(serialized_key)) {
+        return Err(TinkError::new_with_kind(
+            ErrorKind::Unsupported,
+            &format!(
+                "registry: key of type {} uses an algorithm that is not FIPS 140-approved",
+                km.type_url()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+impl Registry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return a copy of this registry that only contains FIPS 140-compatible key managers, and
+    /// that rejects keys with non-approved parameters, regardless of whether FIPS mode is enabled
+    /// globally.
+    pub fn fips_only(&self) -> Self {
+        Self {
+            key_managers: self
+                .key_managers
+                .iter()
+                .filter(|(_, km)| km.fips_compatible())
+                .map(|(type_url, km)| (*type_url, km.clone()))
+                .collect(),
+            kms_clients: self.kms_clients.clone(),
+            wrappers: self.wrappers.clone(),
+            fips: true,
+        }
+    }
+
+    /// Indicate whether this registry only allows FIPS 140-approved keys, either because it was
+    /// created by [`fips_only`](Registry::fips_only) or because [FIPS mode](crate::fips) is
+    /// enabled.
+    pub fn is_fips_only(&self) -> bool {
+        self.fips || crate::fips::is_enabled()
+    }
+
+    /// Register the given key manager. Does not allow overwrite of existing key managers.
+    pub fn register_key_manager<T>(&mut self, km: Arc<T>) -> Result<(), TinkError>
+    where
+        T: 'static + KeyManager,
+    {
+        let type_url = km.type_url();
+        if self.key_managers.contains_key(type_url) {
+            return Err(format!(
+                "registry::register_key_manager: type {} already registered",
+                type_url
+            )
+            .into());
+        }
+        self.key_managers.insert(type_url, km);
+        Ok(())
+    }
+
+    /// Return the key manager for the given `type_url` if it exists.
+    pub fn get_key_manager(&self, type_url: &str) -> Result<Arc<dyn KeyManager>, TinkError> {
+        let km = self.key_managers.get(type_url).ok_or_else(|| {
+            TinkError::new_with_kind(
+                ErrorKind::Unsupported,
+                &format!(
+                    "registry::get_key_manager: unsupported key type: {}",
+                    type_url
+                ),
+            )
+        })?;
+        Ok(km.clone())
+    }
+
+    /// Register the given primitive wrapper.  Does not allow overwrite of an existing wrapper
+    /// for the same primitive type.
+    pub fn register_primitive_wrapper<W>(&mut self, wrapper: W) -> Result<(), TinkError>
+    where
+        W: PrimitiveWrapper,
+    {
+        let type_id = TypeId::of::<W::Primitive>();
+        if self.wrappers.contains_key(&type_id) {
+            return Err(format!(
+                "registry::register_primitive_wrapper: wrapper for {} already registered",
+                std::any::type_name::<W::Primitive>()
+            )
+            .into());
+        }
+        let wrapper: Arc<dyn PrimitiveWrapper<Primitive = W::Primitive>> = Arc::new(wrapper);
+        self.wrappers.insert(type_id, Arc::new(wrapper));
+        Ok(())
+    }
+
+    /// Return the primitive wrapper for primitives of type `P`, if one is registered.
+    pub fn get_primitive_wrapper<P>(
+        &self,
+    ) -> Result<Arc<dyn PrimitiveWrapper<Primitive = P>>, TinkError>
+    where
+        P: ?Sized + 'static,
+    {
+        self.wrappers
+            .get(&TypeId::of::<P>())
+            .and_then(|w| w.downcast_ref::<Arc<dyn PrimitiveWrapper<Primitive = P>>>())
+            .cloned()
+            .ok_or_else(|| {
+                TinkError::new_with_kind(
+                    ErrorKind::Unsupported,
+                    &format!(
+                        "registry::get_primitive_wrapper: no wrapper registered for {}",
+                        std::any::type_name::<P>()
+                    ),
+                )
+            })
+    }
+
+    /// Return the type URLs of all registered key managers.
+    pub fn type_urls(&self) -> Vec<&'static str> {
+        let mut type_urls: Vec<_> = self.key_managers.keys().copied().collect();
+        type_urls.sort_unstable();
+        type_urls
+    }
+
+    /// Generate a new [`KeyData`](crate::proto::KeyData) for the given key template.
+    ///
+    /// Errors from the key manager that are not otherwise categorized have
+    /// [`ErrorKind::InvalidKey`].
+    pub fn new_key_data(
+        &self,
+        kt: &crate::proto::KeyTemplate,
+    ) -> Result<crate::proto::KeyData, TinkError> {
+        let km = self.get_key_manager(&kt.type_url)?;
+        let key_data = km
+            .new_key_data(&kt.value)
+            .map_err(|e| e.or_kind(ErrorKind::InvalidKey))?;
+        check_fips(km.as_ref(), &key_data.value, self.is_fips_only())?;
+        Ok(key_data)
+    }
+
+    /// Generate a new key for the given key template as a serialized protobuf message.
+    ///
+    /// Errors from the key manager that are not otherwise categorized have
+    /// [`ErrorKind::InvalidKey`].
+    pub fn new_key(&self, kt: &crate::proto::KeyTemplate) -> Result<Vec<u8>, TinkError> {
+        let km = self.get_key_manager(&kt.type_url)?;
+        let key = km
+            .new_key(&kt.value)
+            .map_err(|e| e.or_kind(ErrorKind::InvalidKey))?;
+        check_fips(km.as_ref(), &key, self.is_fips_only())?;
+        Ok(key)
+    }
+
+    /// Create a new primitive for the key given in the given
+    /// [`KeyData`](crate::proto::KeyData).
+    pub fn primitive_from_key_data(
+        &self,
+        kd: &crate::proto::KeyData,
+    ) -> Result<crate::Primitive, TinkError> {
+        self.primitive(&kd.type_url, &kd.value)
+    }
+
+    /// Create a new primitive for the given serialized key using the [`KeyManager`]
+    /// identified by the given `type_url`.
+    ///
+    /// Errors from the key manager that are not otherwise categorized have
+    /// [`ErrorKind::InvalidKey`].
+    pub fn primitive(&self, type_url: &str, sk: &[u8]) -> Result<crate::Primitive, TinkError> {
+        if sk.is_empty() {
+            return Err(TinkError::new_with_kind(
+                ErrorKind::InvalidKey,
+                "registry::primitive: invalid serialized key",
+            ));
+        }
+        let km = self.get_key_manager(type_url)?;
+        check_fips(km.as_ref(), sk, self.is_fips_only())?;
+        km.primitive(sk)
+            .map_err(|e| e.or_kind(ErrorKind::InvalidKey))
+    }
+
+    /// Register a new KMS client.
+    pub fn register_kms_client<T>(&mut self, k: T)
+    where
+        T: 'static + KmsClient,
+    {
+        self.kms_clients.push(Arc::new(k));
+    }
+
+    /// Fetches a [`KmsClient`] by a given URI.
+    pub fn get_kms_client(&self, key_uri: &str) -> Result<Arc<dyn KmsClient>, TinkError> {
+        self.kms_clients
+            .iter()
+            .find(|k| k.supported(key_uri))
+            .cloned()
+            .ok_or_else(|| {
+                TinkError::new_with_kind(
+                    ErrorKind::Unsupported,
+                    &format!("KMS client supporting {} not found", key_uri),
+                )
+            })
+    }
+}
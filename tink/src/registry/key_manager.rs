@@ -16,7 +16,7 @@
 
 //! Trait definition for key managers.
 
-use crate::TinkError;
+use crate::{ErrorKind, TinkError};
 
 /// `KeyManager` "understands" keys of a specific key types: it can generate keys of a supported
 /// type and create primitives for supported keys.  A key type is identified by the global name of
@@ -42,6 +42,19 @@ pub trait KeyManager: Send + Sync {
     /// Return the key material type handled by this key manager
     fn key_material_type(&self) -> crate::proto::key_data::KeyMaterialType;
 
+    /// Return the version of this key manager, which is the highest key version that it
+    /// understands.
+    fn version(&self) -> u32 {
+        0
+    }
+
+    /// Return the name of the primitive produced by this key manager (e.g. "Aead" or "Mac"), as
+    /// used in the `primitive_name` field of a [`KeyTypeEntry`](crate::proto::KeyTypeEntry).  The
+    /// default implementation returns an empty string, meaning that the primitive is unknown.
+    fn primitive_name(&self) -> &'static str {
+        ""
+    }
+
     // APIs for Key Management
 
     /// Generate a new [`KeyData`](crate::proto::KeyData) according to specification in
@@ -58,6 +71,21 @@ pub trait KeyManager: Send + Sync {
         })
     }
 
+    /// Indicate whether the key type handled by this `KeyManager` uses a FIPS 140-approved
+    /// algorithm (for at least some key parameters).  Only key managers for which this returns
+    /// true are usable in [FIPS mode](crate::fips).
+    fn fips_compatible(&self) -> bool {
+        false
+    }
+
+    /// Indicate whether the key given in `serialized_key` only uses FIPS 140-approved algorithms.
+    /// The default implementation returns [`fips_compatible`](KeyManager::fips_compatible);
+    /// key managers whose keys may have non-approved parameters (such as a SHA-1 hash) should
+    /// check them here.
+    fn fips_compatible_key(&self, _serialized_key: &[u8]) -> bool {
+        self.fips_compatible()
+    }
+
     /// Indicate whether this `KeyManager` understands private key types.
     fn supports_private_keys(&self) -> bool {
         false
@@ -66,6 +94,9 @@ pub trait KeyManager: Send + Sync {
     /// Extract the public key data from the private key. If `supports_private_keys` returns
     /// false, this method will always return an error.
     fn public_key_data(&self, _serialized_key: &[u8]) -> Result<crate::proto::KeyData, TinkError> {
-        Err("private keys not supported".into())
+        Err(TinkError::new_with_kind(
+            ErrorKind::Unsupported,
+            "private keys not supported",
+        ))
     }
 }
@@ -25,105 +25,130 @@
 //! Note that regular users will usually not work directly with Registry, but rather via primitive
 //! factories, which in the background query the Registry for specific [`KeyManager`]s. Registry is
 //! public though, to enable configurations with custom primitives and [`KeyManager`]s.
+//!
+//! The functions in this module operate on a global registry; separate [`Registry`] instances can
+//! also be created, for example to use different [`KeyManager`]s for the same key type in
+//! different parts of a program.
+//!
+//! The set of available key types can be restricted at runtime by a
+//! [`RegistryConfig`](crate::proto::RegistryConfig), either for a [`Registry`] instance (with
+//! [`Registry::with_config`]) or for the global registry (with [`apply_config`]).
 
 use crate::TinkError;
 use lazy_static::lazy_static;
-use std::{
-    collections::HashMap,
-    sync::{Arc, RwLock},
-};
+use std::sync::{Arc, RwLock};
 
+mod config;
+pub use config::*;
+mod instance;
+pub use instance::*;
 mod kms_client;
 pub use kms_client::*;
 mod key_manager;
 pub use key_manager::*;
 mod key_templates;
 pub use key_templates::*;
+mod primitive_wrapper;
+pub use primitive_wrapper::*;
 
 lazy_static! {
-    /// Global registry of key manager objects, indexed by type URL.
-    static ref KEY_MANAGERS: RwLock<HashMap<&'static str, Arc<dyn KeyManager>>> =
-        RwLock::new(HashMap::new());
-    /// Global list of KMS client objects.
-    static ref KMS_CLIENTS: RwLock<Vec<Arc<dyn KmsClient>>> = RwLock::new(Vec::new());
+    /// Global registry of key manager objects and KMS clients.  Held in an `Arc` so that
+    /// operations can run on a snapshot of the registry without holding the lock, as key managers
+    /// may themselves use the global registry.
+    static ref GLOBAL: RwLock<Arc<Registry>> = RwLock::new(Arc::new(Registry::new()));
+}
+
+/// Error message for global registry lock.
+const GERR: &str = "global registry lock poisoned";
+
+/// Return the current contents of the global registry.
+fn global() -> Arc<Registry> {
+    GLOBAL.read().expect(GERR).clone() // safe: lock
 }
 
-/// Error message for global key manager registry lock.
-const MERR: &str = "global KEY_MANAGERS lock poisoned";
-/// Error message for global KMS client list lock.
-const CERR: &str = "global KMS_CLIENTS lock poisoned";
+/// Return the global registry for modification.
+fn global_mut() -> impl std::ops::DerefMut<Target = Arc<Registry>> {
+    GLOBAL.write().expect(GERR) // safe: lock
+}
 
-/// Register the given key manager. Does not allow overwrite of existing key managers.
+/// Register the given key manager in the global registry. Does not allow overwrite of existing
+/// key managers.
 pub fn register_key_manager<T>(km: Arc<T>) -> Result<(), TinkError>
 where
     T: 'static + KeyManager,
 {
-    let mut key_mgrs = KEY_MANAGERS.write().expect(MERR); // safe: lock
-
-    let type_url = km.type_url();
-    if key_mgrs.contains_key(type_url) {
-        return Err(format!(
-            "registry::register_key_manager: type {} already registered",
-            type_url
-        )
-        .into());
-    }
-    key_mgrs.insert(type_url, km);
-    Ok(())
+    Arc::make_mut(&mut global_mut()).register_key_manager(km)
 }
 
-/// Return the key manager for the given `type_url` if it exists.
+/// Return the key manager for the given `type_url` if it exists in the global registry.
 pub fn get_key_manager(type_url: &str) -> Result<Arc<dyn KeyManager>, TinkError> {
-    let key_mgrs = KEY_MANAGERS.read().expect(MERR); // safe: lock
-    let km = key_mgrs.get(type_url).ok_or_else(|| {
-        TinkError::new(&format!(
-            "registry::get_key_manager: unsupported key type: {}",
-            type_url
-        ))
-    })?;
-    Ok(km.clone())
+    global().get_key_manager(type_url)
+}
+
+/// Register the given primitive wrapper in the global registry. Does not allow overwrite of an
+/// existing wrapper for the same primitive type.
+pub fn register_primitive_wrapper<W>(wrapper: W) -> Result<(), TinkError>
+where
+    W: PrimitiveWrapper,
+{
+    Arc::make_mut(&mut global_mut()).register_primitive_wrapper(wrapper)
+}
+
+/// Return the primitive wrapper for primitives of type `P` if one exists in the global registry.
+pub fn get_primitive_wrapper<P>() -> Result<Arc<dyn PrimitiveWrapper<Primitive = P>>, TinkError>
+where
+    P: ?Sized + 'static,
+{
+    global().get_primitive_wrapper::<P>()
+}
+
+/// Return a copy of the current contents of the global registry, which can then be modified
+/// independently.
+pub fn global_registry() -> Registry {
+    global().as_ref().clone()
+}
+
+/// Restrict the global registry to the key types listed in `config`, as described for
+/// [`Registry::with_config`].  On error, the global registry is left unchanged.
+pub fn apply_config(config: &crate::proto::RegistryConfig) -> Result<(), TinkError> {
+    let mut global = global_mut();
+    *global = Arc::new(global.with_config(config)?);
+    Ok(())
 }
 
-/// Generate a new [`KeyData`](crate::proto::KeyData) for the given key template.
+/// Generate a new [`KeyData`](crate::proto::KeyData) for the given key template, using the
+/// global registry (see [`Registry::new_key_data`]).
 pub fn new_key_data(kt: &crate::proto::KeyTemplate) -> Result<crate::proto::KeyData, TinkError> {
-    get_key_manager(&kt.type_url)?.new_key_data(&kt.value)
+    global().new_key_data(kt)
 }
 
-/// Generate a new key for the given key template as a serialized protobuf message.
+/// Generate a new key for the given key template as a serialized protobuf message, using the
+/// global registry (see [`Registry::new_key`]).
 pub fn new_key(kt: &crate::proto::KeyTemplate) -> Result<Vec<u8>, TinkError> {
-    get_key_manager(&kt.type_url)?.new_key(&kt.value)
+    global().new_key(kt)
 }
 
-/// Create a new primitive for the key given in the given [`KeyData`](crate::proto::KeyData).
+/// Create a new primitive for the key given in the given [`KeyData`](crate::proto::KeyData),
+/// using the global registry.
 pub fn primitive_from_key_data(kd: &crate::proto::KeyData) -> Result<crate::Primitive, TinkError> {
-    primitive(&kd.type_url, &kd.value)
+    global().primitive_from_key_data(kd)
 }
 
-/// Create a new primitive for the given serialized key using the [`KeyManager`]
-/// identified by the given `type_url`.
+/// Create a new primitive for the given serialized key using the [`KeyManager`] identified by the
+/// given `type_url` in the global registry (see [`Registry::primitive`]).
 pub fn primitive(type_url: &str, sk: &[u8]) -> Result<crate::Primitive, TinkError> {
-    if sk.is_empty() {
-        return Err("registry::primitive: invalid serialized key".into());
-    }
-    get_key_manager(type_url)?.primitive(sk)
+    global().primitive(type_url, sk)
 }
 
-/// Register a new KMS client
+/// Register a new KMS client in the global registry.
 pub fn register_kms_client<T>(k: T)
 where
     T: 'static + KmsClient,
 {
-    let mut kms_clients = KMS_CLIENTS.write().expect(CERR); // safe: lock
-    kms_clients.push(Arc::new(k));
+    Arc::make_mut(&mut global_mut()).register_kms_client(k)
 }
 
-/// Fetches a [`KmsClient`] by a given URI.
+/// Fetches a [`KmsClient`] by a given URI from the global registry.
 pub fn get_kms_client(key_uri: &str) -> Result<Arc<dyn KmsClient>, TinkError> {
-    let kms_clients = KMS_CLIENTS.read().expect(CERR); // safe: lock
-    for k in kms_clients.iter() {
-        if k.supported(key_uri) {
-            return Ok(k.clone());
-        }
-    }
-    Err(format!("KMS client supporting {} not found", key_uri).into())
+    global().get_kms_client(key_uri)
 }
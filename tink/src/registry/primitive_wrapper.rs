@@ -0,0 +1,38 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+//! Trait definition for primitive wrappers.
+
+use crate::TinkError;
+
+/// A `PrimitiveWrapper` combines the primitives in a [`PrimitiveSet`](crate::primitiveset::PrimitiveSet)
+/// (one per key in a keyset) into a single primitive of type `Primitive`, which typically uses
+/// the primary key for operations that produce output and tries all matching keys for operations
+/// that consume it.
+///
+/// Primitive crates register a wrapper for each primitive type they provide (in their `init()`
+/// function), which allows [`Handle::primitive`](crate::keyset::Handle::primitive) to build a
+/// primitive of any registered type.
+pub trait PrimitiveWrapper: Send + Sync + 'static {
+    /// The type of primitive produced, usually a trait object type such as `dyn tink::Aead`.
+    type Primitive: ?Sized + 'static;
+
+    /// Wrap the primitives in the given set into a single primitive.
+    fn wrap(
+        &self,
+        ps: crate::primitiveset::PrimitiveSet,
+    ) -> Result<Box<Self::Primitive>, TinkError>;
+}
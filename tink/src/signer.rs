@@ -21,9 +21,23 @@
 /// Implementations of this trait are secure against adaptive chosen-message
 /// attacks.  Signing data ensures authenticity and integrity of that data, but
 /// not its secrecy.
-pub trait Signer: SignerBoxClone {
+pub trait Signer: SignerBoxClone + Send + Sync {
     // Computes the digital signature for data.
     fn sign(&self, data: &[u8]) -> Result<Vec<u8>, crate::TinkError>;
+
+    /// Compute the digital signature for all of the data available from `reader`.
+    ///
+    /// The default implementation reads all of the data into memory and uses
+    /// [`sign`](Signer::sign); implementations that can hash the data incrementally override
+    /// this.  Depending on the signature scheme, the result may only be verifiable with
+    /// [`Verifier::verify_reader`](crate::Verifier::verify_reader).
+    fn sign_reader(&self, reader: &mut dyn std::io::Read) -> Result<Vec<u8>, crate::TinkError> {
+        let mut data = Vec::new();
+        reader
+            .read_to_end(&mut data)
+            .map_err(|e| crate::utils::wrap_err("Signer: failed to read data", e))?;
+        self.sign(&data)
+    }
 }
 
 /// Trait bound to indicate that primitive trait objects should support cloning
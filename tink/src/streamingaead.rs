@@ -27,7 +27,7 @@
 /// Instances of `StreamingAead` must follow the OAE2 definition as proposed in the paper "Online
 /// Authenticated-Encryption and its Nonce-Reuse Misuse-Resistance" by [Hoang, Reyhanitabar, Rogaway
 /// and Vizár](https://eprint.iacr.org/2015/189.pdf)
-pub trait StreamingAead: StreamingAeadBoxClone {
+pub trait StreamingAead: StreamingAeadBoxClone + Send + Sync {
     /// Return a wrapper around an underlying `std::io::Write`, such that any write-operation
     /// via the wrapper results in AEAD-encryption of the written data, using `aad`
     /// as associated authenticated data. The associated data is not included in the ciphertext
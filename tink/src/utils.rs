@@ -21,18 +21,104 @@
 
 use std::error::Error;
 
+/// Broad category of a [`TinkError`], allowing callers to react to different failures (for
+/// example by mapping them to different HTTP status codes, or deciding whether to retry) without
+/// inspecting error messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Uncategorized error.
+    Other,
+    /// Invalid input, such as a parameter of the wrong size or an unsupported option.
+    InvalidArgument,
+    /// Invalid key material or key format.
+    InvalidKey,
+    /// Invalid keyset, or keyset that could not be parsed.
+    InvalidKeyset,
+    /// No key in the keyset matches the key ID requested, or embedded in a ciphertext, MAC or
+    /// signature.
+    KeyNotFound,
+    /// The key type or key URI is not supported, e.g. no key manager or KMS client is registered
+    /// for it.
+    Unsupported,
+    /// Decryption or verification failed: the ciphertext, MAC or signature is not authentic for
+    /// the given key and associated data.
+    Authentication,
+    /// A remote service (such as a KMS) is temporarily unavailable or timed out; the operation
+    /// may succeed if retried.
+    Unavailable,
+    /// The caller is not permitted to use a remote key.
+    PermissionDenied,
+    /// An I/O error occurred while reading or writing data.
+    Io,
+    /// A key has reached a configured usage limit, and should be rotated.
+    LimitExceeded,
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let desc = match self {
+            ErrorKind::Other => "other error",
+            ErrorKind::InvalidArgument => "invalid argument",
+            ErrorKind::InvalidKey => "invalid key",
+            ErrorKind::InvalidKeyset => "invalid keyset",
+            ErrorKind::KeyNotFound => "key not found",
+            ErrorKind::Unsupported => "unsupported",
+            ErrorKind::Authentication => "authentication failed",
+            ErrorKind::Unavailable => "unavailable",
+            ErrorKind::PermissionDenied => "permission denied",
+            ErrorKind::Io => "I/O error",
+            ErrorKind::LimitExceeded => "usage limit exceeded",
+        };
+        write!(f, "{}", desc)
+    }
+}
+
 /// `Error` type for errors emitted by Tink. Note that errors from cryptographic
 /// operations are necessarily uninformative, to avoid information leakage.
+///
+/// `TinkError` is `Send + Sync`, so it can be propagated across threads (and so across
+/// asynchronous tasks).
 #[derive(Debug)]
 pub struct TinkError {
+    kind: ErrorKind,
     msg: String,
-    src: Option<Box<dyn Error>>,
+    src: Option<Box<dyn Error + Send + Sync>>,
 }
 
 impl TinkError {
     pub fn new(msg: &str) -> Self {
         msg.into()
     }
+
+    /// Create a new error of the given kind.
+    pub fn new_with_kind(kind: ErrorKind, msg: &str) -> Self {
+        TinkError {
+            kind,
+            msg: msg.to_string(),
+            src: None,
+        }
+    }
+
+    /// Return the kind of error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Return the error with its kind changed to `kind`.
+    pub fn with_kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Return the error with its kind changed to `kind`, unless it already has a more specific
+    /// kind than [`ErrorKind::Other`].
+    pub fn or_kind(self, kind: ErrorKind) -> Self {
+        match self.kind {
+            ErrorKind::Other => self.with_kind(kind),
+            _ => self,
+        }
+    }
 }
 
 impl std::fmt::Display for TinkError {
@@ -50,6 +136,7 @@ impl Error for TinkError {}
 impl std::convert::From<&str> for TinkError {
     fn from(msg: &str) -> Self {
         TinkError {
+            kind: ErrorKind::Other,
             msg: msg.to_string(),
             src: None,
         }
@@ -58,7 +145,11 @@ impl std::convert::From<&str> for TinkError {
 
 impl std::convert::From<String> for TinkError {
     fn from(msg: String) -> Self {
-        TinkError { msg, src: None }
+        TinkError {
+            kind: ErrorKind::Other,
+            msg,
+            src: None,
+        }
     }
 }
 
@@ -71,12 +162,83 @@ impl std::convert::From<String> for TinkError {
 ///     return nil, fmt.Errorf("FunctionCall failed: %s", err)
 ///   }
 /// ```
+///
+/// If `src` is a [`TinkError`], the returned error has the same [`ErrorKind`].
 pub fn wrap_err<T>(msg: &str, src: T) -> TinkError
 where
-    T: Error + 'static,
+    T: Error + Send + Sync + 'static,
 {
+    let kind = (&src as &(dyn Error + 'static))
+        .downcast_ref::<TinkError>()
+        .map_or(ErrorKind::Other, |e| e.kind);
     TinkError {
+        kind,
         msg: msg.to_string(),
         src: Some(Box::new(src)),
     }
 }
+
+#[cfg(feature = "insecure")]
+thread_local! {
+    /// Whether secret key material should be included in `Debug` output on this thread.
+    static SHOW_SECRETS: std::cell::Cell<bool> = std::cell::Cell::new(false);
+}
+
+/// Guard that includes secret key material in `Debug` output on the current thread until dropped.
+#[cfg(feature = "insecure")]
+pub(crate) struct ShowSecrets(bool);
+
+#[cfg(feature = "insecure")]
+impl ShowSecrets {
+    pub(crate) fn new() -> Self {
+        ShowSecrets(SHOW_SECRETS.with(|show| show.replace(true)))
+    }
+}
+
+#[cfg(feature = "insecure")]
+impl Drop for ShowSecrets {
+    fn drop(&mut self) {
+        let prev = self.0;
+        SHOW_SECRETS.with(|show| show.set(prev));
+    }
+}
+
+#[cfg(feature = "insecure")]
+fn show_secrets() -> bool {
+    SHOW_SECRETS.with(|show| show.get())
+}
+
+#[cfg(not(feature = "insecure"))]
+fn show_secrets() -> bool {
+    false
+}
+
+/// Wrapper for secret key material whose [`Debug`](std::fmt::Debug) output is redacted, to
+/// prevent keys leaking into logs.
+///
+/// With the `insecure` feature enabled, the key material can be shown by formatting via
+/// `tink::keyset::insecure::Unredacted`.
+pub struct SecretBytes<'a>(pub &'a [u8]);
+
+impl std::fmt::Debug for SecretBytes<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if show_secrets() {
+            self.0.fmt(f)
+        } else {
+            write!(f, "<redacted {} bytes>", self.0.len())
+        }
+    }
+}
+
+/// Wrapper for an enumeration field of a generated message, whose [`Debug`](std::fmt::Debug)
+/// output shows the enum value where valid.
+pub(crate) struct DebugEnum<T>(pub i32, pub fn(i32) -> Option<T>);
+
+impl<T: std::fmt::Debug> std::fmt::Debug for DebugEnum<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.1)(self.0) {
+            Some(val) => val.fmt(f),
+            None => self.0.fmt(f),
+        }
+    }
+}
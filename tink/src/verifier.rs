@@ -21,9 +21,79 @@
 /// Implementations of this trait are secure against adaptive chosen-message
 /// attacks.  Signing data ensures authenticity and integrity of that data, but
 /// not its secrecy.
-pub trait Verifier: VerifierBoxClone {
+pub trait Verifier: VerifierBoxClone + Send + Sync {
     // Returns `()` if `signature` is a valid signature for `data`; otherwise returns an error.
     fn verify(&self, signature: &[u8], data: &[u8]) -> Result<(), crate::TinkError>;
+
+    /// Start an incremental verification that `signature` is a valid signature for data that is
+    /// provided in chunks, as produced by [`Signer::sign_reader`](crate::Signer::sign_reader).
+    ///
+    /// The default implementation buffers all of the data and uses
+    /// [`verify`](Verifier::verify).
+    fn new_verification(
+        &self,
+        signature: &[u8],
+    ) -> Result<Box<dyn SignatureVerification>, crate::TinkError> {
+        Ok(Box::new(BufferedSignatureVerification {
+            verifier: self.box_clone(),
+            signature: signature.to_vec(),
+            data: Vec::new(),
+        }))
+    }
+
+    /// Return `()` if `signature` is a valid signature for all of the data available from
+    /// `reader`, as produced by [`Signer::sign_reader`](crate::Signer::sign_reader); otherwise
+    /// return an error.
+    fn verify_reader(
+        &self,
+        signature: &[u8],
+        reader: &mut dyn std::io::Read,
+    ) -> Result<(), crate::TinkError> {
+        let mut verification = self.new_verification(signature)?;
+        let mut buf = vec![0; READ_CHUNK_SIZE];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => verification.update(&buf[..n])?,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(crate::utils::wrap_err("Verifier: failed to read data", e)),
+            }
+        }
+        verification.verify()
+    }
+}
+
+/// Size of the chunks read by [`Verifier::verify_reader`].
+const READ_CHUNK_SIZE: usize = 8192;
+
+/// `SignatureVerification` holds the state of an incremental signature verification, as
+/// returned by [`Verifier::new_verification`].
+pub trait SignatureVerification: Send {
+    /// Process the next chunk of data.
+    fn update(&mut self, data: &[u8]) -> Result<(), crate::TinkError>;
+
+    /// Return `()` if the signature is valid for all of the data processed so far, otherwise
+    /// return an error.
+    fn verify(self: Box<Self>) -> Result<(), crate::TinkError>;
+}
+
+/// [`SignatureVerification`] that accumulates all of the data for a single call to
+/// [`Verifier::verify`].
+struct BufferedSignatureVerification {
+    verifier: Box<dyn Verifier>,
+    signature: Vec<u8>,
+    data: Vec<u8>,
+}
+
+impl SignatureVerification for BufferedSignatureVerification {
+    fn update(&mut self, data: &[u8]) -> Result<(), crate::TinkError> {
+        self.data.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn verify(self: Box<Self>) -> Result<(), crate::TinkError> {
+        self.verifier.verify(&self.signature, &self.data)
+    }
 }
 
 /// Trait bound to indicate that primitive trait objects should support cloning
@@ -0,0 +1,70 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+// FIPS-only mode is process-wide, so these tests live in their own test binary.
+
+use tink::ErrorKind;
+
+#[test]
+fn test_fips_only_mode() {
+    tink_aead::init();
+
+    // Keysets holding non-approved keys are created before the mode is switched on.
+    let chacha_handle =
+        tink::keyset::Handle::new(&tink_aead::cha_cha20_poly1305_key_template()).unwrap();
+    let aes_gcm_handle = tink::keyset::Handle::new(&tink_aead::aes128_gcm_key_template()).unwrap();
+    let chacha_with_nonce_handle = tink::keyset::Handle::new(
+        &tink_aead::aead_with_nonce_key_template(&tink_aead::cha_cha20_poly1305_key_template()),
+    )
+    .unwrap();
+    let aes_gcm_with_nonce_handle = tink::keyset::Handle::new(
+        &tink_aead::aead_with_nonce_key_template(&tink_aead::aes128_gcm_key_template()),
+    )
+    .unwrap();
+
+    tink::fips::enable();
+    assert!(tink::fips::is_enabled());
+    assert!(tink::registry::global_registry().is_fips_only());
+
+    let result = tink::keyset::Handle::new(&tink_aead::cha_cha20_poly1305_key_template());
+    assert!(matches!(result, Err(e) if e.kind() == ErrorKind::Unsupported));
+    let result = tink::keyset::Handle::new(&tink_aead::x_cha_cha20_poly1305_key_template());
+    assert!(matches!(result, Err(e) if e.kind() == ErrorKind::Unsupported));
+
+    match tink_aead::new(&chacha_handle) {
+        Ok(_) => panic!("non-approved primitive created in FIPS-only mode"),
+        Err(e) => {
+            assert_eq!(e.kind(), ErrorKind::Unsupported);
+            assert!(format!("{:?}", e).contains("not FIPS 140-approved"));
+        }
+    }
+
+    let a = tink_aead::new(&aes_gcm_handle).unwrap();
+    let ct = a.encrypt(b"plaintext", b"aad").unwrap();
+    assert_eq!(a.decrypt(&ct, b"aad").unwrap(), b"plaintext");
+    assert!(tink::keyset::Handle::new(&tink_aead::aes256_gcm_key_template()).is_ok());
+
+    // The same applies to keys for AEAD with caller-supplied nonces.
+    let result = tink_aead::new_with_nonce(&chacha_with_nonce_handle);
+    assert!(matches!(result, Err(e) if e.kind() == ErrorKind::Unsupported));
+    let a = tink_aead::new_with_nonce(&aes_gcm_with_nonce_handle).unwrap();
+    let nonce = vec![0; a.nonce_size()];
+    let ct = a.encrypt_with_nonce(&nonce, b"plaintext", b"aad").unwrap();
+    assert_eq!(
+        a.decrypt_with_nonce(&nonce, &ct, b"aad").unwrap(),
+        b"plaintext"
+    );
+}
@@ -16,5 +16,7 @@
 
 mod keyset;
 mod primitiveset;
+mod proto;
 mod registry;
 mod subtle;
+mod utils;
@@ -88,6 +88,50 @@ fn test_read() {
     );
 }
 
+#[test]
+fn test_read_with_associated_data() {
+    let main_key = Box::new(tink_aead::subtle::AesGcm::new(&[b'A'; 32]).unwrap());
+
+    let key_data = tink_testutil::new_key_data(
+        "some type url",
+        &[42, 42, 0x42, 0x42, 0o42, 0o42],
+        KeyMaterialType::Symmetric,
+    );
+    let key = tink_testutil::new_key(
+        &key_data,
+        tink::proto::KeyStatusType::Enabled,
+        1,
+        tink::proto::OutputPrefixType::Tink,
+    );
+    let ks = tink_testutil::new_keyset(1, vec![key]);
+    let h = insecure::new_handle(ks).unwrap();
+
+    let mem_keyset = &mut tink::keyset::MemReaderWriter::default();
+    h.write_with_associated_data(mem_keyset, main_key.clone(), b"service=billing")
+        .unwrap();
+    let h2 = Handle::read_with_associated_data(mem_keyset, main_key.clone(), b"service=billing")
+        .unwrap();
+    assert_eq!(
+        insecure::keyset_material(&h),
+        insecure::keyset_material(&h2),
+        "Decrypt failed: got {:?}, want {:?}",
+        h2,
+        h
+    );
+
+    // The keyset cannot be read with different (or no) associated data.
+    assert!(
+        Handle::read_with_associated_data(mem_keyset, main_key.clone(), b"service=payroll")
+            .is_err()
+    );
+    assert!(Handle::read(mem_keyset, main_key.clone()).is_err());
+
+    // Keysets written without associated data are read with empty associated data.
+    h.write(mem_keyset, main_key.clone()).unwrap();
+    assert!(Handle::read_with_associated_data(mem_keyset, main_key.clone(), b"").is_ok());
+    assert!(Handle::read_with_associated_data(mem_keyset, main_key, b"service=billing").is_err());
+}
+
 #[test]
 fn test_read_with_no_secrets() {
     // Create a keyset containing public key material
@@ -375,3 +419,168 @@ fn test_insecure_read_empty() {
     let result = insecure::read(&mut mem_keyset);
     tink_testutil::expect_err(result, "insecure: invalid keyset");
 }
+
+#[test]
+fn test_primitive() {
+    tink_aead::init();
+    tink_mac::init();
+    let kh = Handle::new(&tink_aead::aes128_gcm_key_template()).unwrap();
+    let a = kh.primitive::<dyn tink::Aead>().unwrap();
+    let ct = a.encrypt(b"plaintext", b"aad").unwrap();
+    assert_eq!(a.decrypt(&ct, b"aad").unwrap(), b"plaintext");
+
+    // A registered wrapper for the wrong primitive type.
+    assert!(
+        matches!(kh.primitive::<dyn tink::Mac>(), Err(e) if e.kind() == tink::ErrorKind::InvalidKeyset)
+    );
+    // No wrapper registered.
+    assert!(
+        matches!(kh.primitive::<dyn tink::DeterministicAead>(), Err(e) if e.kind() == tink::ErrorKind::Unsupported)
+    );
+
+    // Wrappers are also available in registry instances.
+    let mut registry = tink::registry::Registry::new();
+    registry
+        .register_key_manager(Arc::new(tink_testutil::DummyAeadKeyManager::default()))
+        .unwrap();
+    assert!(
+        matches!(kh.primitive_with_config::<dyn tink::Aead>(&registry), Err(e) if e.kind() == tink::ErrorKind::Unsupported)
+    );
+    registry
+        .register_primitive_wrapper(tink_aead::AeadWrapper)
+        .unwrap();
+    assert!(registry
+        .register_primitive_wrapper(tink_aead::AeadWrapper)
+        .is_err());
+    let a = kh
+        .primitive_with_config::<dyn tink::Aead>(&registry)
+        .unwrap();
+    assert!(
+        a.decrypt(&ct, b"aad").is_err(),
+        "expect primitive from dummy key manager"
+    );
+    let a = kh
+        .primitive_with_config::<dyn tink::Aead>(&tink::registry::global_registry())
+        .unwrap();
+    assert_eq!(a.decrypt(&ct, b"aad").unwrap(), b"plaintext");
+}
+
+/// Primitive type defined outside Tink.
+trait BlindIndex: Send + Sync {
+    fn index(&self, data: &[u8]) -> Vec<u8>;
+}
+
+struct TestBlindIndex {
+    key: Vec<u8>,
+}
+
+impl BlindIndex for TestBlindIndex {
+    fn index(&self, data: &[u8]) -> Vec<u8> {
+        let mut result = self.key.clone();
+        result.extend_from_slice(data);
+        result
+    }
+}
+
+const BLIND_INDEX_TYPE_URL: &str = "type.example.com/test.BlindIndexKey";
+
+struct BlindIndexKeyManager;
+
+impl tink::registry::KeyManager for BlindIndexKeyManager {
+    fn primitive(&self, serialized_key: &[u8]) -> Result<tink::Primitive, TinkError> {
+        let p: Arc<dyn BlindIndex> = Arc::new(TestBlindIndex {
+            key: serialized_key.to_vec(),
+        });
+        Ok(tink::Primitive::custom(p))
+    }
+
+    fn new_key(&self, _serialized_key_format: &[u8]) -> Result<Vec<u8>, TinkError> {
+        Ok(tink::subtle::random::get_random_bytes(8))
+    }
+
+    fn type_url(&self) -> &'static str {
+        BLIND_INDEX_TYPE_URL
+    }
+
+    fn key_material_type(&self) -> KeyMaterialType {
+        KeyMaterialType::Symmetric
+    }
+}
+
+/// Keyset primitive that prefixes the index of the primary key with its output prefix.
+struct WrappedBlindIndex {
+    prefix: Vec<u8>,
+    primary: Arc<dyn BlindIndex>,
+}
+
+impl BlindIndex for WrappedBlindIndex {
+    fn index(&self, data: &[u8]) -> Vec<u8> {
+        let mut result = self.prefix.clone();
+        result.extend_from_slice(&self.primary.index(data));
+        result
+    }
+}
+
+struct BlindIndexWrapper;
+
+impl tink::registry::PrimitiveWrapper for BlindIndexWrapper {
+    type Primitive = dyn BlindIndex;
+
+    fn wrap(&self, ps: tink::primitiveset::PrimitiveSet) -> Result<Box<dyn BlindIndex>, TinkError> {
+        let primary = ps.primary.as_ref().ok_or("no primary")?;
+        let p = primary
+            .primitive
+            .downcast_ref::<Arc<dyn BlindIndex>>()
+            .ok_or("not a BlindIndex primitive")?;
+        Ok(Box::new(WrappedBlindIndex {
+            prefix: primary.prefix.clone(),
+            primary: p.clone(),
+        }))
+    }
+}
+
+#[test]
+fn test_custom_primitive() {
+    tink::registry::register_key_manager(Arc::new(BlindIndexKeyManager)).unwrap();
+    tink::registry::register_primitive_wrapper(BlindIndexWrapper).unwrap();
+    let kt = tink::proto::KeyTemplate {
+        type_url: BLIND_INDEX_TYPE_URL.to_string(),
+        value: vec![],
+        output_prefix_type: tink::proto::OutputPrefixType::Tink as i32,
+    };
+
+    let mut ksm = tink::keyset::Manager::new();
+    let old_key_id = ksm.rotate(&kt).unwrap();
+    let kh = ksm.handle().unwrap();
+    let old_index = kh.primitive::<dyn BlindIndex>().unwrap().index(b"data");
+    let new_key_id = ksm.rotate(&kt).unwrap();
+    let kh = ksm.handle().unwrap();
+    let new_index = kh.primitive::<dyn BlindIndex>().unwrap().index(b"data");
+    assert_ne!(old_index, new_index);
+    assert_eq!(&new_index[1..5], &new_key_id.to_be_bytes());
+
+    // The per-key custom primitives are available from the primitive set.
+    let ps = kh.primitives().unwrap();
+    let old_entry = &ps.entries_for_key_id(old_key_id)[0];
+    let old = old_entry
+        .primitive
+        .downcast_ref::<Arc<dyn BlindIndex>>()
+        .unwrap();
+    assert_eq!(&old.index(b"data")[..], &old_index[5..]);
+    assert!(old_entry.primitive.is_custom::<Arc<dyn BlindIndex>>());
+    assert!(!old_entry.primitive.is_custom::<Vec<u8>>());
+    assert!(old_entry
+        .primitive
+        .clone()
+        .downcast_ref::<Arc<dyn BlindIndex>>()
+        .is_some());
+
+    // Built-in primitives are not custom.
+    let mac = tink::Primitive::Mac(Box::new(tink_testutil::DummyMac {
+        name: "".to_string(),
+    }));
+    assert!(mac.downcast_ref::<Arc<dyn BlindIndex>>().is_none());
+
+    // The keyset cannot be used for other primitive types.
+    assert!(kh.primitive::<dyn tink::Aead>().is_err());
+}
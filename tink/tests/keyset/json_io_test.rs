@@ -259,7 +259,7 @@ fn test_json_io_read_fail_decode() {
     tink_mac::init();
     let buf = vec![1, 2, 3];
     let mut r = tink::keyset::JsonReader::new(&buf[..]);
-    assert!(r.read().is_err());
+    assert_eq!(r.read().unwrap_err().kind(), tink::ErrorKind::InvalidKeyset);
 
     let buf = vec![1, 2, 3];
     let mut r = tink::keyset::JsonReader::new(&buf[..]);
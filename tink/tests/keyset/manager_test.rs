@@ -260,11 +260,15 @@ fn test_keyset_manager_operations() {
 
     // Operations with invalid key ID fail
     let invalid_key_id = 99999; // assume this doesn't accidentally clash
-    assert!(keyset_manager.set_primary(invalid_key_id).is_err());
-    assert!(keyset_manager.enable(invalid_key_id).is_err());
-    assert!(keyset_manager.disable(invalid_key_id).is_err());
-    assert!(keyset_manager.destroy(invalid_key_id).is_err());
-    assert!(keyset_manager.delete(invalid_key_id).is_err());
+    for result in vec![
+        keyset_manager.set_primary(invalid_key_id),
+        keyset_manager.enable(invalid_key_id),
+        keyset_manager.disable(invalid_key_id),
+        keyset_manager.destroy(invalid_key_id),
+        keyset_manager.delete(invalid_key_id),
+    ] {
+        assert_eq!(result.unwrap_err().kind(), tink::ErrorKind::KeyNotFound);
+    }
 }
 
 #[test]
@@ -14,6 +14,7 @@
 //
 ////////////////////////////////////////////////////////////////////////////////
 
+use std::sync::Arc;
 use tink::{
     primitiveset::Entry,
     proto::{keyset::Key, KeyStatusType, OutputPrefixType},
@@ -42,7 +43,7 @@ fn create_keyset() -> Vec<Key> {
 fn test_primitive_set_basic() {
     let mut ps = tink::primitiveset::PrimitiveSet::new();
     assert!(ps.primary.is_none());
-    assert!(ps.entries.is_empty());
+    assert!(ps.entries().is_empty());
     // generate test keys
     let keys = create_keyset();
     // add all test primitives
@@ -74,7 +75,7 @@ fn test_primitive_set_basic() {
     let raw_entries = ps.raw_entries();
     assert!(
         validate_entry_list(
-            &raw_entries,
+            raw_entries,
             &raw_ids,
             &raw_macs,
             &raw_statuses,
@@ -98,7 +99,7 @@ fn test_primitive_set_basic() {
     let tink_entries = ps.entries_for_prefix(&prefix);
     assert!(
         validate_entry_list(
-            &tink_entries,
+            tink_entries,
             &tink_ids,
             &tink_macs,
             &tink_statuses,
@@ -116,7 +117,7 @@ fn test_primitive_set_basic() {
     let tink_entries = ps.entries_for_prefix(&prefix);
     assert!(
         validate_entry_list(
-            &tink_entries,
+            tink_entries,
             &tink_ids,
             &tink_macs,
             &tink_statuses,
@@ -134,7 +135,7 @@ fn test_primitive_set_basic() {
     let legacy_entries = ps.entries_for_prefix(&legacy_prefix);
     assert!(
         validate_entry_list(
-            &legacy_entries,
+            legacy_entries,
             &legacy_ids,
             &legacy_macs,
             &legacy_statuses,
@@ -142,6 +143,20 @@ fn test_primitive_set_basic() {
         ),
         "legacy primitives do not match the input key"
     );
+
+    // check lookup by key ID, which includes entries with different prefixes
+    let id_entries = ps.entries_for_key_id(keys[1].key_id);
+    assert_eq!(id_entries.len(), 2);
+    assert!(Arc::ptr_eq(&id_entries[0], &entries[1]));
+    assert!(Arc::ptr_eq(&id_entries[1], &entries[2]));
+    assert!(ps.entries_for_key_id(1).is_empty());
+
+    // lookups share the stored entries rather than copying them
+    assert!(Arc::ptr_eq(
+        &ps.entries_for_prefix(&legacy_prefix)[0],
+        &entries[1]
+    ));
+    assert!(ps.entries_for_prefix(&[0xff; 5]).is_empty());
 }
 
 #[test]
@@ -167,7 +182,7 @@ fn test_add_with_invalid_input() {
 }
 
 fn validate_entry_list(
-    entries: &[Entry],
+    entries: &[Arc<Entry>],
     key_ids: &[tink::KeyId],
     macs: &[Box<DummyMac>],
     statuses: &[KeyStatusType],
@@ -184,6 +199,24 @@ fn validate_entry_list(
     true
 }
 
+#[test]
+fn test_primitives_are_send_sync() {
+    fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+    assert_send_sync::<dyn tink::Aead>();
+    assert_send_sync::<dyn tink::DeterministicAead>();
+    assert_send_sync::<dyn tink::HybridDecrypt>();
+    assert_send_sync::<dyn tink::HybridEncrypt>();
+    assert_send_sync::<dyn tink::Mac>();
+    assert_send_sync::<dyn tink::Prf>();
+    assert_send_sync::<dyn tink::Signer>();
+    assert_send_sync::<dyn tink::StreamingAead>();
+    assert_send_sync::<dyn tink::Verifier>();
+    assert_send_sync::<Primitive>();
+    assert_send_sync::<Entry>();
+    assert_send_sync::<tink::primitiveset::PrimitiveSet>();
+    assert_send_sync::<tink::TinkError>();
+}
+
 // Compare an entry with the [`DummyMAC`] that was used to create the entry.
 fn validate_entry(
     entry: &Entry,
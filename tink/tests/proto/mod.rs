@@ -0,0 +1,124 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+use zeroize::Zeroize;
+
+#[test]
+fn test_secret_proto_zeroize() {
+    let mut key = tink::proto::AesGcmKey {
+        version: 0,
+        key_value: vec![0x42; 32],
+    };
+    key.zeroize();
+    assert!(key.key_value.is_empty());
+
+    let mut keyset = tink::proto::Keyset {
+        primary_key_id: 1,
+        key: vec![tink::proto::keyset::Key {
+            key_data: Some(tink::proto::KeyData {
+                type_url: "type.googleapis.com/google.crypto.tink.AesGcmKey".to_string(),
+                value: vec![0x42; 32],
+                key_material_type: tink::proto::key_data::KeyMaterialType::Symmetric as i32,
+            }),
+            status: tink::proto::KeyStatusType::Enabled as i32,
+            key_id: 1,
+            output_prefix_type: tink::proto::OutputPrefixType::Tink as i32,
+        }],
+    };
+    keyset.zeroize();
+    assert_eq!(keyset.primary_key_id, 0);
+    assert!(keyset.key.is_empty());
+}
+
+#[test]
+fn test_secret_proto_debug_redacted() {
+    let key = tink::proto::AesGcmKey {
+        version: 0,
+        key_value: vec![0x42; 16],
+    };
+    let key_data = tink::proto::KeyData {
+        type_url: "type.googleapis.com/google.crypto.tink.AesGcmKey".to_string(),
+        value: vec![0x42; 16],
+        key_material_type: tink::proto::key_data::KeyMaterialType::Symmetric as i32,
+    };
+    let secret = format!("{:?}", vec![0x42u8; 16]);
+
+    let debug = format!("{:?}", key);
+    assert_eq!(
+        debug,
+        "AesGcmKey { version: 0, key_value: <redacted 16 bytes> }"
+    );
+    let debug = format!("{:?}", key_data);
+    assert!(!debug.contains(&secret), "{}", debug);
+    assert!(debug.contains("key_material_type: Symmetric"), "{}", debug);
+
+    let debug = format!("{:?}", tink::keyset::insecure::Unredacted(&key_data));
+    assert!(debug.contains(&secret), "{}", debug);
+    // Redaction applies again outside of the wrapper.
+    let debug = format!("{:?}", key_data);
+    assert!(!debug.contains(&secret), "{}", debug);
+}
+
+#[test]
+fn test_secret_proto_encoding() {
+    let key = tink::proto::AesGcmKey {
+        version: 1,
+        key_value: vec![0x42; 16],
+    };
+    let mut buf = vec![];
+    prost::Message::encode(&key, &mut buf).unwrap();
+    assert_eq!(prost::Message::encoded_len(&key), buf.len());
+    // Field 1 (varint) followed by field 3 (length-delimited).
+    let mut expected = vec![0x08, 1, 0x1a, 16];
+    expected.extend_from_slice(&[0x42; 16]);
+    assert_eq!(buf, expected);
+
+    let decoded: tink::proto::AesGcmKey = prost::Message::decode(&buf[..]).unwrap();
+    assert_eq!(decoded, key);
+}
+
+#[test]
+fn test_secret_proto_encoding_nested() {
+    let keyset = tink::proto::Keyset {
+        primary_key_id: 1,
+        key: vec![tink::proto::keyset::Key {
+            key_data: Some(tink::proto::KeyData {
+                type_url: "type.googleapis.com/google.crypto.tink.AesGcmKey".to_string(),
+                value: vec![0x42; 16],
+                key_material_type: tink::proto::key_data::KeyMaterialType::Symmetric as i32,
+            }),
+            status: tink::proto::KeyStatusType::Enabled as i32,
+            key_id: 1,
+            output_prefix_type: tink::proto::OutputPrefixType::Tink as i32,
+        }],
+    };
+    let mut buf = vec![];
+    prost::Message::encode(&keyset, &mut buf).unwrap();
+    assert_eq!(prost::Message::encoded_len(&keyset), buf.len());
+    let decoded: tink::proto::Keyset = prost::Message::decode(&buf[..]).unwrap();
+    assert_eq!(decoded, keyset);
+    let key_data = decoded.key[0].key_data.as_ref().unwrap();
+    assert_eq!(
+        key_data.key_material_type(),
+        tink::proto::key_data::KeyMaterialType::Symmetric
+    );
+
+    // Default-valued fields are omitted, and unknown fields are skipped.
+    let empty = tink::proto::KeyData::default();
+    assert_eq!(prost::Message::encoded_len(&empty), 0);
+    let decoded: tink::proto::KeyData = prost::Message::decode(&[0x20, 0x01][..]).unwrap();
+    assert_eq!(decoded, empty);
+}
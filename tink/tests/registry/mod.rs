@@ -27,13 +27,16 @@ fn test_register_key_manager() {
     tink::registry::get_key_manager(tink_testutil::AES_GCM_TYPE_URL).unwrap();
     // some random typeurl
     assert!(
-        tink::registry::get_key_manager("some url").is_err(),
-        "expect an error when a type url doesn't exist in the registry"
+        matches!(tink::registry::get_key_manager("some url"), Err(e) if e.kind() == tink::ErrorKind::Unsupported),
+        "expect an Unsupported error when a type url doesn't exist in the registry"
     );
 
     // HMACKeyManager is symmetric
     assert!(!km.supports_private_keys());
-    assert!(km.public_key_data(&[]).is_err());
+    assert_eq!(
+        km.public_key_data(&[]).unwrap_err().kind(),
+        tink::ErrorKind::Unsupported
+    );
 }
 
 #[test]
@@ -60,6 +63,195 @@ fn test_register_key_manager_duplicate() {
     );
 }
 
+#[test]
+fn test_registry_instance() {
+    tink_aead::init();
+    let mut registry = tink::registry::Registry::new();
+    assert!(registry.type_urls().is_empty());
+    assert!(
+        matches!(registry.get_key_manager(tink_testutil::AES_GCM_TYPE_URL), Err(e) if e.kind() == tink::ErrorKind::Unsupported),
+        "expect an Unsupported error for an empty registry"
+    );
+
+    // A type URL that is registered globally can be registered in a separate instance.
+    registry
+        .register_key_manager(Arc::new(tink_testutil::DummyAeadKeyManager::default()))
+        .unwrap();
+    assert_eq!(registry.type_urls(), vec![tink_testutil::AES_GCM_TYPE_URL]);
+    assert!(
+        registry
+            .register_key_manager(Arc::new(tink_testutil::DummyAeadKeyManager::default()))
+            .is_err(),
+        "Shouldn't allow double registration",
+    );
+    let key = tink_testutil::new_aes_gcm_key(0, 16);
+    let mut serialized_key = vec![];
+    key.encode(&mut serialized_key).unwrap();
+    let p = registry
+        .primitive(tink_testutil::AES_GCM_TYPE_URL, &serialized_key)
+        .unwrap();
+    match p {
+        tink::Primitive::Aead(a) => assert!(a.encrypt(b"data", b"").is_err()),
+        _ => panic!("Primitive not an Aead"),
+    }
+
+    // The global registry is unaffected.
+    let p = tink::registry::primitive(tink_testutil::AES_GCM_TYPE_URL, &serialized_key).unwrap();
+    match p {
+        tink::Primitive::Aead(a) => assert!(a.encrypt(b"data", b"").is_ok()),
+        _ => panic!("Primitive not an Aead"),
+    }
+
+    // A copy of the global registry can be modified independently.
+    let mut copy = tink::registry::global_registry();
+    assert!(copy
+        .get_key_manager(tink_testutil::AES_GCM_TYPE_URL)
+        .is_ok());
+    copy.register_kms_client(tink_testutil::DummyKmsClient {});
+    copy.get_kms_client("dummy").unwrap();
+    assert!(registry.get_kms_client("dummy").is_err());
+}
+
+#[test]
+fn test_registry_fips_only() {
+    tink_aead::init();
+    tink_mac::init();
+    let registry = tink::registry::global_registry().fips_only();
+    assert!(registry.is_fips_only());
+    assert!(registry
+        .get_key_manager(tink_testutil::AES_GCM_TYPE_URL)
+        .is_ok());
+    assert!(registry
+        .get_key_manager(tink_testutil::HMAC_TYPE_URL)
+        .is_ok());
+    assert!(
+        matches!(registry.get_key_manager(tink_testutil::CHA_CHA20_POLY1305_TYPE_URL), Err(e) if e.kind() == tink::ErrorKind::Unsupported),
+        "ChaCha20Poly1305 should not be available in FIPS-only registry"
+    );
+    assert!(registry
+        .new_key_data(&tink_aead::aes128_gcm_key_template())
+        .is_ok());
+
+    // Keys with approved algorithms are allowed.
+    let key_data = tink_testutil::new_hmac_key_data(tink::proto::HashType::Sha256, 16);
+    assert!(registry.primitive_from_key_data(&key_data).is_ok());
+
+    // Keys with non-approved parameters are rejected.
+    let key_data = tink_testutil::new_hmac_key_data(tink::proto::HashType::Sha1, 16);
+    let result = registry.primitive_from_key_data(&key_data);
+    assert!(
+        matches!(&result, Err(e) if e.kind() == tink::ErrorKind::Unsupported && e.to_string().contains("not FIPS 140-approved")),
+        "HMAC-SHA1 should be rejected in FIPS-only registry"
+    );
+    // ...but still allowed by the unrestricted registry.
+    assert!(tink::registry::primitive_from_key_data(&key_data).is_ok());
+}
+
+fn key_type_entry(
+    primitive_name: &str,
+    type_url: &str,
+    key_manager_version: u32,
+    new_key_allowed: bool,
+) -> tink::proto::KeyTypeEntry {
+    tink::proto::KeyTypeEntry {
+        primitive_name: primitive_name.to_string(),
+        type_url: type_url.to_string(),
+        key_manager_version,
+        new_key_allowed,
+        catalogue_name: "TinkAead".to_string(),
+    }
+}
+
+#[test]
+fn test_registry_with_config() {
+    tink_aead::init();
+    tink_mac::init();
+    let config = tink::proto::RegistryConfig {
+        config_name: "test".to_string(),
+        entry: vec![
+            key_type_entry("AEAD", tink_testutil::AES_GCM_TYPE_URL, 0, true),
+            key_type_entry("Mac", tink_testutil::HMAC_TYPE_URL, 0, false),
+        ],
+    };
+    let mut serialized = vec![];
+    config.encode(&mut serialized).unwrap();
+    let config = tink::registry::config_from_binary(&serialized).unwrap();
+    let registry = tink::registry::global_registry()
+        .with_config(&config)
+        .unwrap();
+    assert_eq!(
+        registry.type_urls(),
+        vec![
+            tink_testutil::AES_GCM_TYPE_URL,
+            tink_testutil::HMAC_TYPE_URL
+        ]
+    );
+    assert!(registry
+        .new_key_data(&tink_aead::aes128_gcm_key_template())
+        .is_ok());
+    assert!(matches!(
+        registry.new_key_data(&tink_mac::hmac_sha256_tag128_key_template()),
+        Err(e) if e.kind() == tink::ErrorKind::Unsupported
+    ));
+    let key_data = tink_testutil::new_hmac_key_data(tink::proto::HashType::Sha256, 16);
+    assert!(registry.primitive_from_key_data(&key_data).is_ok());
+    assert!(matches!(
+        registry.new_key_data(&tink_aead::cha_cha20_poly1305_key_template()),
+        Err(e) if e.kind() == tink::ErrorKind::Unsupported
+    ));
+
+    let invalid_configs = vec![
+        // wrong primitive
+        key_type_entry("Mac", tink_testutil::AES_GCM_TYPE_URL, 0, true),
+        // unregistered key type
+        key_type_entry("Aead", "some type url", 0, true),
+        // key manager version too old
+        key_type_entry("Aead", tink_testutil::AES_GCM_TYPE_URL, 1, true),
+        // unknown catalogue
+        tink::proto::KeyTypeEntry {
+            catalogue_name: "Custom".to_string(),
+            ..key_type_entry("Aead", tink_testutil::AES_GCM_TYPE_URL, 0, true)
+        },
+    ];
+    for entry in invalid_configs {
+        let config = tink::proto::RegistryConfig {
+            config_name: "invalid".to_string(),
+            entry: vec![entry],
+        };
+        assert!(tink::registry::global_registry()
+            .with_config(&config)
+            .is_err());
+    }
+    assert!(tink::registry::config_from_binary(&[0xff, 0xff]).is_err());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_registry_with_json_config() {
+    tink_aead::init();
+    let json = format!(
+        r#"{{
+            "configName": "json",
+            "entry": [
+                {{
+                    "primitiveName": "Aead",
+                    "typeUrl": "{}",
+                    "keyManagerVersion": 0,
+                    "newKeyAllowed": true
+                }}
+            ]
+        }}"#,
+        tink_testutil::AES_GCM_TYPE_URL
+    );
+    let config = tink::registry::config_from_json(json.as_bytes()).unwrap();
+    assert_eq!(config.config_name, "json");
+    let registry = tink::registry::global_registry()
+        .with_config(&config)
+        .unwrap();
+    assert_eq!(registry.type_urls(), vec![tink_testutil::AES_GCM_TYPE_URL]);
+    assert!(tink::registry::config_from_json(b"{\"entry\": 1}").is_err());
+}
+
 #[test]
 fn test_new_key_data() {
     tink_mac::init();
@@ -128,14 +320,14 @@ fn test_primitive_from_key_data() {
     // unregistered url
     key_data.type_url = "some url".to_string();
     assert!(
-        tink::registry::primitive_from_key_data(&key_data).is_err(),
-        "expect an error when type_url has not been registered"
+        matches!(tink::registry::primitive_from_key_data(&key_data), Err(e) if e.kind() == tink::ErrorKind::Unsupported),
+        "expect an Unsupported error when type_url has not been registered"
     );
     // unmatched url
     key_data.type_url = tink_testutil::AES_GCM_TYPE_URL.to_string();
     assert!(
-        tink::registry::primitive_from_key_data(&key_data).is_err(),
-        "expect an error when type_url doesn't match key"
+        matches!(tink::registry::primitive_from_key_data(&key_data), Err(e) if e.kind() == tink::ErrorKind::InvalidKey),
+        "expect an InvalidKey error when type_url doesn't match key"
     );
 }
 
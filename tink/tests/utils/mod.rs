@@ -0,0 +1,52 @@
+// Copyright 2020 The Tink-Rust Authors
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+////////////////////////////////////////////////////////////////////////////////
+
+use tink::{utils::wrap_err, ErrorKind, TinkError};
+
+#[test]
+fn test_error_kind() {
+    let err = TinkError::new("uncategorized");
+    assert_eq!(err.kind(), ErrorKind::Other);
+    let err: TinkError = "uncategorized".into();
+    assert_eq!(err.kind(), ErrorKind::Other);
+
+    let err = TinkError::new_with_kind(ErrorKind::KeyNotFound, "no key");
+    assert_eq!(err.kind(), ErrorKind::KeyNotFound);
+    assert_eq!(format!("{}", err), "no key");
+
+    // `or_kind` only replaces an uncategorized kind.
+    let err = err.or_kind(ErrorKind::InvalidKey);
+    assert_eq!(err.kind(), ErrorKind::KeyNotFound);
+    let err = TinkError::new("uncategorized").or_kind(ErrorKind::InvalidKey);
+    assert_eq!(err.kind(), ErrorKind::InvalidKey);
+    let err = err.with_kind(ErrorKind::Authentication);
+    assert_eq!(err.kind(), ErrorKind::Authentication);
+}
+
+#[test]
+fn test_wrap_err_keeps_kind() {
+    let err = wrap_err(
+        "outer",
+        wrap_err(
+            "inner",
+            TinkError::new_with_kind(ErrorKind::Unavailable, "timed out"),
+        ),
+    );
+    assert_eq!(err.kind(), ErrorKind::Unavailable);
+
+    let err = wrap_err("outer", std::io::Error::from(std::io::ErrorKind::Other));
+    assert_eq!(err.kind(), ErrorKind::Other);
+}